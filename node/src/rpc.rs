@@ -7,7 +7,7 @@
 
 use std::sync::Arc;
 
-use subsocial_runtime::{opaque::Block, AccountId, Balance, Index};
+use subsocial_runtime::{opaque::Block, AccountId, Balance, BlockNumber, Index};
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::{Error as BlockChainError, HeaderMetadata, HeaderBackend};
 use sp_block_builder::BlockBuilder;
@@ -34,11 +34,19 @@ pub fn create_full<C, P>(
     C: Send + Sync + 'static,
     C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Index>,
     C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
+    C::Api: donations_rpc::DonationsRuntimeApi<Block, AccountId, Balance>,
+    C::Api: posts_rpc::PostsRuntimeApi<Block, AccountId, BlockNumber>,
+    C::Api: spaces_rpc::SpacesRuntimeApi<Block, AccountId, BlockNumber>,
+    C::Api: roles_rpc::RolesRuntimeApi<Block, AccountId>,
     C::Api: BlockBuilder<Block>,
     P: TransactionPool + 'static,
 {
     use substrate_frame_rpc_system::{FullSystem, SystemApi};
     use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApi};
+    use posts_rpc::{Posts, PostsApi};
+    use spaces_rpc::{Spaces, SpacesApi};
+    use roles_rpc::{Roles, RolesApi};
+    use donations_rpc::{Donations, DonationsApi};
 
     let mut io = jsonrpc_core::IoHandler::default();
     let FullDeps {
@@ -55,6 +63,22 @@ pub fn create_full<C, P>(
         TransactionPaymentApi::to_delegate(TransactionPayment::new(client.clone()))
     );
 
+    io.extend_with(
+        SpacesApi::to_delegate(Spaces::new(client.clone()))
+    );
+
+    io.extend_with(
+        PostsApi::to_delegate(Posts::new(client.clone()))
+    );
+
+    io.extend_with(
+        RolesApi::to_delegate(Roles::new(client.clone()))
+    );
+
+    io.extend_with(
+        DonationsApi::to_delegate(Donations::new(client.clone()))
+    );
+
     // Extend this RPC with a custom API by using the following syntax.
     // `YourRpcStruct` should have a reference to a client, which is needed
     // to call into the runtime.
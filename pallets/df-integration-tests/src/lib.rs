@@ -2,35 +2,51 @@
 mod tests {
     use frame_support::{
         assert_ok, assert_noop,
-        impl_outer_origin, parameter_types,
-        weights::Weight,
+        impl_outer_origin, impl_outer_dispatch, parameter_types,
+        weights::{Weight, IdentityFee},
         dispatch::DispatchResult,
-        storage::StorageMap,
+        storage::{StorageMap, migration::put_storage_value},
+        traits::{OnInitialize, OnFinalize, OnRuntimeUpgrade},
+        StorageHasher, Twox64Concat,
     };
+    use codec::Encode;
     use sp_core::H256;
     use sp_io::TestExternalities;
     use sp_runtime::{
         traits::{BlakeTwo256, IdentityLookup, Zero},
         testing::Header,
+        FixedPointNumber,
         Perbill,
+        Perquintill,
         Storage,
     };
-    use frame_system::{self as system};
+    use frame_system::{self as system, EnsureRoot};
 
+    use pallet_donations::{DonationRecipient, DonationSettingsUpdate};
     use pallet_permissions::{
         SpacePermission,
         SpacePermission as SP,
         SpacePermissions,
     };
-    use pallet_posts::{PostId, Post, PostUpdate, PostExtension, Comment, Error as PostsError};
+    use pallet_balances::Error as BalancesError;
+    use pallet_posts::{
+        PostId, Post, PostUpdate, PostExtension, Comment,
+        Call as PostsCall, Error as PostsError, rpc::FlatPost, SubscriptionPlanIdsByPostId,
+    };
     use pallet_profiles::{ProfileUpdate, Error as ProfilesError};
     use pallet_profile_follows::Error as ProfileFollowsError;
-    use pallet_reactions::{ReactionId, ReactionKind, PostReactionScores, Error as ReactionsError};
+    use pallet_reactions::{
+        ReactionId, ReactionKind, PostReactionScores, PostReactionIdByAccount,
+        Call as ReactionsCall, Error as ReactionsError,
+    };
     use pallet_scores::ScoringAction;
-    use pallet_spaces::{SpaceById, SpaceUpdate, Error as SpacesError};
-    use pallet_space_follows::Error as SpaceFollowsError;
+    use pallet_session_keys::{CallSpaceResolver, SessionKeyPermissions, Error as SessionKeysError};
+    use pallet_spaces::{SpaceById, SubspaceIdsByParentId, SpaceUpdate, Error as SpacesError};
+    use pallet_space_follows::{Call as SpaceFollowsCall, Error as SpaceFollowsError};
+    use pallet_transaction_payment::{Multiplier, TargetedFeeAdjustment};
     use pallet_space_ownership::Error as SpaceOwnershipError;
     use pallet_moderation::{EntityId, EntityStatus, ReportId};
+    use df_traits::subscriptions::{SubscriptionPlanId, SubscriptionPlansProvider};
     use pallet_utils::{
         mock_functions::*,
         Error as UtilsError, Module as Utils,
@@ -41,6 +57,17 @@ mod tests {
         pub enum Origin for TestRuntime {}
     }
 
+    impl_outer_dispatch! {
+        pub enum Call for TestRuntime where origin: Origin {
+            frame_system::System,
+            pallet_balances::Balances,
+            pallet_posts::Posts,
+            pallet_profile_follows::ProfileFollows,
+            pallet_reactions::Reactions,
+            pallet_space_follows::SpaceFollows,
+        }
+    }
+
     #[derive(Clone, Eq, PartialEq)]
     pub struct TestRuntime;
 
@@ -54,7 +81,7 @@ mod tests {
     impl system::Trait for TestRuntime {
         type BaseCallFilter = ();
         type Origin = Origin;
-        type Call = ();
+        type Call = Call;
         type Index = u64;
         type BlockNumber = u64;
         type Hash = H256;
@@ -132,6 +159,21 @@ mod tests {
         type PostScores = Scores;
         type AfterPostUpdated = PostHistory;
         type IsPostBlocked = Moderation;
+        type SubscriptionPlans = MockSubscriptionPlans;
+        type PostCleaner = Reactions;
+    }
+
+    /// Only `PLAN1` exists: it is active in `SPACE1` and `ACCOUNT3` is its only subscriber.
+    pub struct MockSubscriptionPlans;
+
+    impl SubscriptionPlansProvider<AccountId> for MockSubscriptionPlans {
+        fn is_active_plan_in_space(plan_id: SubscriptionPlanId, space_id: SpaceId) -> bool {
+            plan_id == PLAN1 && space_id == SPACE1
+        }
+
+        fn is_active_subscriber(account: &AccountId, plan_id: SubscriptionPlanId) -> bool {
+            plan_id == PLAN1 && *account == ACCOUNT3
+        }
     }
 
     parameter_types! {}
@@ -215,15 +257,19 @@ mod tests {
         type BeforeSpaceUnfollowed = Scores;
     }
 
-    parameter_types! {}
+    parameter_types! {
+        pub const PendingTransferLifetime: u64 = 10;
+    }
 
     impl pallet_space_ownership::Trait for TestRuntime {
         type Event = ();
+        type PendingTransferLifetime = PendingTransferLifetime;
     }
 
     const HANDLE_DEPOSIT: u64 = 5;
     parameter_types! {
         pub const HandleDeposit: u64 = HANDLE_DEPOSIT;
+        pub const MaxItemsToCleanUpPerBlock: u16 = 5;
     }
 
     impl pallet_spaces::Trait for TestRuntime {
@@ -233,9 +279,12 @@ mod tests {
         type SpaceFollows = SpaceFollows;
         type BeforeSpaceCreated = SpaceFollows;
         type AfterSpaceUpdated = SpaceHistory;
+        type AfterSpaceOwnerChanged = SpaceOwnership;
         type IsAccountBlocked = Moderation;
         type IsContentBlocked = Moderation;
         type HandleDeposit = HandleDeposit;
+        type SpaceCleaner = (Posts, Roles, SpaceFollows, SpaceOwnership, Moderation, Donations);
+        type MaxItemsToCleanUpPerBlock = MaxItemsToCleanUpPerBlock;
     }
 
     parameter_types! {}
@@ -244,16 +293,69 @@ mod tests {
 
     parameter_types! {
         pub const DefaultAutoblockThreshold: u16 = 20;
+        pub const AppealPeriod: BlockNumber = 10;
     }
 
     impl pallet_moderation::Trait for TestRuntime {
         type Event = ();
         type DefaultAutoblockThreshold = DefaultAutoblockThreshold;
+        type GlobalModeratorOrigin = EnsureRoot<AccountId>;
+        type AppealPeriod = AppealPeriod;
+    }
+
+    impl pallet_donations::Trait for TestRuntime {
+        type Event = ();
+    }
+
+    parameter_types! {
+        pub const TransactionByteFee: u64 = 1;
+        pub const TargetBlockFullness: Perquintill = Perquintill::from_percent(25);
+        pub AdjustmentVariable: Multiplier = Multiplier::saturating_from_rational(1, 100_000);
+        pub MinimumMultiplier: Multiplier = Multiplier::saturating_from_rational(1, 1_000_000_000u128);
+    }
+
+    impl pallet_transaction_payment::Trait for TestRuntime {
+        type Currency = Balances;
+        type OnTransactionPayment = ();
+        type TransactionByteFee = TransactionByteFee;
+        type WeightToFee = IdentityFee<u64>;
+        type FeeMultiplierUpdate =
+            TargetedFeeAdjustment<Self, TargetBlockFullness, AdjustmentVariable, MinimumMultiplier>;
+    }
+
+    parameter_types! {
+        pub const MaxSessionKeysPerAccount: u16 = 10;
+        pub const BaseSessionKeyBond: u64 = 10;
+    }
+
+    pub struct SessionKeysCallSpaceResolver;
+    impl CallSpaceResolver<Call> for SessionKeysCallSpaceResolver {
+        fn call_space(call: &Call) -> Option<SpaceId> {
+            let space_of_post = |post_id: &PostId| Posts::post_by_id(post_id)
+                .and_then(|post| post.try_get_space_id());
+
+            match call {
+                Call::SpaceFollows(SpaceFollowsCall::follow_space(space_id)) => Some(*space_id),
+                Call::Posts(PostsCall::create_post(space_id_opt, _, _)) => *space_id_opt,
+                Call::Reactions(ReactionsCall::create_post_reaction(post_id, _)) => space_of_post(post_id),
+                _ => None,
+            }
+        }
+    }
+
+    impl pallet_session_keys::Trait for TestRuntime {
+        type Event = ();
+        type Call = Call;
+        type MaxSessionKeysPerAccount = MaxSessionKeysPerAccount;
+        type BaseFilter = ();
+        type BaseSessionKeyBond = BaseSessionKeyBond;
+        type CallSpaceResolver = SessionKeysCallSpaceResolver;
     }
 
     type System = system::Module<TestRuntime>;
     type Balances = pallet_balances::Module<TestRuntime>;
 
+    type Permissions = pallet_permissions::Module<TestRuntime>;
     type Posts = pallet_posts::Module<TestRuntime>;
     type PostHistory = pallet_post_history::Module<TestRuntime>;
     type ProfileFollows = pallet_profile_follows::Module<TestRuntime>;
@@ -267,6 +369,8 @@ mod tests {
     type SpaceOwnership = pallet_space_ownership::Module<TestRuntime>;
     type Spaces = pallet_spaces::Module<TestRuntime>;
     type Moderation = pallet_moderation::Module<TestRuntime>;
+    type Donations = pallet_donations::Module<TestRuntime>;
+    type SessionKeys = pallet_session_keys::Module<TestRuntime>;
 
     pub type AccountId = u64;
     type BlockNumber = u64;
@@ -416,6 +520,8 @@ mod tests {
     const REACTION1: ReactionId = 1;
     const REACTION2: ReactionId = 2;
 
+    const SESSION_KEY1: AccountId = 21;
+
     /// Lowercase a handle and then try to find a space id by it.
     fn find_space_id_by_handle(handle: Vec<u8>) -> Option<SpaceId> {
         let lc_handle = Utils::<TestRuntime>::lowercase_handle(handle);
@@ -609,6 +715,17 @@ mod tests {
         )
     }
 
+    fn _delete_space(origin: Option<Origin>, space_id: Option<SpaceId>) -> DispatchResult {
+        Spaces::delete_space(
+            origin.unwrap_or_else(|| Origin::signed(ACCOUNT1)),
+            space_id.unwrap_or(SPACE1),
+        )
+    }
+
+    fn _clean_up_deleted_spaces() {
+        Spaces::on_initialize(System::block_number());
+    }
+
     fn _default_follow_space() -> DispatchResult {
         _follow_space(None, None)
     }
@@ -717,6 +834,31 @@ mod tests {
         )
     }
 
+    fn _delete_post(origin: Option<Origin>, post_id: Option<PostId>) -> DispatchResult {
+        Posts::delete_post(
+            origin.unwrap_or_else(|| Origin::signed(ACCOUNT1)),
+            post_id.unwrap_or(POST1),
+        )
+    }
+
+    const PLAN1: SubscriptionPlanId = 1;
+
+    fn _limit_default_post_to_subscribers() -> DispatchResult {
+        _update_post_subscription_plans(None, None, vec![PLAN1])
+    }
+
+    fn _update_post_subscription_plans(
+        origin: Option<Origin>,
+        post_id: Option<PostId>,
+        plan_ids: Vec<SubscriptionPlanId>,
+    ) -> DispatchResult {
+        Posts::update_post_subscription_plans(
+            origin.unwrap_or_else(|| Origin::signed(ACCOUNT1)),
+            post_id.unwrap_or(POST1),
+            plan_ids,
+        )
+    }
+
     fn _create_default_post_reaction() -> DispatchResult {
         _create_post_reaction(None, None, None)
     }
@@ -864,11 +1006,21 @@ mod tests {
         origin: Option<Origin>,
         space_id: Option<SpaceId>,
         transfer_to: Option<AccountId>,
+    ) -> DispatchResult {
+        _transfer_space_ownership_for_price(origin, space_id, transfer_to, None)
+    }
+
+    fn _transfer_space_ownership_for_price(
+        origin: Option<Origin>,
+        space_id: Option<SpaceId>,
+        transfer_to: Option<AccountId>,
+        price: Option<u64>,
     ) -> DispatchResult {
         SpaceOwnership::transfer_space_ownership(
             origin.unwrap_or_else(|| Origin::signed(ACCOUNT1)),
             space_id.unwrap_or(SPACE1),
             transfer_to.unwrap_or(ACCOUNT2),
+            price,
         )
     }
 
@@ -877,9 +1029,18 @@ mod tests {
     }
 
     fn _accept_pending_ownership(origin: Option<Origin>, space_id: Option<SpaceId>) -> DispatchResult {
+        _accept_pending_ownership_for_price(origin, space_id, None)
+    }
+
+    fn _accept_pending_ownership_for_price(
+        origin: Option<Origin>,
+        space_id: Option<SpaceId>,
+        max_price: Option<u64>,
+    ) -> DispatchResult {
         SpaceOwnership::accept_pending_ownership(
             origin.unwrap_or_else(|| Origin::signed(ACCOUNT2)),
             space_id.unwrap_or(SPACE1),
+            max_price,
         )
     }
 
@@ -898,6 +1059,17 @@ mod tests {
         )
     }
 
+    fn _cancel_default_pending_ownership() -> DispatchResult {
+        _cancel_pending_ownership(None, None)
+    }
+
+    fn _cancel_pending_ownership(origin: Option<Origin>, space_id: Option<SpaceId>) -> DispatchResult {
+        SpaceOwnership::cancel_pending_ownership(
+            origin.unwrap_or_else(|| Origin::signed(ACCOUNT1)),
+            space_id.unwrap_or(SPACE1),
+        )
+    }
+
     /* ---------------------------------------------------------------------------------------------- */
 
     // TODO: fix copy-paste from pallet_roles
@@ -1034,6 +1206,67 @@ mod tests {
         )
     }
 
+    /* ---------------------------------------------------------------------------------------------- */
+    // Donations pallet mocks
+
+    fn _set_space_share_of_donations(space_share: Perbill) -> DispatchResult {
+        Donations::update_settings(
+            Origin::signed(ACCOUNT1),
+            DonationRecipient::Space(SPACE1),
+            DonationSettingsUpdate {
+                donations_allowed: None,
+                min_amount: None,
+                max_amount: None,
+                space_share: Some(space_share),
+            }
+        )
+    }
+
+    fn _donate(
+        origin: Option<Origin>,
+        recipient: Option<DonationRecipient<AccountId>>,
+        amount: u64,
+    ) -> DispatchResult {
+        _donate_with_comment(origin, recipient, amount, None)
+    }
+
+    fn _donate_with_comment(
+        origin: Option<Origin>,
+        recipient: Option<DonationRecipient<AccountId>>,
+        amount: u64,
+        comment_content: Option<Content>,
+    ) -> DispatchResult {
+        Donations::donate(
+            origin.unwrap_or_else(|| Origin::signed(ACCOUNT3)),
+            recipient.unwrap_or(DonationRecipient::Post(POST2)),
+            amount,
+            comment_content,
+        )
+    }
+
+    /* ---------------------------------------------------------------------------------------------- */
+    // Session keys pallet mocks
+
+    /// Balance that is enough to pay fees for the calls proxied through a session key.
+    const SESSION_KEY_OWNER_BALANCE: u64 = 1_000_000_000;
+
+    fn _add_session_key(owner: AccountId, permissions: SessionKeyPermissions) -> DispatchResult {
+        Balances::make_free_balance_be(&owner, SESSION_KEY_OWNER_BALANCE);
+        SessionKeys::add_key(
+            Origin::signed(owner),
+            SESSION_KEY1,
+            100,
+            None,
+            permissions
+        )
+    }
+
+    fn _proxy_by_session_key(call: Call) -> DispatchResult {
+        SessionKeys::proxy(Origin::signed(SESSION_KEY1), Box::new(call))
+            .map(|_| ())
+            .map_err(|err| err.error)
+    }
+
     /*------------------------------------------------------------------------------------------------*/
     // Moderation tests
 
@@ -1048,6 +1281,17 @@ mod tests {
         );
     }
 
+    fn block_account_2_in_space_1() {
+        assert_ok!(
+            _update_entity_status(
+                None,
+                Some(EntityId::Account(ACCOUNT2)),
+                Some(SPACE1),
+                Some(Some(EntityStatus::Blocked))
+            )
+        );
+    }
+
     fn block_content_in_space_1() {
         assert_ok!(
             _update_entity_status(
@@ -1194,11 +1438,10 @@ mod tests {
         });
     }
 
-    // FIXME: uncomment when `update_post` will be able to move post from one space to another
-    /*
     #[test]
-    fn update_post_should_fail_when_post_is_blocked() {
+    fn move_post_should_fail_when_post_is_blocked() {
         ExtBuilder::build_with_post().execute_with(|| {
+            // Blocking a post also moves it out of the space, once the block cannot be appealed
             assert_ok!(
                 _update_entity_status(
                     None,
@@ -1207,172 +1450,600 @@ mod tests {
                     Some(Some(EntityStatus::Blocked))
                 )
             );
+            let appealable_until = Moderation::appealable_until(EntityId::Post(POST1), SPACE1).unwrap();
+            Moderation::on_finalize(appealable_until);
+            assert!(Posts::post_by_id(POST1).unwrap().space_id.is_none());
+
             assert_noop!(
-                _update_post(
-                    None, // From ACCOUNT1 (has default permission to UpdateOwnPosts)
-                    Some(POST1),
-                    Some(
-                        post_update(
-                            Some(SPACE1),
-                            None,
-                            None
-                        )
-                    )
-                ), UtilsError::<TestRuntime>::PostIsBlocked
+                _move_post(None, None, Some(Some(SPACE1))),
+                UtilsError::<TestRuntime>::PostIsBlocked
             );
         });
     }
-    */
 
-    /*---------------------------------------------------------------------------------------------------*/
-    // Space tests
-    
     #[test]
-    fn create_space_should_work() {
-        ExtBuilder::build().execute_with(|| {
-            assert_ok!(_create_default_space()); // SpaceId 1
-
-            // Check storages
-            assert_eq!(Spaces::space_ids_by_owner(ACCOUNT1), vec![SPACE1]);
-            assert_eq!(find_space_id_by_handle(space_handle()), Some(SPACE1));
-            assert_eq!(Spaces::next_space_id(), SPACE2);
-
-            // Check whether data stored correctly
-            let space = Spaces::space_by_id(SPACE1).unwrap();
-
-            assert_eq!(space.created.account, ACCOUNT1);
-            assert!(space.updated.is_none());
-            assert_eq!(space.hidden, false);
-
-            assert_eq!(space.owner, ACCOUNT1);
-            assert_eq!(space.handle, Some(space_handle()));
-            assert_eq!(space.content, space_content_ipfs());
-
-            assert_eq!(space.posts_count, 0);
-            assert_eq!(space.followers_count, 1);
-            assert!(SpaceHistory::edit_history(space.id).is_empty());
-            assert_eq!(space.score, 0);
-
-            // Check that the handle deposit has been reserved:
-            let reserved_balance = Balances::reserved_balance(ACCOUNT1);
-            assert_eq!(reserved_balance, HANDLE_DEPOSIT);
+    fn create_comment_should_fail_when_account_is_blocked() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            block_account_2_in_space_1();
+            assert_noop!(
+                _create_comment(Some(Origin::signed(ACCOUNT2)), None, None, None),
+                UtilsError::<TestRuntime>::AccountIsBlocked
+            );
         });
     }
 
     #[test]
-    fn create_space_should_store_handle_lowercase() {
-        ExtBuilder::build().execute_with(|| {
-            let new_handle: Vec<u8> = b"sPaCe_hAnDlE".to_vec();
-
-            assert_ok!(_create_space(None, Some(Some(new_handle.clone())), None, None)); // SpaceId 1
-
-            // Handle should be lowercase in storage and original in struct
-            let space = Spaces::space_by_id(SPACE1).unwrap();
-            assert_eq!(space.handle, Some(new_handle.clone()));
-            assert_eq!(find_space_id_by_handle(new_handle), Some(SPACE1));
+    fn create_comment_should_fail_when_account_is_blocked_globally() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            assert_ok!(Moderation::block_entity_globally(Origin::root(), EntityId::Account(ACCOUNT2)));
+            assert_noop!(
+                _create_comment(Some(Origin::signed(ACCOUNT2)), None, None, None),
+                UtilsError::<TestRuntime>::AccountIsBlocked
+            );
         });
     }
 
     #[test]
-    fn create_space_should_fail_when_too_short_handle_provided() {
-        ExtBuilder::build().execute_with(|| {
-            let short_handle: Vec<u8> = vec![65; (MinHandleLen::get() - 1) as usize];
-
-            // Try to catch an error creating a space with too short handle
-            assert_noop!(_create_space(
-                None,
-                Some(Some(short_handle)),
-                None,
-                None
-            ), UtilsError::<TestRuntime>::HandleIsTooShort);
+    fn create_post_reaction_should_fail_when_account_is_blocked() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            block_account_2_in_space_1();
+            assert_noop!(
+                _create_post_reaction(Some(Origin::signed(ACCOUNT2)), None, None),
+                UtilsError::<TestRuntime>::AccountIsBlocked
+            );
         });
     }
 
     #[test]
-    fn create_space_should_fail_when_too_long_handle_provided() {
-        ExtBuilder::build().execute_with(|| {
-            let long_handle: Vec<u8> = vec![65; (MaxHandleLen::get() + 1) as usize];
+    fn update_post_subscription_plans_should_work() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            assert_ok!(_limit_default_post_to_subscribers());
+            assert_eq!(Posts::subscription_plan_ids_by_post_id(POST1), vec![PLAN1]);
 
-            // Try to catch an error creating a space with too long handle
-            assert_noop!(_create_space(
-                None,
-                Some(Some(long_handle)),
-                None,
-                None
-            ), UtilsError::<TestRuntime>::HandleIsTooLong);
+            // An empty list makes the post available to everyone again.
+            assert_ok!(_update_post_subscription_plans(None, None, vec![]));
+            assert!(Posts::subscription_plan_ids_by_post_id(POST1).is_empty());
         });
     }
 
     #[test]
-    fn create_space_should_fail_when_not_unique_handle_provided() {
-        ExtBuilder::build().execute_with(|| {
-            assert_ok!(_create_default_space());
-            // SpaceId 1
-            // Try to catch an error creating a space with not unique handle
-            assert_noop!(_create_default_space(), SpacesError::<TestRuntime>::SpaceHandleIsNotUnique);
+    fn update_post_subscription_plans_should_fail_when_plan_not_found_in_space() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            assert_noop!(
+                _update_post_subscription_plans(None, None, vec![PLAN1 + 1]),
+                PostsError::<TestRuntime>::SubscriptionPlanNotFoundInSpace
+            );
         });
     }
 
     #[test]
-    fn create_space_should_fail_when_handle_contains_at_char() {
-        ExtBuilder::build().execute_with(|| {
-            let invalid_handle: Vec<u8> = b"@space_handle".to_vec();
-
-            assert_noop!(_create_space(
-                None,
-                Some(Some(invalid_handle)),
-                None,
-                None
-            ), UtilsError::<TestRuntime>::HandleContainsInvalidChars);
+    fn update_post_subscription_plans_should_fail_when_post_is_comment() {
+        ExtBuilder::build_with_comment().execute_with(|| {
+            assert_noop!(
+                _update_post_subscription_plans(None, Some(POST2), vec![PLAN1]),
+                PostsError::<TestRuntime>::CannotLimitCommentToSubscribers
+            );
         });
     }
 
     #[test]
-    fn create_space_should_fail_when_handle_contains_minus_char() {
-        ExtBuilder::build().execute_with(|| {
-            let invalid_handle: Vec<u8> = b"space-handle".to_vec();
-
-            assert_noop!(_create_space(
-                None,
-                Some(Some(invalid_handle)),
-                None,
-                None
-            ), UtilsError::<TestRuntime>::HandleContainsInvalidChars);
+    fn update_post_subscription_plans_should_fail_when_account_has_no_permission() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            assert_noop!(
+                _update_post_subscription_plans(Some(Origin::signed(ACCOUNT2)), None, vec![PLAN1]),
+                PostsError::<TestRuntime>::NoPermissionToUpdateAnyPost
+            );
         });
     }
 
     #[test]
-    fn create_space_should_fail_when_handle_contains_space_char() {
-        ExtBuilder::build().execute_with(|| {
-            let invalid_handle: Vec<u8> = b"space handle".to_vec();
-
-            assert_noop!(_create_space(
-                None,
-                Some(Some(invalid_handle)),
-                None,
-                None
-            ), UtilsError::<TestRuntime>::HandleContainsInvalidChars);
+    fn create_comment_should_work_when_account_is_subscriber() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            assert_ok!(_limit_default_post_to_subscribers());
+            assert_ok!(_create_comment(Some(Origin::signed(ACCOUNT3)), None, None, None));
+            assert_ok!(_create_default_comment()); // Post owner does not need a subscription
         });
     }
 
     #[test]
-    fn create_space_should_fail_when_handle_contains_unicode() {
-        ExtBuilder::build().execute_with(|| {
-            let invalid_handle: Vec<u8> = String::from("блог_хендл").into_bytes().to_vec();
+    fn create_comment_should_fail_when_account_is_not_subscriber() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            assert_ok!(_limit_default_post_to_subscribers());
+            assert_noop!(
+                _create_comment(Some(Origin::signed(ACCOUNT2)), None, None, None),
+                PostsError::<TestRuntime>::NotASubscriber
+            );
+        });
+    }
 
-            assert_noop!(_create_space(
-                None,
-                Some(Some(invalid_handle)),
-                None,
-                None
-            ), UtilsError::<TestRuntime>::HandleContainsInvalidChars);
+    #[test]
+    fn create_post_reaction_should_fail_when_account_is_not_subscriber() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            assert_ok!(_limit_default_post_to_subscribers());
+            assert_noop!(
+                _create_post_reaction(Some(Origin::signed(ACCOUNT2)), None, None),
+                PostsError::<TestRuntime>::NotASubscriber
+            );
+            assert_ok!(_create_post_reaction(Some(Origin::signed(ACCOUNT3)), None, None));
         });
     }
 
     #[test]
-    fn create_space_should_fail_when_ipfs_cid_is_invalid() {
-        ExtBuilder::build().execute_with(|| {
-            // Try to catch an error creating a space with invalid content
+    fn update_post_reaction_should_fail_when_account_is_not_subscriber() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            assert_ok!(_create_post_reaction(Some(Origin::signed(ACCOUNT2)), None, None)); // ReactionId 1
+            assert_ok!(_limit_default_post_to_subscribers());
+
+            assert_noop!(
+                _update_post_reaction(Some(Origin::signed(ACCOUNT2)), None, REACTION1, Some(reaction_downvote())),
+                PostsError::<TestRuntime>::NotASubscriber
+            );
+        });
+    }
+
+    #[test]
+    fn share_post_should_fail_when_account_is_not_subscriber() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            assert_ok!(_create_space(
+                Some(Origin::signed(ACCOUNT2)),
+                Some(Some(space_handle_2())),
+                None,
+                None
+            )); // SpaceId 2 by ACCOUNT2
+
+            assert_ok!(_limit_default_post_to_subscribers());
+            assert_noop!(
+                _create_post(
+                    Some(Origin::signed(ACCOUNT2)),
+                    Some(Some(SPACE2)),
+                    Some(extension_shared_post(POST1)),
+                    None
+                ), PostsError::<TestRuntime>::NotASubscriber
+            );
+        });
+    }
+
+    #[test]
+    fn create_comment_should_work_when_post_plans_are_not_active() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            // This plan is not active in the space of the post, e.g. it was deactivated.
+            SubscriptionPlanIdsByPostId::insert(POST1, vec![PLAN1 + 1]);
+
+            assert_ok!(_create_comment(Some(Origin::signed(ACCOUNT2)), None, None, None));
+        });
+    }
+
+    #[test]
+    fn share_post_should_fail_when_account_is_blocked_in_original_space() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            assert_ok!(_create_space(
+                Some(Origin::signed(ACCOUNT2)),
+                Some(Some(space_handle_2())),
+                None,
+                None
+            )); // SpaceId 2 by ACCOUNT2
+
+            block_account_2_in_space_1();
+            assert_noop!(
+                _create_post(
+                    Some(Origin::signed(ACCOUNT2)),
+                    Some(Some(SPACE2)),
+                    Some(extension_shared_post(POST1)),
+                    None
+                ), UtilsError::<TestRuntime>::AccountIsBlocked
+            );
+        });
+    }
+
+    #[test]
+    fn follow_space_should_fail_when_account_is_blocked() {
+        ExtBuilder::build_with_space().execute_with(|| {
+            block_account_2_in_space_1();
+            assert_noop!(_default_follow_space(), UtilsError::<TestRuntime>::AccountIsBlocked);
+        });
+    }
+
+    #[test]
+    fn block_account_should_unfollow_space() {
+        ExtBuilder::build_with_space().execute_with(|| {
+            assert_ok!(_default_follow_space()); // ACCOUNT2 follows SpaceId 1
+
+            block_account_2_in_space_1();
+
+            assert!(!SpaceFollows::space_followed_by_account((ACCOUNT2, SPACE1)));
+            assert_eq!(Spaces::space_by_id(SPACE1).unwrap().followers_count, 1);
+        });
+    }
+
+    /*---------------------------------------------------------------------------------------------------*/
+    // Donations tests
+
+    #[test]
+    fn donate_to_post_should_split_donation_between_post_owner_and_space() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            assert_ok!(_create_comment(Some(Origin::signed(ACCOUNT2)), None, None, None)); // PostId 2 by ACCOUNT2
+            assert_ok!(_set_space_share_of_donations(Perbill::from_percent(20)));
+
+            let space_owner_balance = Balances::free_balance(ACCOUNT1);
+            let post_owner_balance = Balances::free_balance(ACCOUNT2);
+
+            assert_ok!(_donate(None, None, 50));
+
+            assert_eq!(Balances::free_balance(ACCOUNT3), 100 - 50);
+            assert_eq!(Balances::free_balance(ACCOUNT2), post_owner_balance + 40);
+            assert_eq!(Balances::free_balance(ACCOUNT1), space_owner_balance + 10);
+
+            let donation = Donations::donation_by_id(1).unwrap();
+            assert_eq!(donation.amount, 50);
+            assert_eq!(donation.donation_wallet, ACCOUNT2);
+            assert_eq!(donation.space_share, Some((ACCOUNT1, 10)));
+        });
+    }
+
+    #[test]
+    fn donate_to_post_should_send_all_to_post_owner_by_default() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            assert_ok!(_create_comment(Some(Origin::signed(ACCOUNT2)), None, None, None)); // PostId 2 by ACCOUNT2
+
+            let space_owner_balance = Balances::free_balance(ACCOUNT1);
+            assert_ok!(_donate(None, None, 50));
+
+            assert_eq!(Balances::free_balance(ACCOUNT2), 100 + 50);
+            assert_eq!(Balances::free_balance(ACCOUNT1), space_owner_balance);
+            assert_eq!(Donations::donation_by_id(1).unwrap().space_share, None);
+        });
+    }
+
+    #[test]
+    fn donate_to_post_should_fail_and_make_no_transfers_when_space_share_cannot_be_paid() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            assert_ok!(_create_comment(Some(Origin::signed(ACCOUNT2)), None, None, None)); // PostId 2 by ACCOUNT2
+            assert_ok!(_set_space_share_of_donations(Perbill::from_percent(20)));
+
+            // The post owner's part can be paid, but paying the space share
+            // would leave the supporter's account below the existential deposit.
+            assert_noop!(_donate(None, None, 100), BalancesError::<TestRuntime>::KeepAlive);
+        });
+    }
+
+    #[test]
+    fn donate_should_update_donation_totals() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            assert_ok!(_create_comment(Some(Origin::signed(ACCOUNT2)), None, None, None)); // PostId 2 by ACCOUNT2
+
+            assert_ok!(_donate(None, None, 10));
+            assert_ok!(_donate(None, None, 15));
+            assert_ok!(_donate(None, Some(DonationRecipient::Account(ACCOUNT2)), 5));
+
+            let post_recipient = DonationRecipient::Post(POST2);
+            assert_eq!(Donations::total_donated_to_recipient(&post_recipient), 25);
+            assert_eq!(Donations::total_donated_by_supporter(ACCOUNT3), 30);
+            assert_eq!(Donations::total_donated_by_supporter_to_recipient(ACCOUNT3, &post_recipient), 25);
+            assert_eq!(Donations::supporters_by_recipient(&post_recipient), vec![ACCOUNT3]);
+        });
+    }
+
+    #[test]
+    fn get_top_supporters_should_return_biggest_supporters_first() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            assert_ok!(_create_comment(Some(Origin::signed(ACCOUNT2)), None, None, None)); // PostId 2 by ACCOUNT2
+
+            assert_ok!(_donate(Some(Origin::signed(ACCOUNT1)), None, 10));
+            assert_ok!(_donate(None, None, 20));
+            assert_ok!(_donate(Some(Origin::signed(ACCOUNT1)), None, 15));
+
+            let top_supporters = Donations::get_top_supporters(DonationRecipient::Post(POST2), 10);
+            let top_supporters: Vec<(AccountId, u64)> = top_supporters.into_iter()
+                .map(|supporter| (supporter.account, supporter.total_donated))
+                .collect();
+            assert_eq!(top_supporters, vec![(ACCOUNT1, 25), (ACCOUNT3, 20)]);
+
+            let top_supporter = Donations::get_top_supporters(DonationRecipient::Post(POST2), 1);
+            assert_eq!(top_supporter.len(), 1);
+            assert_eq!(top_supporter[0].account, ACCOUNT1);
+        });
+    }
+
+    #[test]
+    fn donate_with_comment_should_create_super_comment() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            let post_recipient = Some(DonationRecipient::Post(POST1));
+            assert_ok!(_donate_with_comment(None, post_recipient.clone(), 10, Some(comment_content_ipfs())));
+            assert_ok!(_donate_with_comment(
+                Some(Origin::signed(ACCOUNT2)),
+                post_recipient,
+                20,
+                Some(comment_content_ipfs())
+            ));
+
+            // Check that comments were created by supporters
+            let comment = Posts::post_by_id(POST2).unwrap();
+            assert_eq!(comment.owner, ACCOUNT3);
+            assert_eq!(comment.extension, extension_comment(None, POST1));
+            assert_eq!(Posts::post_by_id(POST3).unwrap().owner, ACCOUNT2);
+
+            // Check that comments are linked to donations
+            assert_eq!(Donations::donation_by_id(1).unwrap().comment_id, Some(POST2));
+            assert_eq!(Donations::donation_id_by_comment_id(POST2), Some(1));
+            assert_eq!(Donations::super_comment_ids_by_post_id(POST1), vec![POST2, POST3]);
+
+            let super_comment_ids: Vec<PostId> = Donations::get_super_comments(POST1).into_iter()
+                .map(|super_comment| super_comment.comment_id)
+                .collect();
+            assert_eq!(super_comment_ids, vec![POST3, POST2]);
+        });
+    }
+
+    #[test]
+    fn get_super_comments_should_skip_deleted_comments() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            let post_recipient = Some(DonationRecipient::Post(POST1));
+            assert_ok!(_donate_with_comment(None, post_recipient.clone(), 10, Some(comment_content_ipfs())));
+            assert_ok!(_donate_with_comment(
+                Some(Origin::signed(ACCOUNT2)),
+                post_recipient,
+                20,
+                Some(comment_content_ipfs())
+            ));
+
+            assert_ok!(_delete_post(Some(Origin::signed(ACCOUNT2)), Some(POST3)));
+
+            let super_comment_ids: Vec<PostId> = Donations::get_super_comments(POST1).into_iter()
+                .map(|super_comment| super_comment.comment_id)
+                .collect();
+            assert_eq!(super_comment_ids, vec![POST2]);
+        });
+    }
+
+    #[test]
+    fn donate_with_comment_should_fail_and_make_no_transfers_when_comment_cannot_be_created() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            assert_ok!(Moderation::block_entity_globally(Origin::root(), EntityId::Account(ACCOUNT3)));
+
+            assert_noop!(
+                _donate_with_comment(None, Some(DonationRecipient::Post(POST1)), 10, Some(comment_content_ipfs())),
+                UtilsError::<TestRuntime>::AccountIsBlocked
+            );
+        });
+    }
+
+    #[test]
+    fn donate_with_comment_should_fail_when_recipient_is_not_post() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            assert_noop!(
+                _donate_with_comment(
+                    None,
+                    Some(DonationRecipient::Account(ACCOUNT1)),
+                    10,
+                    Some(comment_content_ipfs())
+                ),
+                pallet_donations::Error::<TestRuntime>::CommentAllowedOnlyOnPostDonation
+            );
+        });
+    }
+
+    #[test]
+    fn donate_with_comment_should_fail_when_comment_content_is_empty() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            assert_noop!(
+                _donate_with_comment(None, Some(DonationRecipient::Post(POST1)), 10, Some(Content::None)),
+                UtilsError::<TestRuntime>::ContentIsEmpty
+            );
+        });
+    }
+
+    /*---------------------------------------------------------------------------------------------------*/
+    // Session keys tests
+
+    #[test]
+    fn session_key_should_follow_space_on_behalf_of_owner() {
+        ExtBuilder::build_with_space().execute_with(|| {
+            assert_ok!(_add_session_key(ACCOUNT2, SessionKeyPermissions::default()));
+
+            assert_ok!(_proxy_by_session_key(
+                Call::SpaceFollows(SpaceFollowsCall::follow_space(SPACE1))
+            ));
+
+            assert!(SpaceFollows::space_followed_by_account((ACCOUNT2, SPACE1)));
+            assert!(!SpaceFollows::space_followed_by_account((SESSION_KEY1, SPACE1)));
+        });
+    }
+
+    #[test]
+    fn session_key_should_create_post_on_behalf_of_owner() {
+        ExtBuilder::build_with_space().execute_with(|| {
+            assert_ok!(_add_session_key(ACCOUNT1, SessionKeyPermissions::default()));
+
+            assert_ok!(_proxy_by_session_key(
+                Call::Posts(PostsCall::create_post(Some(SPACE1), extension_regular_post(), post_content_ipfs()))
+            ));
+
+            let post = Posts::post_by_id(POST1).unwrap();
+            assert_eq!(post.created.account, ACCOUNT1);
+            assert_eq!(post.space_id, Some(SPACE1));
+        });
+    }
+
+    #[test]
+    fn session_key_should_react_to_post_on_behalf_of_owner() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            assert_ok!(_add_session_key(ACCOUNT2, SessionKeyPermissions::default()));
+
+            assert_ok!(_proxy_by_session_key(
+                Call::Reactions(ReactionsCall::create_post_reaction(POST1, ReactionKind::Upvote))
+            ));
+
+            assert_eq!(Reactions::post_reaction_id_by_account((ACCOUNT2, POST1)), REACTION1);
+            assert_eq!(Posts::post_by_id(POST1).unwrap().upvotes_count, 1);
+        });
+    }
+
+    #[test]
+    fn session_key_should_fail_to_create_post_in_space_not_allowed_for_key() {
+        ExtBuilder::build_with_space().execute_with(|| {
+            assert_ok!(_add_session_key(ACCOUNT1, SessionKeyPermissions {
+                allowed_calls: None,
+                allowed_spaces: Some(vec![SPACE2]),
+            }));
+
+            assert_noop!(
+                _proxy_by_session_key(
+                    Call::Posts(PostsCall::create_post(Some(SPACE1), extension_regular_post(), post_content_ipfs()))
+                ),
+                SessionKeysError::<TestRuntime>::SpaceNotAllowedForSessionKey
+            );
+            assert!(Posts::post_by_id(POST1).is_none());
+        });
+    }
+
+    // Space tests
+    
+    #[test]
+    fn create_space_should_work() {
+        ExtBuilder::build().execute_with(|| {
+            assert_ok!(_create_default_space()); // SpaceId 1
+
+            // Check storages
+            assert_eq!(Spaces::space_ids_by_owner(ACCOUNT1), vec![SPACE1]);
+            assert_eq!(find_space_id_by_handle(space_handle()), Some(SPACE1));
+            assert_eq!(Spaces::next_space_id(), SPACE2);
+
+            // Check whether data stored correctly
+            let space = Spaces::space_by_id(SPACE1).unwrap();
+
+            assert_eq!(space.created.account, ACCOUNT1);
+            assert!(space.updated.is_none());
+            assert_eq!(space.hidden, false);
+
+            assert_eq!(space.owner, ACCOUNT1);
+            assert_eq!(space.handle, Some(space_handle()));
+            assert_eq!(space.content, space_content_ipfs());
+
+            assert_eq!(space.posts_count, 0);
+            assert_eq!(space.followers_count, 1);
+            assert!(SpaceHistory::edit_history(space.id).is_empty());
+            assert_eq!(space.score, 0);
+
+            // Check that the handle deposit has been reserved:
+            let reserved_balance = Balances::reserved_balance(ACCOUNT1);
+            assert_eq!(reserved_balance, HANDLE_DEPOSIT);
+        });
+    }
+
+    #[test]
+    fn create_space_should_store_handle_lowercase() {
+        ExtBuilder::build().execute_with(|| {
+            let new_handle: Vec<u8> = b"sPaCe_hAnDlE".to_vec();
+
+            assert_ok!(_create_space(None, Some(Some(new_handle.clone())), None, None)); // SpaceId 1
+
+            // Handle should be lowercase in storage and original in struct
+            let space = Spaces::space_by_id(SPACE1).unwrap();
+            assert_eq!(space.handle, Some(new_handle.clone()));
+            assert_eq!(find_space_id_by_handle(new_handle), Some(SPACE1));
+        });
+    }
+
+    #[test]
+    fn create_space_should_fail_when_too_short_handle_provided() {
+        ExtBuilder::build().execute_with(|| {
+            let short_handle: Vec<u8> = vec![65; (MinHandleLen::get() - 1) as usize];
+
+            // Try to catch an error creating a space with too short handle
+            assert_noop!(_create_space(
+                None,
+                Some(Some(short_handle)),
+                None,
+                None
+            ), UtilsError::<TestRuntime>::HandleIsTooShort);
+        });
+    }
+
+    #[test]
+    fn create_space_should_fail_when_too_long_handle_provided() {
+        ExtBuilder::build().execute_with(|| {
+            let long_handle: Vec<u8> = vec![65; (MaxHandleLen::get() + 1) as usize];
+
+            // Try to catch an error creating a space with too long handle
+            assert_noop!(_create_space(
+                None,
+                Some(Some(long_handle)),
+                None,
+                None
+            ), UtilsError::<TestRuntime>::HandleIsTooLong);
+        });
+    }
+
+    #[test]
+    fn create_space_should_fail_when_not_unique_handle_provided() {
+        ExtBuilder::build().execute_with(|| {
+            assert_ok!(_create_default_space());
+            // SpaceId 1
+            // Try to catch an error creating a space with not unique handle
+            assert_noop!(_create_default_space(), SpacesError::<TestRuntime>::SpaceHandleIsNotUnique);
+        });
+    }
+
+    #[test]
+    fn create_space_should_fail_when_handle_contains_at_char() {
+        ExtBuilder::build().execute_with(|| {
+            let invalid_handle: Vec<u8> = b"@space_handle".to_vec();
+
+            assert_noop!(_create_space(
+                None,
+                Some(Some(invalid_handle)),
+                None,
+                None
+            ), UtilsError::<TestRuntime>::HandleContainsInvalidChars);
+        });
+    }
+
+    #[test]
+    fn create_space_should_fail_when_handle_contains_minus_char() {
+        ExtBuilder::build().execute_with(|| {
+            let invalid_handle: Vec<u8> = b"space-handle".to_vec();
+
+            assert_noop!(_create_space(
+                None,
+                Some(Some(invalid_handle)),
+                None,
+                None
+            ), UtilsError::<TestRuntime>::HandleContainsInvalidChars);
+        });
+    }
+
+    #[test]
+    fn create_space_should_fail_when_handle_contains_space_char() {
+        ExtBuilder::build().execute_with(|| {
+            let invalid_handle: Vec<u8> = b"space handle".to_vec();
+
+            assert_noop!(_create_space(
+                None,
+                Some(Some(invalid_handle)),
+                None,
+                None
+            ), UtilsError::<TestRuntime>::HandleContainsInvalidChars);
+        });
+    }
+
+    #[test]
+    fn create_space_should_fail_when_handle_contains_unicode() {
+        ExtBuilder::build().execute_with(|| {
+            let invalid_handle: Vec<u8> = String::from("блог_хендл").into_bytes().to_vec();
+
+            assert_noop!(_create_space(
+                None,
+                Some(Some(invalid_handle)),
+                None,
+                None
+            ), UtilsError::<TestRuntime>::HandleContainsInvalidChars);
+        });
+    }
+
+    #[test]
+    fn create_space_should_fail_when_ipfs_cid_is_invalid() {
+        ExtBuilder::build().execute_with(|| {
+            // Try to catch an error creating a space with invalid content
             assert_noop!(_create_space(
                 None,
                 None,
@@ -1651,11 +2322,281 @@ mod tests {
 
             assert_ok!(_delete_default_role());
 
-            assert_noop!(_update_space(
+            assert_noop!(_update_space(
+                Some(Origin::signed(ACCOUNT2)),
+                Some(SPACE1),
+                Some(space_update)
+            ), SpacesError::<TestRuntime>::NoPermissionToUpdateSpace);
+        });
+    }
+
+    #[test]
+    fn get_spaces_should_work() {
+        ExtBuilder::build_with_post_and_two_spaces().execute_with(|| {
+            let spaces = Spaces::get_spaces(SPACE1, 10);
+            assert_eq!(spaces.len(), 2);
+            assert_eq!(spaces[0].id, SPACE1);
+            assert_eq!(spaces[0].owner_id, ACCOUNT1);
+            assert_eq!(spaces[0].handle, Some(space_handle().to_ascii_lowercase()));
+            assert_eq!(spaces[0].visible_posts_count, 1);
+            assert_eq!(spaces[0].permissions, Permissions::resolve_space_perms(None));
+            assert_eq!(spaces[1].id, SPACE2);
+
+            // Should respect the limit and the start id:
+            assert_eq!(Spaces::get_spaces(SPACE1, 1).len(), 1);
+            assert_eq!(Spaces::get_spaces(SPACE2, 10).len(), 1);
+        });
+    }
+
+    #[test]
+    fn get_spaces_should_return_space_permissions_merged_with_defaults() {
+        ExtBuilder::build().execute_with(|| {
+            let space_perms = SpacePermissions {
+                none: Some(vec![SP::CreatePosts].into_iter().collect()),
+                everyone: Some(vec![SP::CreatePosts, SP::CreateComments].into_iter().collect()),
+                follower: None,
+                space_owner: None,
+            };
+            assert_ok!(_create_space(None, None, None, Some(Some(space_perms)))); // SpaceId 1
+
+            let permissions = Spaces::get_spaces_by_ids(vec![SPACE1])[0].permissions.clone();
+            assert_eq!(permissions.none, Some(vec![SP::CreatePosts].into_iter().collect()));
+            assert_eq!(permissions.everyone, Some(vec![SP::CreateComments].into_iter().collect()));
+            assert!(permissions.follower.is_none());
+
+            // Not overridden, so the defaults are returned without forbidden permissions.
+            let space_owner_perms = permissions.space_owner.unwrap();
+            assert!(space_owner_perms.contains(&SP::UpdateSpace));
+            assert!(!space_owner_perms.contains(&SP::CreatePosts));
+        });
+    }
+
+    #[test]
+    fn get_spaces_by_ids_should_skip_unknown_spaces() {
+        ExtBuilder::build_with_space().execute_with(|| {
+            let spaces = Spaces::get_spaces_by_ids(vec![SPACE1, SPACE2]);
+            assert_eq!(spaces.len(), 1);
+            assert_eq!(spaces[0].id, SPACE1);
+        });
+    }
+
+    #[test]
+    fn get_space_by_handle_should_work_with_any_case() {
+        ExtBuilder::build_with_space().execute_with(|| {
+            let space = Spaces::get_space_by_handle(b"SPACE_HANDLE".to_vec()).unwrap();
+            assert_eq!(space.id, SPACE1);
+
+            assert!(Spaces::get_space_by_handle(space_handle_2()).is_none());
+        });
+    }
+
+    #[test]
+    fn get_space_ids_by_owner_should_work() {
+        ExtBuilder::build_with_post_and_two_spaces().execute_with(|| {
+            assert_eq!(Spaces::get_space_ids_by_owner(ACCOUNT1), vec![SPACE1, SPACE2]);
+            assert!(Spaces::get_space_ids_by_owner(ACCOUNT2).is_empty());
+        });
+    }
+
+    // Delete space tests
+
+    #[test]
+    fn delete_space_should_work() {
+        ExtBuilder::build_with_space().execute_with(|| {
+            assert_ok!(_delete_space(None, None));
+
+            assert!(Spaces::space_by_id(SPACE1).is_none());
+            assert!(find_space_id_by_handle(space_handle()).is_none());
+            assert!(Spaces::space_ids_by_owner(ACCOUNT1).is_empty());
+            assert_eq!(Spaces::space_ids_to_clean_up(), vec![SPACE1]);
+
+            // Handle deposit should be released
+            assert!(Balances::reserved_balance(ACCOUNT1).is_zero());
+        });
+    }
+
+    #[test]
+    fn delete_space_should_clean_up_space_data_across_blocks() {
+        ExtBuilder::build_with_comment().execute_with(|| {
+            assert_ok!(_default_follow_space()); // ACCOUNT2 follows SpaceId 1
+            assert_ok!(_create_default_role()); // RoleId 1
+            assert_ok!(_grant_default_role()); // Grant RoleId 1 to ACCOUNT2
+            assert_ok!(_report_default_post()); // ReportId 1
+
+            assert_ok!(_delete_space(None, None));
+
+            // Posts, a role with its user and one follower fit into the first block
+            _clean_up_deleted_spaces();
+            assert!(Posts::post_by_id(POST1).is_none());
+            assert!(Posts::post_by_id(POST2).is_none());
+            assert!(Posts::post_ids_by_space_id(SPACE1).is_empty());
+            assert!(Roles::role_by_id(ROLE1).is_none());
+            assert!(Roles::role_ids_by_space_id(SPACE1).is_empty());
+            assert!(Roles::role_ids_by_user_in_space(User::Account(ACCOUNT2), SPACE1).is_empty());
+            assert_eq!(SpaceFollows::space_followers(SPACE1).len(), 1);
+            assert_eq!(Spaces::space_ids_to_clean_up(), vec![SPACE1]);
+
+            _clean_up_deleted_spaces();
+            assert!(SpaceFollows::space_followers(SPACE1).is_empty());
+            assert!(!SpaceFollows::space_followed_by_account((ACCOUNT2, SPACE1)));
+            assert!(SpaceFollows::spaces_followed_by_account(ACCOUNT2).is_empty());
+            assert!(Moderation::report_by_id(REPORT1).is_none());
+            assert!(Moderation::report_ids_by_space_id(SPACE1).is_empty());
+            assert!(Spaces::space_ids_to_clean_up().is_empty());
+        });
+    }
+
+    #[test]
+    fn delete_space_should_clean_up_entity_statuses_and_donation_settings() {
+        ExtBuilder::build_with_space().execute_with(|| {
+            assert_ok!(_set_space_share_of_donations(Perbill::from_percent(10)));
+            assert_ok!(_update_entity_status(None, Some(EntityId::Account(ACCOUNT2)), None, None));
+            assert_ok!(_suggest_entity_status(
+                None,
+                Some(EntityId::Account(ACCOUNT3)),
+                None,
+                Some(Some(EntityStatus::Allowed)),
+                Some(None)
+            )); // A suggestion without a report
+
+            assert_ok!(_delete_space(None, None));
+            _clean_up_deleted_spaces();
+
+            assert!(Moderation::status_by_entity_in_space(EntityId::Account(ACCOUNT2), SPACE1).is_none());
+            assert!(Moderation::suggested_statuses(EntityId::Account(ACCOUNT3), SPACE1).is_empty());
+            assert!(Moderation::moderated_entities_by_space_id(SPACE1).is_empty());
+            assert!(Donations::donation_settings_by_recipient(DonationRecipient::Space(SPACE1)).is_none());
+            assert!(Spaces::space_ids_to_clean_up().is_empty());
+        });
+    }
+
+    #[test]
+    fn delete_space_should_clean_up_reactions_on_its_posts() {
+        ExtBuilder::build_with_reacted_post_and_two_spaces().execute_with(|| {
+            assert_ok!(_delete_space(None, None));
+            _clean_up_deleted_spaces();
+
+            assert!(Posts::post_by_id(POST1).is_none());
+            assert!(Reactions::reaction_by_id(REACTION1).is_none());
+            assert!(Reactions::reaction_ids_by_post_id(POST1).is_empty());
+            assert!(!PostReactionIdByAccount::<TestRuntime>::contains_key((ACCOUNT1, POST1)));
+            assert!(Spaces::space_ids_to_clean_up().is_empty());
+        });
+    }
+
+    #[test]
+    fn delete_space_should_move_its_subspaces_to_root() {
+        ExtBuilder::build_with_space().execute_with(|| {
+            assert_ok!(_create_subspace(None, Some(Some(SPACE1)), Some(None), None, None)); // SpaceId 2
+            assert_eq!(Spaces::subspace_ids_by_parent_id(SPACE1), vec![SPACE2]);
+
+            assert_ok!(_delete_space(None, None));
+            _clean_up_deleted_spaces();
+
+            assert!(Spaces::space_by_id(SPACE2).unwrap().parent_id.is_none());
+            assert!(Spaces::subspace_ids_by_parent_id(SPACE1).is_empty());
+        });
+    }
+
+    #[test]
+    fn runtime_upgrade_should_index_existing_subspaces() {
+        ExtBuilder::build_with_space().execute_with(|| {
+            assert_ok!(_create_subspace(None, Some(Some(SPACE1)), Some(None), None, None)); // SpaceId 2
+
+            // A subspace created before subspaces were indexed by their parent.
+            SubspaceIdsByParentId::remove(SPACE1);
+
+            Spaces::on_runtime_upgrade();
+            assert_eq!(Spaces::subspace_ids_by_parent_id(SPACE1), vec![SPACE2]);
+
+            // Already indexed subspaces are not added twice.
+            Spaces::on_runtime_upgrade();
+            assert_eq!(Spaces::subspace_ids_by_parent_id(SPACE1), vec![SPACE2]);
+        });
+    }
+
+    #[test]
+    fn delete_space_should_keep_tombstone_of_post_shared_in_another_space() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            assert_ok!(_create_space(
+                Some(Origin::signed(ACCOUNT2)),
+                Some(Some(b"space2_handle".to_vec())),
+                None,
+                None
+            )); // SpaceId 2 by ACCOUNT2
+
+            assert_ok!(_create_post(
+                Some(Origin::signed(ACCOUNT2)),
+                Some(Some(SPACE2)),
+                Some(extension_shared_post(POST1)),
+                None
+            )); // Share PostId 1 on SpaceId 2 by ACCOUNT2
+
+            assert_ok!(_delete_space(None, None));
+            _clean_up_deleted_spaces();
+
+            let original_post = Posts::post_by_id(POST1).unwrap();
+            assert!(original_post.space_id.is_none());
+            assert_eq!(original_post.content, Content::None);
+            assert_eq!(Posts::shared_post_ids_by_original_post_id(POST1), vec![POST2]);
+            assert!(Posts::post_ids_by_space_id(SPACE1).is_empty());
+
+            // The sharing post still can be deleted.
+            assert_ok!(_delete_post(Some(Origin::signed(ACCOUNT2)), Some(POST2)));
+            assert_eq!(Posts::post_by_id(POST1).unwrap().shares_count, 0);
+            assert!(Posts::shared_post_ids_by_original_post_id(POST1).is_empty());
+        });
+    }
+
+    #[test]
+    fn delete_subspace_should_work_for_parent_space_owner() {
+        ExtBuilder::build_with_a_few_roles_granted_to_account2(vec![SP::CreateSubspaces]).execute_with(|| {
+            assert_ok!(_create_subspace(
+                Some(Origin::signed(ACCOUNT2)),
+                Some(Some(SPACE1)),
+                Some(None),
+                None,
+                None
+            )); // SpaceId 2 by ACCOUNT2
+
+            // ACCOUNT1 has a default permission to DeleteAnySubspace in SpaceId 1
+            assert_ok!(_delete_space(None, Some(SPACE2)));
+            assert!(Spaces::space_by_id(SPACE2).is_none());
+        });
+    }
+
+    #[test]
+    fn delete_space_should_fail_when_space_not_found() {
+        ExtBuilder::build().execute_with(|| {
+            assert_noop!(_delete_space(None, None), SpacesError::<TestRuntime>::SpaceNotFound);
+        });
+    }
+
+    #[test]
+    fn delete_space_should_fail_when_account_is_not_a_space_owner() {
+        ExtBuilder::build_with_space().execute_with(|| {
+            assert_noop!(
+                _delete_space(Some(Origin::signed(ACCOUNT2)), None),
+                SpacesError::<TestRuntime>::NotASpaceOwner
+            );
+        });
+    }
+
+    #[test]
+    fn delete_subspace_should_fail_when_account_has_no_permission_to_delete_any_subspace() {
+        ExtBuilder::build_with_a_few_roles_granted_to_account2(vec![SP::CreateSubspaces]).execute_with(|| {
+            assert_ok!(_create_subspace(
                 Some(Origin::signed(ACCOUNT2)),
-                Some(SPACE1),
-                Some(space_update)
-            ), SpacesError::<TestRuntime>::NoPermissionToUpdateSpace);
+                Some(Some(SPACE1)),
+                Some(None),
+                None,
+                None
+            )); // SpaceId 2 by ACCOUNT2
+
+            assert_noop!(
+                _delete_space(Some(Origin::signed(ACCOUNT3)), Some(SPACE2)),
+                SpacesError::<TestRuntime>::NoPermissionToDeleteAnySubspace
+            );
         });
     }
 
@@ -2156,178 +3097,486 @@ mod tests {
                 assert_eq!(comment.replies_count, replies_should_be as u16);
                 assert_eq!(Posts::reply_ids_by_post_id(comment_id), vec![comment_id + 1]);
 
-                assert_eq!(comment.hidden_replies_count, 0);
-            }
+                assert_eq!(comment.hidden_replies_count, 0);
+            }
+
+            let last_comment = Posts::post_by_id(last_comment_id).unwrap();
+            assert_eq!(last_comment.replies_count, 0);
+            assert!(Posts::reply_ids_by_post_id(last_comment_id).is_empty());
+
+            assert_eq!(last_comment.hidden_replies_count, 0);
+        });
+    }
+
+    #[test]
+    fn create_comment_should_fail_when_post_not_found() {
+        ExtBuilder::build().execute_with(|| {
+            // Try to catch an error creating a comment with wrong post
+            assert_noop!(_create_default_comment(), PostsError::<TestRuntime>::PostNotFound);
+        });
+    }
+
+    #[test]
+    fn create_comment_should_fail_when_parent_comment_is_unknown() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            // Try to catch an error creating a comment with wrong parent
+            assert_noop!(_create_comment(
+                None,
+                None,
+                Some(Some(POST2)),
+                None
+            ), PostsError::<TestRuntime>::UnknownParentComment);
+        });
+    }
+
+    #[test]
+    fn create_comment_should_fail_when_ipfs_cid_is_invalid() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            // Try to catch an error creating a comment with wrong parent
+            assert_noop!(_create_comment(
+                None,
+                None,
+                None,
+                Some(invalid_content_ipfs())
+            ), UtilsError::<TestRuntime>::InvalidIpfsCid);
+        });
+    }
+
+    #[test]
+    fn create_comment_should_fail_when_trying_to_create_in_hidden_space_scope() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            assert_ok!(_update_space(
+                None,
+                None,
+                Some(space_update(None, None, Some(true)))
+            ));
+
+            assert_noop!(_create_default_comment(), PostsError::<TestRuntime>::CannotCreateInHiddenScope);
+        });
+    }
+
+    #[test]
+    fn create_comment_should_fail_when_trying_create_in_hidden_post_scope() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            assert_ok!(_update_post(
+                None,
+                None,
+                Some(post_update(None, None, Some(true)))
+            ));
+
+            assert_noop!(_create_default_comment(), PostsError::<TestRuntime>::CannotCreateInHiddenScope);
+        });
+    }
+
+    #[test]
+    fn create_comment_should_fail_when_max_comment_depth_reached() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            assert_ok!(_create_comment(None, None, Some(None), None)); // PostId 2
+
+            for parent_id in 2..11 as PostId {
+                assert_ok!(_create_comment(None, None, Some(Some(parent_id)), None)); // PostId N (last = 10)
+            }
+
+            // Some(Some(11)) - here is parent_id 11 of type PostId
+            assert_noop!(_create_comment(
+                None,
+                None,
+                Some(Some(11)),
+                None
+            ), PostsError::<TestRuntime>::MaxCommentDepthReached);
+        });
+    }
+
+    #[test]
+    fn update_comment_should_work() {
+        ExtBuilder::build_with_comment().execute_with(|| {
+            // Post update with ID 1 should be fine
+            assert_ok!(_update_comment(None, None, None));
+
+            // Check whether post updates correctly
+            let comment = Posts::post_by_id(POST2).unwrap();
+            assert_eq!(comment.content, reply_content_ipfs());
+
+            // Check whether history recorded correctly
+            assert_eq!(PostHistory::edit_history(POST2)[0].old_data.content, Some(comment_content_ipfs()));
+        });
+    }
+
+    #[test]
+    fn update_comment_hidden_should_work_when_comment_has_parents() {
+        ExtBuilder::build_with_comment().execute_with(|| {
+            let first_comment_id: PostId = 2;
+            let penultimate_comment_id: PostId = 8;
+            let last_comment_id: PostId = 9;
+
+            for parent_id in first_comment_id..last_comment_id as PostId {
+                // last created = `last_comment_id`; last parent = `penultimate_comment_id`
+                assert_ok!(_create_comment(None, None, Some(Some(parent_id)), None));
+            }
+
+            assert_ok!(_update_comment(
+                None,
+                Some(last_comment_id),
+                Some(post_update(
+                    None,
+                    None,
+                    Some(true) // make comment hidden
+                ))
+            ));
+
+            for comment_id in first_comment_id..penultimate_comment_id as PostId {
+                let comment = Posts::post_by_id(comment_id).unwrap();
+                assert_eq!(comment.hidden_replies_count, 1);
+            }
+            let last_comment = Posts::post_by_id(last_comment_id).unwrap();
+            assert_eq!(last_comment.hidden_replies_count, 0);
+        });
+    }
+
+    #[test]
+    // `PostNotFound` here: Post with Comment extension. Means that comment wasn't found.
+    fn update_comment_should_fail_when_post_not_found() {
+        ExtBuilder::build().execute_with(|| {
+            // Try to catch an error updating a comment with wrong PostId
+            assert_noop!(_update_comment(None, None, None), PostsError::<TestRuntime>::PostNotFound);
+        });
+    }
+
+    #[test]
+    fn update_comment_should_fail_when_account_is_not_a_comment_author() {
+        ExtBuilder::build_with_comment().execute_with(|| {
+            // Try to catch an error updating a comment with wrong Account
+            assert_noop!(_update_comment(
+                Some(Origin::signed(ACCOUNT2)),
+                None,
+                None
+            ), PostsError::<TestRuntime>::NotACommentAuthor);
+        });
+    }
+
+    #[test]
+    fn update_comment_should_fail_when_ipfs_cid_is_invalid() {
+        ExtBuilder::build_with_comment().execute_with(|| {
+            // Try to catch an error updating a comment with invalid content
+            assert_noop!(_update_comment(
+                None,
+                None,
+                Some(
+                    post_update(
+                        None,
+                        Some(invalid_content_ipfs()),
+                        None
+                    )
+                )
+            ), UtilsError::<TestRuntime>::InvalidIpfsCid);
+        });
+    }
+
+    // Delete post tests
+
+    #[test]
+    fn delete_post_should_work() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            assert_ok!(_delete_post(None, None));
+
+            assert!(Posts::post_by_id(POST1).is_none());
+            assert!(Posts::post_ids_by_space_id(SPACE1).is_empty());
+
+            let space = Spaces::space_by_id(SPACE1).unwrap();
+            assert_eq!(space.posts_count, 0);
+            assert_eq!(space.hidden_posts_count, 0);
+        });
+    }
+
+    #[test]
+    fn delete_hidden_post_should_update_hidden_posts_count() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            assert_ok!(_update_post(None, None, Some(post_update(None, None, Some(true)))));
+            assert_ok!(_delete_post(None, None));
+
+            let space = Spaces::space_by_id(SPACE1).unwrap();
+            assert_eq!(space.posts_count, 0);
+            assert_eq!(space.hidden_posts_count, 0);
+        });
+    }
+
+    #[test]
+    fn delete_post_with_comments_should_leave_tombstone() {
+        ExtBuilder::build_with_comment().execute_with(|| {
+            assert_ok!(_create_comment(None, None, Some(Some(POST2)), None)); // PostId 3
+
+            assert_ok!(_delete_post(None, None));
+
+            let tombstone = Posts::post_by_id(POST1).unwrap();
+            assert_eq!(tombstone.content, Content::None);
+            assert_eq!(tombstone.replies_count, 2);
+            assert!(Posts::is_post_deleted(POST1));
+            assert!(Posts::post_by_id(POST2).is_some());
+            assert!(Posts::post_by_id(POST3).is_some());
+            assert_eq!(Posts::reply_ids_by_post_id(POST1), vec![POST2]);
+            assert_eq!(Posts::post_ids_by_space_id(SPACE1), vec![POST1]);
+
+            assert_noop!(
+                _delete_post(None, None),
+                PostsError::<TestRuntime>::PostIsAlreadyDeleted
+            );
+        });
+    }
+
+    #[test]
+    fn delete_post_with_reactions_should_leave_tombstone() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            assert_ok!(_create_default_post_reaction()); // ReactionId 1 by ACCOUNT1
+
+            assert_ok!(_delete_post(None, None));
+
+            let tombstone = Posts::post_by_id(POST1).unwrap();
+            assert_eq!(tombstone.content, Content::None);
+            assert_eq!(tombstone.upvotes_count, 1);
+            assert_eq!(Reactions::reaction_ids_by_post_id(POST1), vec![REACTION1]);
+        });
+    }
+
+    #[test]
+    fn delete_sharing_post_should_revert_share() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            assert_ok!(_create_space(
+                Some(Origin::signed(ACCOUNT2)),
+                Some(Some(b"space2_handle".to_vec())),
+                None,
+                None
+            )); // SpaceId 2 by ACCOUNT2
+
+            assert_ok!(_create_post(
+                Some(Origin::signed(ACCOUNT2)),
+                Some(Some(SPACE2)),
+                Some(extension_shared_post(POST1)),
+                None
+            )); // Share PostId 1 on SpaceId 2 by ACCOUNT2
+
+            assert_ok!(_delete_post(Some(Origin::signed(ACCOUNT2)), Some(POST2)));
+
+            let original_post = Posts::post_by_id(POST1).unwrap();
+            assert_eq!(original_post.shares_count, 0);
+            assert_eq!(original_post.score, 0);
+            assert_eq!(Profiles::social_account_by_id(ACCOUNT1).unwrap().reputation, 1);
+            assert!(Scores::post_score_by_account((ACCOUNT2, POST1, scoring_action_share_post())).is_none());
+            assert!(Posts::shared_post_ids_by_original_post_id(POST1).is_empty());
+            assert_eq!(Spaces::space_by_id(SPACE2).unwrap().posts_count, 0);
+        });
+    }
+
+    #[test]
+    fn delete_sharing_post_without_content_should_revert_share() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            assert_ok!(_create_space(
+                Some(Origin::signed(ACCOUNT2)),
+                Some(Some(b"space2_handle".to_vec())),
+                None,
+                None
+            )); // SpaceId 2 by ACCOUNT2
+
+            assert_ok!(_create_post(
+                Some(Origin::signed(ACCOUNT2)),
+                Some(Some(SPACE2)),
+                Some(extension_shared_post(POST1)),
+                Some(Content::None)
+            )); // Share PostId 1 on SpaceId 2 by ACCOUNT2 without a comment
 
-            let last_comment = Posts::post_by_id(last_comment_id).unwrap();
-            assert_eq!(last_comment.replies_count, 0);
-            assert!(Posts::reply_ids_by_post_id(last_comment_id).is_empty());
+            assert_ok!(_delete_post(Some(Origin::signed(ACCOUNT2)), Some(POST2)));
 
-            assert_eq!(last_comment.hidden_replies_count, 0);
+            assert!(Posts::post_by_id(POST2).is_none());
+            assert_eq!(Posts::post_by_id(POST1).unwrap().shares_count, 0);
+            assert!(Posts::shared_post_ids_by_original_post_id(POST1).is_empty());
+            assert_eq!(Spaces::space_by_id(SPACE2).unwrap().posts_count, 0);
         });
     }
 
     #[test]
-    fn create_comment_should_fail_when_post_not_found() {
-        ExtBuilder::build().execute_with(|| {
-            // Try to catch an error creating a comment with wrong post
-            assert_noop!(_create_default_comment(), PostsError::<TestRuntime>::PostNotFound);
+    fn delete_any_post_should_work_when_one_of_roles_is_permitted() {
+        ExtBuilder::build_with_a_few_roles_granted_to_account2(vec![SP::DeleteAnyPost]).execute_with(|| {
+            assert_ok!(_create_default_post()); // PostId 1
+
+            assert_ok!(_delete_post(Some(Origin::signed(ACCOUNT2)), None));
+            assert!(Posts::post_by_id(POST1).is_none());
         });
     }
 
     #[test]
-    fn create_comment_should_fail_when_parent_comment_is_unknown() {
-        ExtBuilder::build_with_post().execute_with(|| {
-            // Try to catch an error creating a comment with wrong parent
-            assert_noop!(_create_comment(
-                None,
-                None,
-                Some(Some(POST2)),
-                None
-            ), PostsError::<TestRuntime>::UnknownParentComment);
+    fn delete_post_should_fail_when_post_not_found() {
+        ExtBuilder::build_with_space().execute_with(|| {
+            assert_noop!(_delete_post(None, None), PostsError::<TestRuntime>::PostNotFound);
         });
     }
 
     #[test]
-    fn create_comment_should_fail_when_ipfs_cid_is_invalid() {
+    fn delete_post_should_fail_when_account_has_no_permission_to_delete_any_post() {
         ExtBuilder::build_with_post().execute_with(|| {
-            // Try to catch an error creating a comment with wrong parent
-            assert_noop!(_create_comment(
-                None,
-                None,
-                None,
-                Some(invalid_content_ipfs())
-            ), UtilsError::<TestRuntime>::InvalidIpfsCid);
+            assert_noop!(
+                _delete_post(Some(Origin::signed(ACCOUNT2)), None),
+                PostsError::<TestRuntime>::NoPermissionToDeleteAnyPost
+            );
         });
     }
 
     #[test]
-    fn create_comment_should_fail_when_trying_to_create_in_hidden_space_scope() {
+    fn delete_comment_should_work() {
         ExtBuilder::build_with_post().execute_with(|| {
-            assert_ok!(_update_space(
-                None,
-                None,
-                Some(space_update(None, None, Some(true)))
-            ));
+            assert_ok!(_create_comment(Some(Origin::signed(ACCOUNT2)), None, None, None)); // PostId 2
 
-            assert_noop!(_create_default_comment(), PostsError::<TestRuntime>::CannotCreateInHiddenScope);
+            assert_ok!(_delete_post(Some(Origin::signed(ACCOUNT2)), Some(POST2)));
+
+            assert!(Posts::post_by_id(POST2).is_none());
+            assert!(Posts::reply_ids_by_post_id(POST1).is_empty());
+
+            let root_post = Posts::post_by_id(POST1).unwrap();
+            assert_eq!(root_post.replies_count, 0);
+            assert_eq!(root_post.score, 0);
+            assert_eq!(Spaces::space_by_id(SPACE1).unwrap().score, 0);
+            assert!(Scores::post_score_by_account((ACCOUNT2, POST1, scoring_action_create_comment())).is_none());
         });
     }
 
     #[test]
-    fn create_comment_should_fail_when_trying_create_in_hidden_post_scope() {
-        ExtBuilder::build_with_post().execute_with(|| {
-            assert_ok!(_update_post(
-                None,
-                None,
-                Some(post_update(None, None, Some(true)))
-            ));
+    fn delete_comment_should_update_counters_on_ancestors() {
+        ExtBuilder::build_with_comment().execute_with(|| {
+            assert_ok!(_create_comment(None, None, Some(Some(POST2)), None)); // PostId 3
+            assert_ok!(_update_comment(None, Some(POST3), Some(post_update(None, None, Some(true)))));
 
-            assert_noop!(_create_default_comment(), PostsError::<TestRuntime>::CannotCreateInHiddenScope);
+            assert_ok!(_delete_post(None, Some(POST3)));
+
+            assert!(Posts::post_by_id(POST3).is_none());
+            assert!(Posts::reply_ids_by_post_id(POST2).is_empty());
+
+            let parent_comment = Posts::post_by_id(POST2).unwrap();
+            assert_eq!(parent_comment.replies_count, 0);
+            assert_eq!(parent_comment.hidden_replies_count, 0);
+
+            let root_post = Posts::post_by_id(POST1).unwrap();
+            assert_eq!(root_post.replies_count, 1);
+            assert_eq!(root_post.hidden_replies_count, 0);
         });
     }
 
     #[test]
-    fn create_comment_should_fail_when_max_comment_depth_reached() {
-        ExtBuilder::build_with_post().execute_with(|| {
-            assert_ok!(_create_comment(None, None, Some(None), None)); // PostId 2
+    fn delete_comment_with_replies_should_leave_tombstone() {
+        ExtBuilder::build_with_comment().execute_with(|| {
+            assert_ok!(_create_comment(None, None, Some(Some(POST2)), None)); // PostId 3
 
-            for parent_id in 2..11 as PostId {
-                assert_ok!(_create_comment(None, None, Some(Some(parent_id)), None)); // PostId N (last = 10)
-            }
+            assert_ok!(_delete_post(None, Some(POST2)));
 
-            // Some(Some(11)) - here is parent_id 11 of type PostId
-            assert_noop!(_create_comment(
-                None,
-                None,
-                Some(Some(11)),
-                None
-            ), PostsError::<TestRuntime>::MaxCommentDepthReached);
+            let tombstone = Posts::post_by_id(POST2).unwrap();
+            assert_eq!(tombstone.content, Content::None);
+            assert_eq!(tombstone.replies_count, 1);
+            assert_eq!(Posts::reply_ids_by_post_id(POST1), vec![POST2]);
+            assert_eq!(Posts::reply_ids_by_post_id(POST2), vec![POST3]);
+            assert_eq!(Posts::post_by_id(POST1).unwrap().replies_count, 2);
+
+            assert_noop!(
+                _delete_post(None, Some(POST2)),
+                PostsError::<TestRuntime>::PostIsAlreadyDeleted
+            );
         });
     }
 
     #[test]
-    fn update_comment_should_work() {
-        ExtBuilder::build_with_comment().execute_with(|| {
-            // Post update with ID 1 should be fine
-            assert_ok!(_update_comment(None, None, None));
+    fn delete_comment_should_keep_score_when_account_has_other_comments() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            assert_ok!(_create_comment(Some(Origin::signed(ACCOUNT2)), None, None, None)); // PostId 2
+            assert_ok!(_create_comment(Some(Origin::signed(ACCOUNT2)), None, None, None)); // PostId 3
 
-            // Check whether post updates correctly
-            let comment = Posts::post_by_id(POST2).unwrap();
-            assert_eq!(comment.content, reply_content_ipfs());
+            let score_diff = CreateCommentActionWeight::get() as i32;
+            assert_eq!(Posts::post_by_id(POST1).unwrap().score, score_diff);
 
-            // Check whether history recorded correctly
-            assert_eq!(PostHistory::edit_history(POST2)[0].old_data.content, Some(comment_content_ipfs()));
+            assert_ok!(_delete_post(Some(Origin::signed(ACCOUNT2)), Some(POST2)));
+            assert_eq!(Posts::post_by_id(POST1).unwrap().score, score_diff);
+            assert_eq!(Posts::comments_count_by_account_on_post((ACCOUNT2, POST1)), 1);
+
+            assert_ok!(_delete_post(Some(Origin::signed(ACCOUNT2)), Some(POST3)));
+            assert_eq!(Posts::post_by_id(POST1).unwrap().score, 0);
+            assert_eq!(Spaces::space_by_id(SPACE1).unwrap().score, 0);
+            assert_eq!(Posts::comments_count_by_account_on_post((ACCOUNT2, POST1)), 0);
         });
     }
 
     #[test]
-    fn update_comment_hidden_should_work_when_comment_has_parents() {
+    fn delete_comment_should_fail_when_account_is_not_a_comment_author() {
         ExtBuilder::build_with_comment().execute_with(|| {
-            let first_comment_id: PostId = 2;
-            let penultimate_comment_id: PostId = 8;
-            let last_comment_id: PostId = 9;
+            assert_noop!(
+                _delete_post(Some(Origin::signed(ACCOUNT2)), Some(POST2)),
+                PostsError::<TestRuntime>::NotACommentAuthor
+            );
+        });
+    }
 
-            for parent_id in first_comment_id..last_comment_id as PostId {
-                // last created = `last_comment_id`; last parent = `penultimate_comment_id`
-                assert_ok!(_create_comment(None, None, Some(Some(parent_id)), None));
-            }
+    // Posts runtime API tests
+    #[test]
+    fn get_posts_by_space_should_return_newest_posts_first() {
+        ExtBuilder::build_with_post().execute_with(|| {
+            assert_ok!(_create_default_post()); // PostId 2
+            assert_ok!(_create_default_post()); // PostId 3
 
-            assert_ok!(_update_comment(
-                None,
-                Some(last_comment_id),
-                Some(post_update(
-                    None,
-                    None,
-                    Some(true) // make comment hidden
-                ))
-            ));
+            // Hide the PostId 2
+            assert_ok!(_update_post(None, Some(POST2), Some(post_update(None, None, Some(true)))));
 
-            for comment_id in first_comment_id..penultimate_comment_id as PostId {
-                let comment = Posts::post_by_id(comment_id).unwrap();
-                assert_eq!(comment.hidden_replies_count, 1);
-            }
-            let last_comment = Posts::post_by_id(last_comment_id).unwrap();
-            assert_eq!(last_comment.hidden_replies_count, 0);
+            let post_ids = |posts: Vec<FlatPost<AccountId, BlockNumber>>| -> Vec<PostId> {
+                posts.iter().map(|post| post.id).collect()
+            };
+
+            assert_eq!(post_ids(Posts::get_posts_by_space(SPACE1, 0, 10, false)), vec![POST3, POST1]);
+            assert_eq!(post_ids(Posts::get_posts_by_space(SPACE1, 0, 10, true)), vec![POST3, POST2, POST1]);
+            assert_eq!(post_ids(Posts::get_posts_by_space(SPACE1, 1, 1, true)), vec![POST2]);
+            assert!(Posts::get_posts_by_space(SPACE2, 0, 10, true).is_empty());
         });
     }
 
     #[test]
-    // `PostNotFound` here: Post with Comment extension. Means that comment wasn't found.
-    fn update_comment_should_fail_when_post_not_found() {
-        ExtBuilder::build().execute_with(|| {
-            // Try to catch an error updating a comment with wrong PostId
-            assert_noop!(_update_comment(None, None, None), PostsError::<TestRuntime>::PostNotFound);
+    fn get_reply_tree_should_work() {
+        ExtBuilder::build_with_comment().execute_with(|| {
+            assert_ok!(_create_comment(None, None, Some(Some(POST2)), None)); // PostId 3
+
+            let tree = Posts::get_reply_tree(POST1, 10).unwrap();
+            assert_eq!(tree.post.id, POST1);
+            assert_eq!(tree.replies.len(), 1);
+            assert_eq!(tree.replies[0].post.id, POST2);
+            assert_eq!(tree.replies[0].replies[0].post.id, POST3);
+            assert!(tree.replies[0].replies[0].replies.is_empty());
+
+            // Replies deeper than `max_depth` should not be included:
+            let tree = Posts::get_reply_tree(POST1, 1).unwrap();
+            assert_eq!(tree.replies.len(), 1);
+            assert!(tree.replies[0].replies.is_empty());
+
+            assert!(Posts::get_reply_tree(POST1 + 100, 10).is_none());
         });
     }
 
     #[test]
-    fn update_comment_should_fail_when_account_is_not_a_comment_author() {
+    fn get_reply_tree_should_skip_hidden_replies() {
         ExtBuilder::build_with_comment().execute_with(|| {
-            // Try to catch an error updating a comment with wrong Account
-            assert_noop!(_update_comment(
-                Some(Origin::signed(ACCOUNT2)),
-                None,
-                None
-            ), PostsError::<TestRuntime>::NotACommentAuthor);
+            assert_ok!(_update_comment(None, None, Some(post_update(None, None, Some(true)))));
+
+            let tree = Posts::get_reply_tree(POST1, 10).unwrap();
+            assert!(tree.replies.is_empty());
         });
     }
 
     #[test]
-    fn update_comment_should_fail_when_ipfs_cid_is_invalid() {
-        ExtBuilder::build_with_comment().execute_with(|| {
-            // Try to catch an error updating a comment with invalid content
-            assert_noop!(_update_comment(
-                None,
+    fn get_shared_posts_should_work() {
+        ExtBuilder::build_with_post_and_two_spaces().execute_with(|| {
+            assert_ok!(_create_post(
                 None,
-                Some(
-                    post_update(
-                        None,
-                        Some(invalid_content_ipfs()),
-                        None
-                    )
-                )
-            ), UtilsError::<TestRuntime>::InvalidIpfsCid);
+                Some(Some(SPACE2)),
+                Some(extension_shared_post(POST1)),
+                None
+            )); // PostId 2
+
+            let shared_posts = Posts::get_shared_posts(POST1);
+            assert_eq!(shared_posts.len(), 1);
+            assert_eq!(shared_posts[0].id, POST2);
+            assert_eq!(shared_posts[0].space_id, Some(SPACE2));
+
+            assert!(Posts::get_shared_posts(POST2).is_empty());
         });
     }
 
@@ -3352,7 +4601,10 @@ mod tests {
         ExtBuilder::build_with_space().execute_with(|| {
             assert_ok!(_transfer_default_space_ownership()); // Transfer SpaceId 1 owned by ACCOUNT1 to ACCOUNT2
 
-            assert_eq!(SpaceOwnership::pending_space_owner(SPACE1).unwrap(), ACCOUNT2);
+            let transfer = SpaceOwnership::pending_transfer_by_space_id(SPACE1).unwrap();
+            assert_eq!(transfer.transfer_to, ACCOUNT2);
+            assert_eq!(transfer.price, None);
+            assert_eq!(transfer.expires_at, 1 + PendingTransferLifetime::get());
         });
     }
 
@@ -3396,7 +4648,7 @@ mod tests {
             assert_eq!(space.owner, ACCOUNT2);
 
             // Check whether storage state is correct
-            assert!(SpaceOwnership::pending_space_owner(SPACE1).is_none());
+            assert!(SpaceOwnership::pending_transfer_by_space_id(SPACE1).is_none());
         });
     }
 
@@ -3450,7 +4702,7 @@ mod tests {
             assert_eq!(space.owner, ACCOUNT1);
 
             // Check whether storage state is correct
-            assert!(SpaceOwnership::pending_space_owner(SPACE1).is_none());
+            assert!(SpaceOwnership::pending_transfer_by_space_id(SPACE1).is_none());
         });
     }
 
@@ -3466,7 +4718,7 @@ mod tests {
             assert_eq!(space.owner, ACCOUNT1);
 
             // Check whether storage state is correct
-            assert!(SpaceOwnership::pending_space_owner(SPACE1).is_none());
+            assert!(SpaceOwnership::pending_transfer_by_space_id(SPACE1).is_none());
         });
     }
 
@@ -3495,4 +4747,174 @@ mod tests {
             ), SpaceOwnershipError::<TestRuntime>::NotAllowedToRejectOwnershipTransfer); // Rejecting a transfer from ACCOUNT2
         });
     }
+
+    #[test]
+    fn transfer_space_ownership_should_fail_when_price_is_zero() {
+        ExtBuilder::build_with_space().execute_with(|| {
+            assert_noop!(
+                _transfer_space_ownership_for_price(None, None, None, Some(0)),
+                SpaceOwnershipError::<TestRuntime>::ZeroPriceProvided
+            );
+        });
+    }
+
+    #[test]
+    fn transfer_space_ownership_should_replace_previous_pending_transfer() {
+        ExtBuilder::build_with_space().execute_with(|| {
+            assert_ok!(_transfer_default_space_ownership());
+            let old_expires_at = SpaceOwnership::pending_transfer_by_space_id(SPACE1).unwrap().expires_at;
+
+            System::set_block_number(2);
+            assert_ok!(_transfer_space_ownership(None, None, Some(ACCOUNT3)));
+
+            let transfer = SpaceOwnership::pending_transfer_by_space_id(SPACE1).unwrap();
+            assert_eq!(transfer.transfer_to, ACCOUNT3);
+            assert_eq!(transfer.expires_at, 2 + PendingTransferLifetime::get());
+            assert!(SpaceOwnership::pending_transfers_by_expire_block(old_expires_at).is_empty());
+        });
+    }
+
+    #[test]
+    fn accept_pending_ownership_should_pay_price_and_move_handle_deposit() {
+        ExtBuilder::build_with_space().execute_with(|| {
+            let price = 30;
+            assert_ok!(_transfer_space_ownership_for_price(None, None, None, Some(price)));
+
+            let seller_balance = Balances::free_balance(ACCOUNT1);
+            let buyer_balance = Balances::free_balance(ACCOUNT2);
+
+            assert_ok!(_accept_pending_ownership_for_price(None, None, Some(price)));
+
+            assert_eq!(Spaces::space_by_id(SPACE1).unwrap().owner, ACCOUNT2);
+            // The handle deposit is moved from the old owner and reserved on the new one.
+            assert_eq!(Balances::free_balance(ACCOUNT1), seller_balance + price);
+            assert!(Balances::reserved_balance(ACCOUNT1).is_zero());
+            assert_eq!(Balances::free_balance(ACCOUNT2), buyer_balance - price);
+            assert_eq!(Balances::reserved_balance(ACCOUNT2), HANDLE_DEPOSIT);
+        });
+    }
+
+    #[test]
+    fn accept_pending_ownership_should_fail_when_buyer_cannot_pay_price() {
+        ExtBuilder::build_with_space().execute_with(|| {
+            assert_ok!(_transfer_space_ownership_for_price(None, None, None, Some(1000)));
+
+            assert_noop!(
+                _accept_pending_ownership_for_price(None, None, Some(1000)),
+                BalancesError::<TestRuntime>::InsufficientBalance
+            );
+            assert_eq!(Spaces::space_by_id(SPACE1).unwrap().owner, ACCOUNT1);
+            assert_eq!(Balances::reserved_balance(ACCOUNT1), HANDLE_DEPOSIT);
+        });
+    }
+
+    #[test]
+    fn accept_pending_ownership_should_fail_when_price_is_higher_than_expected() {
+        ExtBuilder::build_with_space().execute_with(|| {
+            assert_ok!(_transfer_space_ownership_for_price(None, None, None, Some(30)));
+
+            assert_noop!(
+                _accept_default_pending_ownership(),
+                SpaceOwnershipError::<TestRuntime>::PriceIsHigherThanExpected
+            );
+            assert_noop!(
+                _accept_pending_ownership_for_price(None, None, Some(29)),
+                SpaceOwnershipError::<TestRuntime>::PriceIsHigherThanExpected
+            );
+        });
+    }
+
+    #[test]
+    fn accept_pending_ownership_should_fail_when_offered_by_previous_owner() {
+        ExtBuilder::build_with_space().execute_with(|| {
+            assert_ok!(_transfer_default_space_ownership());
+
+            // Simulate an owner change that did not drop the pending transfer.
+            <SpaceById<TestRuntime>>::mutate(SPACE1, |space_opt| {
+                if let Some(space) = space_opt {
+                    space.owner = ACCOUNT3;
+                }
+            });
+
+            assert_noop!(
+                _accept_default_pending_ownership(),
+                SpaceOwnershipError::<TestRuntime>::TransferOfferedByPreviousOwner
+            );
+        });
+    }
+
+    #[test]
+    fn runtime_upgrade_should_migrate_old_pending_space_owners() {
+        ExtBuilder::build_with_space().execute_with(|| {
+            // An offer in the format used before transfers got an expiry and a price.
+            put_storage_value(
+                b"SpaceOwnershipModule",
+                b"PendingSpaceOwner",
+                &Twox64Concat::hash(&SPACE1.encode()),
+                ACCOUNT2,
+            );
+
+            SpaceOwnership::on_runtime_upgrade();
+
+            let transfer = SpaceOwnership::pending_transfer_by_space_id(SPACE1).unwrap();
+            let expires_at = System::block_number() + PendingTransferLifetime::get();
+            assert_eq!(transfer.created.account, ACCOUNT1);
+            assert_eq!(transfer.transfer_to, ACCOUNT2);
+            assert_eq!(transfer.price, None);
+            assert_eq!(transfer.expires_at, expires_at);
+            assert_eq!(SpaceOwnership::pending_transfers_by_expire_block(expires_at), vec![SPACE1]);
+
+            assert_ok!(_accept_default_pending_ownership());
+            assert_eq!(Spaces::space_by_id(SPACE1).unwrap().owner, ACCOUNT2);
+        });
+    }
+
+    #[test]
+    fn cancel_pending_ownership_should_work() {
+        ExtBuilder::build_with_space().execute_with(|| {
+            assert_ok!(_transfer_default_space_ownership());
+            let expires_at = SpaceOwnership::pending_transfer_by_space_id(SPACE1).unwrap().expires_at;
+
+            assert_ok!(_cancel_default_pending_ownership());
+
+            assert!(SpaceOwnership::pending_transfer_by_space_id(SPACE1).is_none());
+            assert!(SpaceOwnership::pending_transfers_by_expire_block(expires_at).is_empty());
+            assert_noop!(
+                _accept_default_pending_ownership(),
+                SpaceOwnershipError::<TestRuntime>::NoPendingTransferOnSpace
+            );
+        });
+    }
+
+    #[test]
+    fn cancel_pending_ownership_should_fail_when_account_is_not_space_owner() {
+        ExtBuilder::build_with_space().execute_with(|| {
+            assert_ok!(_transfer_default_space_ownership());
+
+            assert_noop!(
+                _cancel_pending_ownership(Some(Origin::signed(ACCOUNT2)), None),
+                SpacesError::<TestRuntime>::NotASpaceOwner
+            );
+        });
+    }
+
+    #[test]
+    fn pending_ownership_transfer_should_be_removed_when_expired() {
+        ExtBuilder::build_with_space().execute_with(|| {
+            assert_ok!(_transfer_default_space_ownership());
+            let expires_at = SpaceOwnership::pending_transfer_by_space_id(SPACE1).unwrap().expires_at;
+
+            SpaceOwnership::on_finalize(expires_at - 1);
+            assert!(SpaceOwnership::pending_transfer_by_space_id(SPACE1).is_some());
+
+            SpaceOwnership::on_finalize(expires_at);
+            assert!(SpaceOwnership::pending_transfer_by_space_id(SPACE1).is_none());
+            assert!(SpaceOwnership::pending_transfers_by_expire_block(expires_at).is_empty());
+
+            assert_noop!(
+                _accept_default_pending_ownership(),
+                SpaceOwnershipError::<TestRuntime>::NoPendingTransferOnSpace
+            );
+        });
+    }
 }
@@ -0,0 +1,23 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::too_many_arguments)]
+#![allow(clippy::unnecessary_mut_passed)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+use pallet_donations::{DonationRecipient, rpc::{FlatSuperComment, FlatSupporter}};
+use pallet_posts::PostId;
+
+sp_api::decl_runtime_apis! {
+    pub trait DonationsApi<AccountId, Balance> where
+        AccountId: Codec,
+        Balance: Codec
+    {
+        fn get_top_supporters(
+            recipient: DonationRecipient<AccountId>,
+            limit: u64,
+        ) -> Vec<FlatSupporter<AccountId, Balance>>;
+
+        fn get_super_comments(post_id: PostId) -> Vec<FlatSuperComment<AccountId, Balance>>;
+    }
+}
@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::Result;
+use jsonrpc_derive::rpc;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+use pallet_donations::{DonationRecipient, rpc::{FlatSuperComment, FlatSupporter}};
+use pallet_posts::PostId;
+use utils_rpc::map_rpc_error;
+pub use donations_runtime_api::DonationsApi as DonationsRuntimeApi;
+
+#[rpc]
+pub trait DonationsApi<BlockHash, AccountId, Balance> {
+    #[rpc(name = "donations_getTopSupporters")]
+    fn get_top_supporters(
+        &self,
+        at: Option<BlockHash>,
+        recipient: DonationRecipient<AccountId>,
+        limit: u64,
+    ) -> Result<Vec<FlatSupporter<AccountId, Balance>>>;
+
+    #[rpc(name = "donations_getSuperComments")]
+    fn get_super_comments(
+        &self,
+        at: Option<BlockHash>,
+        post_id: PostId,
+    ) -> Result<Vec<FlatSuperComment<AccountId, Balance>>>;
+}
+
+/// A struct that implements the [`DonationsApi`].
+pub struct Donations<C, M> {
+    client: Arc<C>,
+    _marker: std::marker::PhantomData<M>,
+}
+
+impl<C, M> Donations<C, M> {
+    /// Create new `Donations` with the given reference to the client.
+    pub fn new(client: Arc<C>) -> Self {
+        Self {
+            client,
+            _marker: Default::default(),
+        }
+    }
+}
+
+impl<C, Block, AccountId, Balance> DonationsApi<<Block as BlockT>::Hash, AccountId, Balance>
+    for Donations<C, Block>
+where
+    Block: BlockT,
+    AccountId: Codec,
+    Balance: Codec,
+    C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+    C::Api: DonationsRuntimeApi<Block, AccountId, Balance>,
+{
+    fn get_top_supporters(
+        &self,
+        at: Option<<Block as BlockT>::Hash>,
+        recipient: DonationRecipient<AccountId>,
+        limit: u64,
+    ) -> Result<Vec<FlatSupporter<AccountId, Balance>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.get_top_supporters(&at, recipient, limit).map_err(map_rpc_error)
+    }
+
+    fn get_super_comments(
+        &self,
+        at: Option<<Block as BlockT>::Hash>,
+        post_id: PostId,
+    ) -> Result<Vec<FlatSuperComment<AccountId, Balance>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.get_super_comments(&at, post_id).map_err(map_rpc_error)
+    }
+}
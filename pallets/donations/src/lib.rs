@@ -2,29 +2,44 @@
 //!
 //! The Donations module allows accounts to make donations (aka tips) to other accounts, spaces,
 //! or posts.
+//!
+//! A donation to a post is received by the post owner. A space can take a share of donations
+//! to its posts (see `DonationSettings::space_share`), that is sent to the space wallet
+//! within the same donation.
+//!
+//! A donation to a post can come with a comment (aka "super comment") that is created under
+//! this post in the same extrinsic. Such comments are linked to their donations, so they can be
+//! highlighted and ordered by a donated amount (see `rpc::get_super_comments`).
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use codec::{Encode, Decode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
 use sp_std::prelude::*;
-use sp_runtime::RuntimeDebug;
+use sp_runtime::{RuntimeDebug, Perbill, traits::{Saturating, Zero}};
 use frame_support::{
     decl_error, decl_event, decl_module, decl_storage, ensure, traits::Get,
     dispatch::{DispatchResult, DispatchError},
+    storage::{with_transaction, TransactionOutcome},
     traits::{Currency, ExistenceRequirement}
 };
 use frame_system::{self as system, ensure_signed};
 
-use pallet_posts::{Module as Posts, PostId};
+use df_traits::SpaceCleaner;
+use pallet_posts::{Module as Posts, PostId, PostExtension, Comment};
 use pallet_spaces::{Module as Spaces};
-use pallet_utils::{Content, WhoAndWhen, SpaceId};
+use pallet_utils::{Module as Utils, Content, WhoAndWhen, SpaceId};
 
-type BalanceOf<T> = <<T as pallet_utils::Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+pub mod rpc;
+
+pub type BalanceOf<T> = <<T as pallet_utils::Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
 
 pub type DonationId = u64;
 
 // TODO find a better name. Maybe DonationReason?
 #[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub enum DonationRecipient<AccountId> {
     Account(AccountId),
     Space(SpaceId),
@@ -38,7 +53,12 @@ pub struct Donation<T: Trait> {
     pub created: WhoAndWhen<T>,
     pub recipient: DonationRecipient<T::AccountId>, // TODO rename to 'reason'?
     pub donation_wallet: T::AccountId, // TODO rename to 'recipient_wallet' or 'recipient'?
+    /// The total donated amount, including the space share.
     pub amount: BalanceOf<T>,
+    /// A space wallet and the part of `amount` it received, if it's a donation to a post
+    /// in a space that takes a share of donations.
+    pub space_share: Option<(T::AccountId, BalanceOf<T>)>,
+    /// A comment created together with this donation.
     pub comment_id: Option<PostId>,
 }
 
@@ -48,8 +68,11 @@ pub struct DonationSettings<BalanceOf> {
     pub min_amount: Option<BalanceOf>,
     pub max_amount: Option<BalanceOf>,
 
-    // TODO think about 'post owner can receive donations' or 'who receives donations on post'?
-    // TODO % of post donations that space takes. 0% by default.
+    /// A share of donations to posts that is sent to the space wallet, while the rest
+    /// is received by a post owner. 0% by default.
+    /// It is only taken into account in the settings of a space.
+    pub space_share: Perbill,
+
     // TODO donation_posts_allowed: bool
 }
 
@@ -58,6 +81,7 @@ pub struct DonationSettingsUpdate<BalanceOf> {
     pub donations_allowed: Option<bool>,
     pub min_amount: Option<Option<BalanceOf>>,
     pub max_amount: Option<Option<BalanceOf>>,
+    pub space_share: Option<Perbill>,
 }
 
 pub trait Trait: system::Trait
@@ -104,6 +128,39 @@ decl_storage! {
         pub DonationSettingsByRecipient get(fn donation_settings_by_recipient):
             map hasher(blake2_128_concat) DonationRecipient<T::AccountId>
             => Option<DonationSettings<BalanceOf<T>>>;
+
+        /// The total amount of tokens donated to this recipient (key).
+        pub TotalDonatedToRecipient get(fn total_donated_to_recipient):
+            map hasher(blake2_128_concat) DonationRecipient<T::AccountId>
+            => BalanceOf<T>;
+
+        /// The total amount of tokens donated by this supporter (key).
+        pub TotalDonatedBySupporter get(fn total_donated_by_supporter):
+            map hasher(blake2_128_concat) T::AccountId
+            => BalanceOf<T>;
+
+        /// The total amount of tokens donated by a supporter (key 1) to a recipient (key 2).
+        pub TotalDonatedBySupporterToRecipient get(fn total_donated_by_supporter_to_recipient):
+            double_map
+                hasher(blake2_128_concat) T::AccountId,
+                hasher(blake2_128_concat) DonationRecipient<T::AccountId>
+            => BalanceOf<T>;
+
+        /// An id of a donation that a comment (key) was created with.
+        /// Such comments are called "super comments".
+        pub DonationIdByCommentId get(fn donation_id_by_comment_id):
+            map hasher(twox_64_concat) PostId
+            => Option<DonationId>;
+
+        /// Ids of super comments created with donations to a post (key).
+        pub SuperCommentIdsByPostId get(fn super_comment_ids_by_post_id):
+            map hasher(twox_64_concat) PostId
+            => Vec<PostId>;
+
+        /// Unique accounts that have donated to this recipient (key).
+        pub SupportersByRecipient get(fn supporters_by_recipient):
+            map hasher(blake2_128_concat) DonationRecipient<T::AccountId>
+            => Vec<T::AccountId>;
     }
 }
 
@@ -118,8 +175,11 @@ decl_event!(
             AccountId,
             // To which recipient it was donated.
             DonationRecipient,
-            // Amount of donated tokens.
-            BalanceOf
+            // Total amount of donated tokens.
+            BalanceOf,
+            // Wallets that received the donation and how much each of them received:
+            // a recipient wallet and, if a space takes a share, a space wallet.
+            Vec<(AccountId, BalanceOf)>
         ),
         DonationWalletUpdated(
             // Origin - who set a new wallet.
@@ -157,6 +217,8 @@ decl_error! {
         TooLargeDonation,
         /// A space, a post or an account doesn't accept donations based on its donation settings.
         DonationsAreDisabled,
+        /// A comment can be attached only to a donation to a post.
+        CommentAllowedOnlyOnPostDonation,
     }
 }
 
@@ -168,15 +230,28 @@ decl_module! {
     fn deposit_event() = default;
 
     /// Donate a certain `amount` of tokens to a `recipient` with an optional comment message.
-    #[weight = 10_000 + T::DbWeight::get().reads_writes(5, 4)]
+    /// If it's a post in a space that takes a share of donations, the donation is split
+    /// between the post owner and the space, and either both transfers succeed or none.
+    ///
+    /// If `comment_content` is provided, a comment to the post is created on behalf of
+    /// the supporter. The donation fails, if the comment cannot be created, and vice versa.
+    #[weight = 100_000 + T::DbWeight::get().reads_writes(18, 14)]
     pub fn donate(
         origin,
         recipient: DonationRecipient<T::AccountId>,
         amount: BalanceOf<T>,
-        _comment_content: Content
+        comment_content: Option<Content>
     ) -> DispatchResult {
         let supporter = ensure_signed(origin)?;
 
+        if let Some(content) = &comment_content {
+            ensure!(
+                matches!(recipient, DonationRecipient::Post(_)),
+                Error::<T>::CommentAllowedOnlyOnPostDonation
+            );
+            Utils::<T>::ensure_content_is_some(content)?;
+        }
+
         let settings = Self::resolve_donation_settings(recipient.clone())?;
 
         ensure!(settings.donations_allowed, Error::<T>::DonationsAreDisabled);
@@ -190,29 +265,50 @@ decl_module! {
         }
             
         let donation_wallet = Self::get_recipient_wallet(recipient.clone())?;
+        let space_share = Self::resolve_space_share(&recipient, amount)?;
         let donation_id = Self::next_donation_id();
 
         // TODO check settings.donation_posts_allowed to post as a root post to space.
-        // TODO create a comment under the post or a new post in DonationSpace
+
+        let mut transfers = vec![(donation_wallet.clone(), amount)];
+        if let Some((space_wallet, space_amount)) = space_share.clone() {
+            transfers[0].1 = amount.saturating_sub(space_amount);
+            transfers.push((space_wallet, space_amount));
+        }
+
+        let comment_id = Self::all_or_nothing(|| {
+            let comment_id = match (&recipient, comment_content) {
+                (DonationRecipient::Post(post_id), Some(content)) =>
+                    Some(Self::create_donation_comment(&supporter, *post_id, content)?),
+                _ => None,
+            };
+
+            Self::transfer_to_wallets(&supporter, &transfers)?;
+            Ok(comment_id)
+        })?;
 
         let donation = Donation {
             id: donation_id,
             created: WhoAndWhen::<T>::new(supporter.clone()),
             recipient: recipient.clone(),
-            donation_wallet: donation_wallet.clone(),
+            donation_wallet,
             amount,
-            comment_id: None // TODO put id of created comment
+            space_share,
+            comment_id,
         };
 
-        // Transfer donated tokens from a supporter to a donation wallet of this reason.
-        <T as pallet_utils::Trait>::Currency::transfer(&supporter, &donation_wallet, amount, ExistenceRequirement::KeepAlive)?;
+        if let (DonationRecipient::Post(post_id), Some(comment_id)) = (&recipient, comment_id) {
+            DonationIdByCommentId::insert(comment_id, donation_id);
+            SuperCommentIdsByPostId::mutate(post_id, |ids| ids.push(comment_id));
+        }
 
         DonationById::<T>::insert(donation_id, donation);
         DonationIdsBySupporter::<T>::mutate(supporter.clone(), |ids| ids.push(donation_id));
         DonationIdsByRecipient::<T>::mutate(recipient.clone(), |ids| ids.push(donation_id));
         NextDonationId::mutate(|n| { *n += 1; });
+        Self::add_to_donation_totals(&supporter, &recipient, amount);
 
-        Self::deposit_event(RawEvent::Donated(supporter, recipient, amount));
+        Self::deposit_event(RawEvent::Donated(supporter, recipient, amount, transfers));
         Ok(())
     }
 
@@ -260,7 +356,8 @@ decl_module! {
         let has_updates =
             update.donations_allowed.is_some() ||
             update.min_amount.is_some() ||
-            update.max_amount.is_some();
+            update.max_amount.is_some() ||
+            update.space_share.is_some();
 
         ensure!(has_updates, Error::<T>::NoUpdatesForDonationSettings);
 
@@ -292,6 +389,13 @@ decl_module! {
             }
         }
 
+        if let Some(space_share) = update.space_share {
+            if space_share != settings.space_share {
+                settings.space_share = space_share;
+                should_update = true;
+            }
+        }
+
         if should_update {
             DonationSettingsByRecipient::<T>::insert(recipient.clone(), settings);
             Self::deposit_event(RawEvent::DonationSettingsUpdated(who, recipient));
@@ -309,6 +413,7 @@ impl<BalanceOf> Default for DonationSettings<BalanceOf> {
             donations_allowed: true,
             min_amount: None,
             max_amount: None,
+            space_share: Perbill::zero(),
         }
     }
 }
@@ -347,7 +452,7 @@ impl<T: Trait> Module<T> {
                 Self::resolve_donation_settings(space_owner)
             },
             DonationRecipient::Post(post_id) => {
-                let post_owner = Self::resolve_space_owner_as_recipient(post_id)?;
+                let post_owner = Self::resolve_post_owner_as_recipient(post_id)?;
                 Self::resolve_donation_settings(post_owner)
             },
         }
@@ -372,12 +477,107 @@ impl<T: Trait> Module<T> {
                 Self::get_recipient_wallet(owner)
             },
             DonationRecipient::Post(post_id) => {
-                let owner = Self::resolve_space_owner_as_recipient(post_id)?;
+                let owner = Self::resolve_post_owner_as_recipient(post_id)?;
                 Self::get_recipient_wallet(owner)
             },
         }
     }
 
+    /// Get a space wallet and the part of `amount` that the space takes,
+    /// if the `recipient` is a post in a space with a non-zero `space_share`.
+    pub fn resolve_space_share(
+        recipient: &DonationRecipient<T::AccountId>,
+        amount: BalanceOf<T>,
+    ) -> Result<Option<(T::AccountId, BalanceOf<T>)>, DispatchError> {
+        let space_id = match recipient {
+            DonationRecipient::Post(post_id) => {
+                // A space of a comment is the space of its root post.
+                match Posts::<T>::require_post(*post_id)?.try_get_space_id() {
+                    Some(space_id) => space_id,
+                    None => return Ok(None),
+                }
+            },
+            _ => return Ok(None),
+        };
+
+        let space_recipient = DonationRecipient::Space(space_id);
+        let space_amount = Self::resolve_donation_settings(space_recipient.clone())?.space_share * amount;
+        if space_amount.is_zero() {
+            return Ok(None)
+        }
+
+        let space_wallet = Self::get_recipient_wallet(space_recipient)?;
+        Ok(Some((space_wallet, space_amount)))
+    }
+
+    /// Run `f` and revert all the storage changes it made if it fails.
+    fn all_or_nothing<R>(f: impl FnOnce() -> Result<R, DispatchError>) -> Result<R, DispatchError> {
+        with_transaction(|| {
+            let res = f();
+            if res.is_ok() {
+                TransactionOutcome::Commit(res)
+            } else {
+                TransactionOutcome::Rollback(res)
+            }
+        })
+    }
+
+    /// Transfer tokens from a `supporter` to every wallet in `transfers`.
+    /// Should be called within `all_or_nothing`, so no transfers are made if any of them fails.
+    fn transfer_to_wallets(
+        supporter: &T::AccountId,
+        transfers: &[(T::AccountId, BalanceOf<T>)],
+    ) -> DispatchResult {
+        for (wallet, amount) in transfers.iter() {
+            <T as pallet_utils::Trait>::Currency::transfer(
+                supporter, wallet, *amount, ExistenceRequirement::KeepAlive
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Create a comment by the `supporter` to the post that receives their donation.
+    /// If the post is a comment itself, the new comment is a reply to it.
+    fn create_donation_comment(
+        supporter: &T::AccountId,
+        post_id: PostId,
+        content: Content,
+    ) -> Result<PostId, DispatchError> {
+        let post = Posts::<T>::require_post(post_id)?;
+
+        let comment_ext = match post.extension {
+            PostExtension::Comment(comment) => Comment {
+                parent_id: Some(post_id),
+                root_post_id: comment.root_post_id,
+            },
+            _ => Comment {
+                parent_id: None,
+                root_post_id: post_id,
+            },
+        };
+
+        Posts::<T>::do_create_post(supporter.clone(), None, PostExtension::Comment(comment_ext), content)
+    }
+
+    /// Add a donated `amount` to the totals of a supporter, a recipient and their pair.
+    fn add_to_donation_totals(
+        supporter: &T::AccountId,
+        recipient: &DonationRecipient<T::AccountId>,
+        amount: BalanceOf<T>,
+    ) {
+        TotalDonatedToRecipient::<T>::mutate(recipient, |total| *total = total.saturating_add(amount));
+        TotalDonatedBySupporter::<T>::mutate(supporter, |total| *total = total.saturating_add(amount));
+
+        let is_new_supporter = !TotalDonatedBySupporterToRecipient::<T>::contains_key(supporter, recipient);
+        TotalDonatedBySupporterToRecipient::<T>::mutate(supporter, recipient, |total| {
+            *total = total.saturating_add(amount)
+        });
+
+        if is_new_supporter {
+            SupportersByRecipient::<T>::mutate(recipient, |supporters| supporters.push(supporter.clone()));
+        }
+    }
+
     /// Checks if `maybe_owner` can manage / is an owner of a `recipient`.
     pub fn ensure_recipient_manager(
         maybe_owner: T::AccountId,
@@ -400,3 +600,21 @@ impl<T: Trait> Module<T> {
         Ok(())
     }
 }
+
+impl<T: Trait> SpaceCleaner for Module<T> {
+    fn clean_up_space(space_id: SpaceId, limit: u32) -> u32 {
+        if limit == 0 {
+            return 0;
+        }
+
+        let recipient = DonationRecipient::Space(space_id);
+        let has_settings = Self::donation_settings_by_recipient(&recipient).is_some();
+        let has_wallet = Self::donation_wallet_by_recipient(&recipient).is_some();
+
+        // The settings and the wallet of a space are removed at once, so they count as one item.
+        DonationSettingsByRecipient::<T>::remove(&recipient);
+        DonationWalletByRecipient::<T>::remove(&recipient);
+
+        (has_settings || has_wallet) as u32
+    }
+}
@@ -0,0 +1,75 @@
+use codec::{Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_runtime::RuntimeDebug;
+use sp_std::prelude::*;
+
+use pallet_posts::{Module as Posts, PostId};
+
+use crate::{BalanceOf, DonationId, DonationRecipient, Module, Trait};
+
+/// An account that supported a recipient and how much it has donated to them in total.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct FlatSupporter<AccountId, Balance> {
+    pub account: AccountId,
+    pub total_donated: Balance,
+}
+
+/// A comment created together with a donation to a post.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct FlatSuperComment<AccountId, Balance> {
+    pub comment_id: PostId,
+    pub donation_id: DonationId,
+    pub supporter: AccountId,
+    pub amount: Balance,
+}
+
+impl<T: Trait> Module<T> {
+
+    /// Get up to `limit` accounts that have donated the most to a `recipient`,
+    /// starting from the biggest supporter.
+    pub fn get_top_supporters(
+        recipient: DonationRecipient<T::AccountId>,
+        limit: u64,
+    ) -> Vec<FlatSupporter<T::AccountId, BalanceOf<T>>> {
+        let mut supporters: Vec<FlatSupporter<T::AccountId, BalanceOf<T>>> =
+            Self::supporters_by_recipient(&recipient).into_iter()
+                .map(|account| FlatSupporter {
+                    total_donated: Self::total_donated_by_supporter_to_recipient(&account, &recipient),
+                    account,
+                })
+                .collect();
+
+        supporters.sort_by(|a, b| b.total_donated.cmp(&a.total_donated));
+        supporters.truncate(limit as usize);
+        supporters
+    }
+
+    /// Get super comments created with donations to a post, starting from the one
+    /// with the biggest donation. Deleted comments and tombstones are skipped.
+    pub fn get_super_comments(post_id: PostId) -> Vec<FlatSuperComment<T::AccountId, BalanceOf<T>>> {
+        let mut super_comments: Vec<FlatSuperComment<T::AccountId, BalanceOf<T>>> =
+            Self::super_comment_ids_by_post_id(post_id).into_iter()
+                .filter(|comment_id| Posts::<T>::post_by_id(comment_id).is_some()
+                    && !Posts::<T>::is_post_deleted(comment_id))
+                .filter_map(|comment_id| {
+                    let donation = Self::donation_id_by_comment_id(comment_id)
+                        .and_then(Self::donation_by_id)?;
+
+                    Some(FlatSuperComment {
+                        comment_id,
+                        donation_id: donation.id,
+                        supporter: donation.created.account,
+                        amount: donation.amount,
+                    })
+                })
+                .collect();
+
+        super_comments.sort_by(|a, b| b.amount.cmp(&a.amount));
+        super_comments
+    }
+}
@@ -6,6 +6,12 @@
 //! Currently, only sudo account can add, update and remove faucets.
 //! But this can be changed in the future to allow anyone else
 //! to set up new faucets for their needs.
+//!
+//! Sudo can also set a refill policy for a faucet, so the faucet will be topped up
+//! from the treasury account of `pallet_utils` once its balance drops below a threshold.
+//!
+//! If sudo sets claim rules for a faucet, any account that satisfies these rules
+//! can claim tokens from this faucet by itself with an unsigned (fee-free) transaction.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -14,12 +20,17 @@ use frame_support::{
     decl_error, decl_event, decl_module, decl_storage,
     dispatch::{DispatchError, DispatchResult},
     ensure,
+    storage::migration::{get_storage_value, put_storage_value, StorageIterator},
     traits::{Currency, ExistenceRequirement, Get},
-    weights::Pays,
+    weights::{Pays, Weight},
 };
-use frame_system::{self as system, ensure_root, ensure_signed};
+use frame_system::{self as system, ensure_none, ensure_root, ensure_signed};
 use sp_runtime::RuntimeDebug;
-use sp_runtime::traits::{Saturating, Zero};
+use sp_runtime::traits::{Hash, Saturating, ValidateUnsigned, Zero};
+use sp_runtime::transaction_validity::{
+    InvalidTransaction, TransactionLongevity, TransactionPriority,
+    TransactionSource, TransactionValidity, ValidTransaction,
+};
 use sp_std::{
     collections::btree_set::BTreeSet,
     iter::FromIterator,
@@ -41,28 +52,113 @@ pub struct Faucet<T: Trait> {
     pub period_limit: BalanceOf<T>,
     pub drip_limit: BalanceOf<T>,
 
+    /// How many tokens a single recipient can receive from this faucet per period.
+    pub recipient_period_limit: BalanceOf<T>,
+
+    /// How many blocks should pass between two drips to the same recipient.
+    /// Zero means no cooldown.
+    pub recipient_cooldown: T::BlockNumber,
+
     // State
     pub next_period_at: T::BlockNumber,
     pub dripped_in_current_period: BalanceOf<T>,
 }
 
+/// `Faucet` as it was stored before the recipient limits were added.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+struct OldFaucet<T: Trait> {
+    enabled: bool,
+    period: T::BlockNumber,
+    period_limit: BalanceOf<T>,
+    drip_limit: BalanceOf<T>,
+    next_period_at: T::BlockNumber,
+    dripped_in_current_period: BalanceOf<T>,
+}
+
 #[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
 pub struct FaucetUpdate<BlockNumber, Balance> {
     pub enabled: Option<bool>,
     pub period: Option<BlockNumber>,
     pub period_limit: Option<Balance>,
     pub drip_limit: Option<Balance>,
+    pub recipient_period_limit: Option<Balance>,
+    pub recipient_cooldown: Option<BlockNumber>,
 }
 
-type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+/// What a recipient has received from a particular faucet.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+pub struct RecipientDrips<T: Trait> {
+    pub last_drip_at: T::BlockNumber,
 
-/// The pallet's configuration trait.
-pub trait Trait: system::Trait {
+    /// The end of the faucet period in which `received_in_current_period` was counted.
+    pub period_ends_at: T::BlockNumber,
+    pub received_in_current_period: BalanceOf<T>,
+    pub total_received: BalanceOf<T>,
+}
+
+/// Settings and state of an automatic refill of a faucet from the treasury.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+pub struct RefillPolicy<T: Trait> {
+
+    // Settings
+
+    /// Refill the faucet when its free balance drops below this threshold.
+    pub threshold: BalanceOf<T>,
+
+    /// The free balance the faucet should have after a refill.
+    pub target_balance: BalanceOf<T>,
+
+    pub period: T::BlockNumber,
+
+    /// How many tokens can be moved from the treasury to the faucet per period.
+    pub period_cap: BalanceOf<T>,
+
+    // State
+    pub next_period_at: T::BlockNumber,
+    pub refilled_in_current_period: BalanceOf<T>,
+}
 
+/// Who can claim tokens from a faucet by themselves and how many tokens they get per claim.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+pub struct ClaimRules<Balance> {
+    pub claim_amount: Balance,
+
+    // Social rules. An account should satisfy all of them to be eligible.
+    pub min_reputation: u32,
+    pub profile_required: bool,
+    pub min_following_spaces: u16,
+
+    /// Whether a root-issued claim code makes an account eligible regardless of the social rules.
+    /// A code is issued for one recipient (see `Module::claim_code_hash`), so anyone who sees it
+    /// in the transaction pool cannot claim tokens with it to another account.
+    pub accept_claim_codes: bool,
+}
+
+/// Why a recipient cannot receive a drip, see `Module::check_recipient_limits`.
+#[derive(Clone, Copy, Eq, PartialEq, RuntimeDebug)]
+pub enum DripRejectionReason {
+    RecipientPeriodLimitReached,
+    RecipientCooldownNotPassed,
+}
+
+type BalanceOf<T> = <<T as pallet_utils::Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+/// The pallet's configuration trait.
+pub trait Trait: system::Trait
+    + pallet_utils::Trait
+    + pallet_profiles::Trait
+{
     /// The overarching event type.
     type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
 
-    type Currency: Currency<Self::AccountId>;
+    /// The priority of unsigned claim transactions in the transaction pool.
+    type ClaimPriority: Get<TransactionPriority>;
+
+    /// For how many blocks an unsigned claim transaction stays valid in the transaction pool.
+    type ClaimLongevity: Get<TransactionLongevity>;
+
+    /// How many faucets with a refill policy can be checked for a refill in one block.
+    type MaxFaucetsToRefillPerBlock: Get<u16>;
 }
 
 decl_storage! {
@@ -72,6 +168,37 @@ decl_storage! {
         pub FaucetByAccount get(fn faucet_by_account):
             map hasher(twox_64_concat) T::AccountId // Faucet account
             => Option<Faucet<T>>;
+
+        /// Get what a recipient has received from a faucet.
+        pub DripsByFaucetAndRecipient get(fn drips_by_faucet_and_recipient):
+            double_map
+                hasher(twox_64_concat) T::AccountId, // Faucet account
+                hasher(twox_64_concat) T::AccountId  // Recipient account
+            => Option<RecipientDrips<T>>;
+
+        /// Get a refill policy of a faucet by the faucet account id.
+        pub RefillPolicyByFaucet get(fn refill_policy_by_faucet):
+            map hasher(twox_64_concat) T::AccountId // Faucet account
+            => Option<RefillPolicy<T>>;
+
+        /// Accounts of the faucets that have a refill policy, in the order they are checked for a refill.
+        pub FaucetsWithRefillPolicy get(fn faucets_with_refill_policy): Vec<T::AccountId>;
+
+        /// An index in `FaucetsWithRefillPolicy` of the faucet to check for a refill next.
+        pub NextFaucetToRefill get(fn next_faucet_to_refill): u32;
+
+        /// Get the rules of self-claiming tokens from a faucet by the faucet account id.
+        pub ClaimRulesByFaucet get(fn claim_rules_by_faucet):
+            map hasher(twox_64_concat) T::AccountId // Faucet account
+            => Option<ClaimRules<BalanceOf<T>>>;
+
+        /// Whether a one-time claim code with this hash can be used to claim from a faucet.
+        /// The hash is made of a code and a recipient account, see `Module::claim_code_hash`.
+        pub ClaimCodeIsValid get(fn claim_code_is_valid):
+            double_map
+                hasher(twox_64_concat) T::AccountId, // Faucet account
+                hasher(identity) T::Hash             // Hash of a claim code and its recipient
+            => bool;
     }
 }
 
@@ -88,6 +215,20 @@ decl_event!(
             AccountId, // Recipient account
             Balance    // Amount dripped
         ),
+        RefillPolicySet(AccountId),
+        RefillPolicyRemoved(AccountId),
+        FaucetRefilled(
+            AccountId, // Faucet account
+            AccountId, // Treasury account
+            Balance    // Amount refilled
+        ),
+        ClaimRulesUpdated(AccountId),
+        ClaimCodesAdded(AccountId, u32),
+        Claimed(
+            AccountId, // Faucet account
+            AccountId, // Recipient account
+            bool       // Whether a claim code was used
+        ),
     }
 );
 
@@ -107,21 +248,43 @@ decl_error! {
         ZeroPeriodProvided,
         ZeroPeriodLimitProvided,
         ZeroDripLimitProvided,
+        ZeroRecipientPeriodLimitProvided,
         ZeroDripAmountProvided,
         
         PeriodLimitReached,
         DripLimitReached,
+        RecipientPeriodLimitReached,
+        RecipientCooldownNotPassed,
+
+        RefillPolicyNotFound,
+        ZeroRefillPeriodCapProvided,
+        RefillTargetBalanceNotAboveThreshold,
+
+        ClaimingIsDisabled,
+        NotEligibleToClaim,
+        InvalidClaimCode,
+        NoClaimCodesProvided,
     }
 }
 
 decl_module! {
     pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        const MaxFaucetsToRefillPerBlock: u16 = T::MaxFaucetsToRefillPerBlock::get();
+
         // Initializing errors
         type Error = Error<T>;
 
         // Initializing events
         fn deposit_event() = default;
 
+        fn on_runtime_upgrade() -> Weight {
+            Self::migrate_faucets_to_recipient_limits()
+        }
+
+        fn on_initialize(block_number: T::BlockNumber) -> Weight {
+            Self::refill_faucets(block_number)
+        }
+
         #[weight = 50_000 + T::DbWeight::get().reads_writes(2, 1)]
         pub fn add_faucet(
             origin,
@@ -129,6 +292,8 @@ decl_module! {
             period: T::BlockNumber,
             period_limit: BalanceOf<T>,
             drip_limit: BalanceOf<T>,
+            recipient_period_limit: BalanceOf<T>,
+            recipient_cooldown: T::BlockNumber,
         ) -> DispatchResult {
 
             ensure_root(origin.clone())?;
@@ -136,6 +301,7 @@ decl_module! {
             Self::ensure_period_not_zero(period)?;
             Self::ensure_period_limit_not_zero(period_limit)?;
             Self::ensure_drip_limit_not_zero(drip_limit)?;
+            Self::ensure_recipient_period_limit_not_zero(recipient_period_limit)?;
 
             ensure!(
                 Self::faucet_by_account(&faucet).is_none(),
@@ -151,7 +317,9 @@ decl_module! {
             let new_faucet = Faucet::<T>::new(
                 period,
                 period_limit,
-                drip_limit
+                drip_limit,
+                recipient_period_limit,
+                recipient_cooldown
             );
 
             FaucetByAccount::<T>::insert(faucet.clone(), new_faucet);
@@ -172,7 +340,9 @@ decl_module! {
                 update.enabled.is_some() ||
                 update.period.is_some() ||
                 update.period_limit.is_some() ||
-                update.drip_limit.is_some();
+                update.drip_limit.is_some() ||
+                update.recipient_period_limit.is_some() ||
+                update.recipient_cooldown.is_some();
 
             ensure!(has_updates, Error::<T>::NoUpdatesProvided);
 
@@ -215,6 +385,22 @@ decl_module! {
                 }
             }
 
+            if let Some(recipient_period_limit) = update.recipient_period_limit {
+                Self::ensure_recipient_period_limit_not_zero(recipient_period_limit)?;
+
+                if recipient_period_limit != settings.recipient_period_limit {
+                    settings.recipient_period_limit = recipient_period_limit;
+                    should_update = true;
+                }
+            }
+
+            if let Some(recipient_cooldown) = update.recipient_cooldown {
+                if recipient_cooldown != settings.recipient_cooldown {
+                    settings.recipient_cooldown = recipient_cooldown;
+                    should_update = true;
+                }
+            }
+
             ensure!(should_update, Error::<T>::NothingToUpdate);
 
             FaucetByAccount::<T>::insert(faucet.clone(), settings);
@@ -235,14 +421,74 @@ decl_module! {
             let unique_faucets = BTreeSet::from_iter(faucets.iter());
             for faucet in unique_faucets.iter() {
                 FaucetByAccount::<T>::remove(faucet);
+                DripsByFaucetAndRecipient::<T>::remove_prefix(faucet);
+                Self::remove_refill_policy_of(faucet);
+                ClaimRulesByFaucet::<T>::remove(faucet);
+                ClaimCodeIsValid::<T>::remove_prefix(faucet);
             }
 
             Self::deposit_event(RawEvent::FaucetsRemoved(faucets));
             Ok(())
         }
 
+        #[weight = 50_000 + T::DbWeight::get().reads_writes(2, 1)]
+        pub fn set_refill_policy(
+            origin,
+            faucet: T::AccountId,
+            threshold: BalanceOf<T>,
+            target_balance: BalanceOf<T>,
+            period: T::BlockNumber,
+            period_cap: BalanceOf<T>,
+        ) -> DispatchResult {
+
+            ensure_root(origin)?;
+
+            Self::require_faucet(&faucet)?;
+            Self::ensure_period_not_zero(period)?;
+            ensure!(period_cap > Zero::zero(), Error::<T>::ZeroRefillPeriodCapProvided);
+            ensure!(target_balance > threshold, Error::<T>::RefillTargetBalanceNotAboveThreshold);
+
+            // Keep the state of the current period, so the cap cannot be bypassed
+            // by setting the policy again.
+            let policy = match Self::refill_policy_by_faucet(&faucet) {
+                Some(existing) => RefillPolicy {
+                    threshold,
+                    target_balance,
+                    period,
+                    period_cap,
+                    ..existing
+                },
+                None => {
+                    FaucetsWithRefillPolicy::<T>::mutate(|faucets| faucets.push(faucet.clone()));
+                    RefillPolicy::<T>::new(threshold, target_balance, period, period_cap)
+                },
+            };
+
+            RefillPolicyByFaucet::<T>::insert(faucet.clone(), policy);
+            Self::deposit_event(RawEvent::RefillPolicySet(faucet));
+            Ok(())
+        }
+
+        #[weight = 20_000 + T::DbWeight::get().reads_writes(1, 1)]
+        pub fn remove_refill_policy(
+            origin,
+            faucet: T::AccountId
+        ) -> DispatchResult {
+
+            ensure_root(origin)?;
+
+            ensure!(
+                RefillPolicyByFaucet::<T>::contains_key(&faucet),
+                Error::<T>::RefillPolicyNotFound
+            );
+
+            Self::remove_refill_policy_of(&faucet);
+            Self::deposit_event(RawEvent::RefillPolicyRemoved(faucet));
+            Ok(())
+        }
+
         #[weight = (
-            50_000 + T::DbWeight::get().reads_writes(2, 2),
+            50_000 + T::DbWeight::get().reads_writes(3, 3),
             
             // TODO Replace with Ok(Pays::No.into())
             // See https://github.com/substrate-developer-hub/substrate-node-template/commit/6546b15634bf088e8faee806b5cf266621412889#diff-657cb55f3d39058f730b46f7c84f90698ad43b3ab5c1aa8789a435a230c77f19R106
@@ -255,55 +501,247 @@ decl_module! {
         ) -> DispatchResult {
             let faucet = ensure_signed(origin)?;
 
-            // Validate input values
-            ensure!(faucet != recipient, Error::<T>::RecipientEqualsFaucet);
-            ensure!(amount > Zero::zero(), Error::<T>::ZeroDripAmountProvided);
+            let (settings, mut drips) = Self::ensure_can_drip(&faucet, &recipient, amount)?;
 
-            let mut settings = Self::require_faucet(&faucet)?;
-            ensure!(settings.enabled, Error::<T>::FaucetDisabled);
-            ensure!(amount <= settings.drip_limit, Error::<T>::DripLimitReached);
+            let current_block = <system::Module<T>>::block_number();
+            Self::check_recipient_limits(&settings, &mut drips, current_block, amount)
+                .map_err(Error::<T>::from)?;
 
-            let faucet_balance = T::Currency::free_balance(&faucet);
-            ensure!(amount <= faucet_balance, Error::<T>::NotEnoughFreeBalanceOnFaucet);
+            Self::do_drip(faucet, recipient, amount, settings, drips)
+        }
 
-            let current_block = <system::Module<T>>::block_number();
+        #[weight = 50_000 + T::DbWeight::get().reads_writes(1, 1)]
+        pub fn set_claim_rules(
+            origin,
+            faucet: T::AccountId,
+            rules_opt: Option<ClaimRules<BalanceOf<T>>>
+        ) -> DispatchResult {
 
-            if settings.next_period_at <= current_block {
-                // Move to the next period and reset the period stats
-                settings.next_period_at = current_block.saturating_add(settings.period);
-                settings.dripped_in_current_period = Zero::zero();
+            ensure_root(origin)?;
+
+            let settings = Self::require_faucet(&faucet)?;
+
+            match rules_opt {
+                Some(rules) => {
+                    ensure!(rules.claim_amount > Zero::zero(), Error::<T>::ZeroDripAmountProvided);
+                    ensure!(rules.claim_amount <= settings.drip_limit, Error::<T>::DripLimitReached);
+                    ClaimRulesByFaucet::<T>::insert(&faucet, rules);
+                },
+                None => ClaimRulesByFaucet::<T>::remove(&faucet),
             }
 
-            // Calculate have many tokens still can be dripped in the current period
-            let tokens_left_in_current_period = settings.period_limit
-                .saturating_sub(settings.dripped_in_current_period);
+            Self::deposit_event(RawEvent::ClaimRulesUpdated(faucet));
+            Ok(())
+        }
 
-            ensure!(amount <= tokens_left_in_current_period, Error::<T>::PeriodLimitReached);
+        /// Add one-time claim codes to a faucet. Every hash should be made with `claim_code_hash`
+        /// off-chain, so a code can be used only by the recipient it was issued for.
+        #[weight = 20_000 + T::DbWeight::get().reads_writes(1, 0) + 20_000 * code_hashes.len() as u64]
+        pub fn add_claim_codes(
+            origin,
+            faucet: T::AccountId,
+            code_hashes: Vec<T::Hash>
+        ) -> DispatchResult {
 
-            T::Currency::transfer(
-                &faucet,
-                &recipient,
-                amount,
-                ExistenceRequirement::KeepAlive
-            )?;
+            ensure_root(origin)?;
 
-            settings.dripped_in_current_period = amount
-                .saturating_add(settings.dripped_in_current_period);
+            ensure!(!code_hashes.is_empty(), Error::<T>::NoClaimCodesProvided);
+            Self::require_faucet(&faucet)?;
 
-            FaucetByAccount::<T>::insert(&faucet, settings);
+            let unique_hashes = BTreeSet::from_iter(code_hashes.iter());
+            for code_hash in unique_hashes.iter() {
+                ClaimCodeIsValid::<T>::insert(&faucet, code_hash, true);
+            }
 
-            Self::deposit_event(RawEvent::Dripped(faucet, recipient, amount));
+            Self::deposit_event(RawEvent::ClaimCodesAdded(faucet, unique_hashes.len() as u32));
+            Ok(())
+        }
+
+        /// Claim tokens from a faucet to a recipient account that satisfies the faucet claim rules.
+        /// This is an unsigned transaction, so a new account does not need tokens to claim them.
+        #[weight = (
+            50_000 + T::DbWeight::get().reads_writes(6, 4),
+            Pays::No
+        )]
+        pub fn claim(
+            origin,
+            faucet: T::AccountId,
+            recipient: T::AccountId,
+            claim_code: Option<Vec<u8>>
+        ) -> DispatchResult {
+            ensure_none(origin)?;
+
+            let (rules, settings, drips, code_hash_opt) =
+                Self::ensure_can_claim(&faucet, &recipient, &claim_code)?;
+
+            if let Some(code_hash) = code_hash_opt {
+                ClaimCodeIsValid::<T>::remove(&faucet, code_hash);
+            }
+
+            Self::do_drip(faucet.clone(), recipient.clone(), rules.claim_amount, settings, drips)?;
+
+            Self::deposit_event(RawEvent::Claimed(faucet, recipient, code_hash_opt.is_some()));
             Ok(())
         }
     }
 }
 
+impl<T: Trait> ValidateUnsigned for Module<T> {
+    type Call = Call<T>;
+
+    fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+        if let Call::claim(faucet, recipient, claim_code) = call {
+            let (_, _, drips, code_hash_opt) = Self::ensure_can_claim(faucet, recipient, claim_code)
+                .map_err(|_| InvalidTransaction::Call)?;
+
+            let mut validity = ValidTransaction::with_tag_prefix("FaucetsClaim")
+                .priority(T::ClaimPriority::get())
+                // Only one claim of a recipient per faucet and its drip stats can be in the pool.
+                .and_provides((faucet, recipient, drips.period_ends_at, drips.total_received))
+                .longevity(T::ClaimLongevity::get())
+                .propagate(true);
+
+            // A claim code can be used only once, so the pool keeps only one claim with it.
+            if let Some(code_hash) = code_hash_opt {
+                validity = validity.and_provides((faucet, code_hash));
+            }
+
+            validity.build()
+        } else {
+            InvalidTransaction::Call.into()
+        }
+    }
+}
+
 impl<T: Trait> Module<T> {
 
     pub fn require_faucet(faucet: &T::AccountId) -> Result<Faucet<T>, DispatchError> {
         Ok(Self::faucet_by_account(faucet).ok_or(Error::<T>::FaucetNotFound)?)
     }
 
+    /// The hash under which a claim code issued for a `recipient` is stored.
+    pub fn claim_code_hash(code: &[u8], recipient: &T::AccountId) -> T::Hash {
+        T::Hashing::hash_of(&(code, recipient))
+    }
+
+    /// Check the faucet limits and return the faucet and recipient stats prepared for the drip.
+    /// Recipient limits should be checked separately with `check_recipient_limits`.
+    fn ensure_can_drip(
+        faucet: &T::AccountId,
+        recipient: &T::AccountId,
+        amount: BalanceOf<T>,
+    ) -> Result<(Faucet<T>, RecipientDrips<T>), DispatchError> {
+
+        // Validate input values
+        ensure!(faucet != recipient, Error::<T>::RecipientEqualsFaucet);
+        ensure!(amount > Zero::zero(), Error::<T>::ZeroDripAmountProvided);
+
+        let mut settings = Self::require_faucet(faucet)?;
+        ensure!(settings.enabled, Error::<T>::FaucetDisabled);
+        ensure!(amount <= settings.drip_limit, Error::<T>::DripLimitReached);
+
+        let faucet_balance = T::Currency::free_balance(faucet);
+        ensure!(amount <= faucet_balance, Error::<T>::NotEnoughFreeBalanceOnFaucet);
+
+        let current_block = <system::Module<T>>::block_number();
+
+        if settings.next_period_at <= current_block {
+            // Move to the next period and reset the period stats
+            settings.next_period_at = current_block.saturating_add(settings.period);
+            settings.dripped_in_current_period = Zero::zero();
+        }
+
+        // Calculate have many tokens still can be dripped in the current period
+        let tokens_left_in_current_period = settings.period_limit
+            .saturating_sub(settings.dripped_in_current_period);
+
+        ensure!(amount <= tokens_left_in_current_period, Error::<T>::PeriodLimitReached);
+
+        let drips = Self::drips_by_faucet_and_recipient(faucet, recipient)
+            .unwrap_or_else(|| RecipientDrips::<T>::new(settings.next_period_at));
+
+        Ok((settings, drips))
+    }
+
+    fn do_drip(
+        faucet: T::AccountId,
+        recipient: T::AccountId,
+        amount: BalanceOf<T>,
+        mut settings: Faucet<T>,
+        mut drips: RecipientDrips<T>,
+    ) -> DispatchResult {
+
+        T::Currency::transfer(
+            &faucet,
+            &recipient,
+            amount,
+            ExistenceRequirement::KeepAlive
+        )?;
+
+        settings.dripped_in_current_period = amount
+            .saturating_add(settings.dripped_in_current_period);
+
+        drips.last_drip_at = <system::Module<T>>::block_number();
+        drips.received_in_current_period = amount
+            .saturating_add(drips.received_in_current_period);
+        drips.total_received = amount.saturating_add(drips.total_received);
+
+        FaucetByAccount::<T>::insert(&faucet, settings);
+        DripsByFaucetAndRecipient::<T>::insert(&faucet, &recipient, drips);
+
+        Self::deposit_event(RawEvent::Dripped(faucet, recipient, amount));
+        Ok(())
+    }
+
+    /// Check that the recipient can claim tokens from the faucet by themselves.
+    /// Returns the hash of a claim code if the recipient is eligible because of this code.
+    fn ensure_can_claim(
+        faucet: &T::AccountId,
+        recipient: &T::AccountId,
+        claim_code: &Option<Vec<u8>>,
+    ) -> Result<(ClaimRules<BalanceOf<T>>, Faucet<T>, RecipientDrips<T>, Option<T::Hash>), DispatchError> {
+
+        let rules = Self::claim_rules_by_faucet(faucet).ok_or(Error::<T>::ClaimingIsDisabled)?;
+
+        let code_hash_opt = match claim_code {
+            Some(code) => {
+                let code_hash = Self::claim_code_hash(code, recipient);
+                ensure!(
+                    rules.accept_claim_codes && Self::claim_code_is_valid(faucet, code_hash),
+                    Error::<T>::InvalidClaimCode
+                );
+                Some(code_hash)
+            },
+            None => {
+                ensure!(Self::is_socially_eligible(recipient, &rules), Error::<T>::NotEligibleToClaim);
+                None
+            },
+        };
+
+        let (settings, mut drips) = Self::ensure_can_drip(faucet, recipient, rules.claim_amount)?;
+
+        let current_block = <system::Module<T>>::block_number();
+        Self::check_recipient_limits(&settings, &mut drips, current_block, rules.claim_amount)
+            .map_err(Error::<T>::from)?;
+
+        Ok((rules, settings, drips, code_hash_opt))
+    }
+
+    fn is_socially_eligible(account: &T::AccountId, rules: &ClaimRules<BalanceOf<T>>) -> bool {
+        let (reputation, has_profile, following_spaces_count) =
+            pallet_profiles::Module::<T>::social_account_by_id(account)
+                .map(|social_account| (
+                    social_account.reputation,
+                    social_account.profile.is_some(),
+                    social_account.following_spaces_count
+                ))
+                .unwrap_or((0, false, 0));
+
+        reputation >= rules.min_reputation
+            && (!rules.profile_required || has_profile)
+            && following_spaces_count >= rules.min_following_spaces
+    }
+
     fn ensure_period_not_zero(period: T::BlockNumber) -> DispatchResult {
         ensure!(period > Zero::zero(), Error::<T>::ZeroPeriodProvided);
         Ok(())
@@ -318,6 +756,170 @@ impl<T: Trait> Module<T> {
         ensure!(drip_limit > Zero::zero(), Error::<T>::ZeroDripLimitProvided);
         Ok(())
     }
+
+    fn ensure_recipient_period_limit_not_zero(recipient_period_limit: BalanceOf<T>) -> DispatchResult {
+        ensure!(recipient_period_limit > Zero::zero(), Error::<T>::ZeroRecipientPeriodLimitProvided);
+        Ok(())
+    }
+
+    /// Check whether the recipient can receive `amount` more tokens from the faucet.
+    /// Resets the recipient's period stats if the faucet has moved to a new period.
+    fn check_recipient_limits(
+        settings: &Faucet<T>,
+        drips: &mut RecipientDrips<T>,
+        current_block: T::BlockNumber,
+        amount: BalanceOf<T>,
+    ) -> Result<(), DripRejectionReason> {
+
+        if drips.period_ends_at != settings.next_period_at {
+            drips.period_ends_at = settings.next_period_at;
+            drips.received_in_current_period = Zero::zero();
+        }
+
+        let is_first_drip = drips.total_received.is_zero();
+        let cooldown_ends_at = drips.last_drip_at.saturating_add(settings.recipient_cooldown);
+        if !is_first_drip && current_block < cooldown_ends_at {
+            return Err(DripRejectionReason::RecipientCooldownNotPassed);
+        }
+
+        let tokens_left_for_recipient = settings.recipient_period_limit
+            .saturating_sub(drips.received_in_current_period);
+
+        if amount > tokens_left_for_recipient {
+            return Err(DripRejectionReason::RecipientPeriodLimitReached);
+        }
+
+        Ok(())
+    }
+
+    fn remove_refill_policy_of(faucet: &T::AccountId) {
+        RefillPolicyByFaucet::<T>::remove(faucet);
+        FaucetsWithRefillPolicy::<T>::mutate(|faucets| faucets.retain(|account| account != faucet));
+    }
+
+    /// Top up from the treasury the faucets whose free balance dropped below the threshold
+    /// of their refill policy. Up to `MaxFaucetsToRefillPerBlock` faucets are checked per block,
+    /// and the next block continues with the faucets that follow them.
+    fn refill_faucets(current_block: T::BlockNumber) -> Weight {
+        let faucets = Self::faucets_with_refill_policy();
+        if faucets.is_empty() {
+            return T::DbWeight::get().reads(1);
+        }
+
+        let faucets_to_check = faucets.len().min(T::MaxFaucetsToRefillPerBlock::get() as usize);
+        let first = Self::next_faucet_to_refill() as usize % faucets.len();
+
+        let treasury = pallet_utils::Module::<T>::treasury_account();
+        let mut refills: u64 = 0;
+
+        for i in 0..faucets_to_check {
+            let faucet = &faucets[(first + i) % faucets.len()];
+            if Self::refill_faucet(faucet, &treasury, current_block) {
+                refills += 1;
+            }
+        }
+
+        NextFaucetToRefill::put(((first + faucets_to_check) % faucets.len()) as u32);
+
+        let checked = faucets_to_check as u64;
+        T::DbWeight::get().reads_writes(3 + checked * 2 + refills * 2, 1 + refills * 3)
+    }
+
+    /// Refill the faucet if its free balance is below the threshold of its refill policy.
+    /// Returns whether the faucet was refilled.
+    fn refill_faucet(faucet: &T::AccountId, treasury: &T::AccountId, current_block: T::BlockNumber) -> bool {
+        let mut policy = match Self::refill_policy_by_faucet(faucet) {
+            Some(policy) => policy,
+            None => return false,
+        };
+
+        let faucet_balance = T::Currency::free_balance(faucet);
+        if faucet_balance >= policy.threshold {
+            return false;
+        }
+
+        if policy.next_period_at <= current_block {
+            // Move to the next period and reset the period stats
+            policy.next_period_at = current_block.saturating_add(policy.period);
+            policy.refilled_in_current_period = Zero::zero();
+        }
+
+        let cap_left_in_current_period = policy.period_cap
+            .saturating_sub(policy.refilled_in_current_period);
+
+        let amount = policy.target_balance
+            .saturating_sub(faucet_balance)
+            .min(cap_left_in_current_period);
+
+        if amount.is_zero() {
+            return false;
+        }
+
+        let transferred = T::Currency::transfer(
+            treasury,
+            faucet,
+            amount,
+            ExistenceRequirement::KeepAlive
+        );
+
+        if transferred.is_err() {
+            return false;
+        }
+
+        policy.refilled_in_current_period = amount
+            .saturating_add(policy.refilled_in_current_period);
+
+        RefillPolicyByFaucet::<T>::insert(faucet, policy);
+        Self::deposit_event(RawEvent::FaucetRefilled(faucet.clone(), treasury.clone(), amount));
+        true
+    }
+
+    /// Convert faucets stored in the old layout of `Faucet`, that had no recipient limits.
+    /// A recipient of such a faucet can receive up to the whole faucet period limit, without a cooldown.
+    ///
+    /// A value in the old layout is shorter, so it cannot be decoded as a new `Faucet`.
+    /// Faucets in the new layout are skipped, so running this again does nothing.
+    fn migrate_faucets_to_recipient_limits() -> Weight {
+        let mut faucets: u64 = 0;
+        let mut migrated: u64 = 0;
+
+        let stored_faucets = StorageIterator::<OldFaucet<T>>::new(b"FaucetsModule", b"FaucetByAccount");
+        for (key, old) in stored_faucets {
+            faucets += 1;
+
+            let has_new_layout =
+                get_storage_value::<Faucet<T>>(b"FaucetsModule", b"FaucetByAccount", &key).is_some();
+
+            if has_new_layout {
+                continue;
+            }
+
+            let faucet = Faucet::<T> {
+                enabled: old.enabled,
+                period: old.period,
+                period_limit: old.period_limit,
+                drip_limit: old.drip_limit,
+                recipient_period_limit: old.period_limit,
+                recipient_cooldown: Zero::zero(),
+                next_period_at: old.next_period_at,
+                dripped_in_current_period: old.dripped_in_current_period,
+            };
+
+            put_storage_value(b"FaucetsModule", b"FaucetByAccount", &key, faucet);
+            migrated += 1;
+        }
+
+        T::DbWeight::get().reads_writes(faucets * 2, migrated)
+    }
+}
+
+impl<T: Trait> From<DripRejectionReason> for Error<T> {
+    fn from(reason: DripRejectionReason) -> Self {
+        match reason {
+            DripRejectionReason::RecipientPeriodLimitReached => Error::<T>::RecipientPeriodLimitReached,
+            DripRejectionReason::RecipientCooldownNotPassed => Error::<T>::RecipientCooldownNotPassed,
+        }
+    }
 }
 
 impl<T: Trait> Faucet<T> {
@@ -326,15 +928,51 @@ impl<T: Trait> Faucet<T> {
         period: T::BlockNumber,
         period_limit: BalanceOf<T>,
         drip_limit: BalanceOf<T>,
+        recipient_period_limit: BalanceOf<T>,
+        recipient_cooldown: T::BlockNumber,
     ) -> Self {
         Self {
             enabled: true,
             period,
             period_limit,
             drip_limit,
+            recipient_period_limit,
+            recipient_cooldown,
 
             next_period_at: Zero::zero(),
             dripped_in_current_period: Zero::zero(),
         }
     }
 }
+
+impl<T: Trait> RecipientDrips<T> {
+
+    pub fn new(period_ends_at: T::BlockNumber) -> Self {
+        Self {
+            last_drip_at: Zero::zero(),
+            period_ends_at,
+            received_in_current_period: Zero::zero(),
+            total_received: Zero::zero(),
+        }
+    }
+}
+
+impl<T: Trait> RefillPolicy<T> {
+
+    pub fn new(
+        threshold: BalanceOf<T>,
+        target_balance: BalanceOf<T>,
+        period: T::BlockNumber,
+        period_cap: BalanceOf<T>,
+    ) -> Self {
+        Self {
+            threshold,
+            target_balance,
+            period,
+            period_cap,
+
+            next_period_at: Zero::zero(),
+            refilled_in_current_period: Zero::zero(),
+        }
+    }
+}
@@ -1,10 +1,11 @@
 // Creating mock runtime here
-use crate::{Module, Trait, Faucet, FaucetUpdate};
+use crate::{Module, Trait, Faucet, FaucetUpdate, RefillPolicy, ClaimRules};
 
 use sp_core::H256;
 use sp_io::TestExternalities;
 use sp_runtime::{
-    traits::{BlakeTwo256, IdentityLookup}, testing::Header, RuntimeDebug, Perbill, Storage
+    traits::{BlakeTwo256, IdentityLookup}, testing::Header, RuntimeDebug, Perbill, Storage,
+    transaction_validity::{TransactionLongevity, TransactionPriority},
 };
 
 use frame_support::{
@@ -101,9 +102,22 @@ impl pallet_utils::Trait for Test {
     type MaxHandleLen = MaxHandleLen;
 }
 
+impl pallet_profiles::Trait for Test {
+    type Event = ();
+    type AfterProfileUpdated = ();
+}
+
+parameter_types! {
+    pub const ClaimPriority: TransactionPriority = 100;
+    pub const ClaimLongevity: TransactionLongevity = 10;
+    pub const MaxFaucetsToRefillPerBlock: u16 = 1;
+}
+
 impl Trait for Test {
     type Event = ();
-    type Currency = Balances;
+    type ClaimPriority = ClaimPriority;
+    type ClaimLongevity = ClaimLongevity;
+    type MaxFaucetsToRefillPerBlock = MaxFaucetsToRefillPerBlock;
 }
 
 pub(crate) type System = system::Module<Test>;
@@ -124,8 +138,15 @@ impl ExtBuilder {
             faucet_accounts.push(faucet);
         }
 
+        let _ = pallet_utils::GenesisConfig::<Test> {
+            treasury_account: TREASURY,
+        }.assimilate_storage(storage);
+
+        let mut balances: Vec<_> = faucet_accounts.iter().cloned().map(|k|(k, FAUCET_INITIAL_BALANCE)).collect();
+        balances.push((TREASURY, TREASURY_INITIAL_BALANCE));
+
         let _ = pallet_balances::GenesisConfig::<Test> {
-            balances: faucet_accounts.iter().cloned().map(|k|(k, FAUCET_INITIAL_BALANCE)).collect(),
+            balances,
         }.assimilate_storage(storage);
     }
 
@@ -182,6 +203,7 @@ impl ExtBuilder {
 }
 
 pub(crate) const FAUCET_INITIAL_BALANCE: Balance = 400;
+pub(crate) const TREASURY_INITIAL_BALANCE: Balance = 1_000;
 
 pub(crate) const FAUCET1: AccountId = 1;
 pub(crate) const FAUCET2: AccountId = 2;
@@ -189,6 +211,9 @@ pub(crate) const FAUCET8: AccountId = 8;
 pub(crate) const FAUCET9: AccountId = 9;
 
 pub(crate) const ACCOUNT1: AccountId = 11;
+pub(crate) const ACCOUNT2: AccountId = 12;
+
+pub(crate) const TREASURY: AccountId = 100;
 
 pub(crate) const INITIAL_BLOCK_NUMBER: BlockNumber = 20;
 
@@ -198,6 +223,8 @@ pub(crate) const fn default_faucet() -> Faucet<Test> {
         period: 100,
         period_limit: 50,
         drip_limit: 25,
+        recipient_period_limit: 50,
+        recipient_cooldown: 0,
 
         next_period_at: 0,
         dripped_in_current_period: 0,
@@ -209,7 +236,9 @@ pub(crate) const fn default_faucet_update() -> FaucetUpdate<BlockNumber, Balance
         enabled: None,
         period: Some(7_200),
         period_limit: Some(100),
-        drip_limit: Some(50)
+        drip_limit: Some(50),
+        recipient_period_limit: Some(75),
+        recipient_cooldown: Some(10),
     }
 }
 
@@ -227,7 +256,9 @@ pub(crate) fn _add_faucet(
         faucet_account.unwrap_or(FAUCET1),
         settings.period,
         settings.period_limit,
-        settings.drip_limit
+        settings.drip_limit,
+        settings.recipient_period_limit,
+        settings.recipient_cooldown
     )
 }
 
@@ -239,6 +270,22 @@ pub(crate) fn _update_faucet_settings(settings: FaucetUpdate<BlockNumber, Balanc
     _update_faucet(None, None, Some(settings))
 }
 
+pub(crate) fn _update_recipient_limits(
+    recipient_period_limit: Option<Balance>,
+    recipient_cooldown: Option<BlockNumber>,
+) -> DispatchResult {
+    _update_faucet_settings(
+        FaucetUpdate {
+            enabled: None,
+            period: None,
+            period_limit: None,
+            drip_limit: None,
+            recipient_period_limit,
+            recipient_cooldown,
+        }
+    )
+}
+
 pub(crate) fn _update_faucet(
     origin: Option<Origin>,
     faucet_account: Option<AccountId>,
@@ -280,3 +327,117 @@ pub(crate) fn _drip(
         amount.unwrap_or(default_faucet().drip_limit)
     )
 }
+
+pub(crate) const fn default_refill_policy() -> RefillPolicy<Test> {
+    RefillPolicy {
+        threshold: 300,
+        target_balance: 400,
+        period: 100,
+        period_cap: 150,
+
+        next_period_at: 0,
+        refilled_in_current_period: 0,
+    }
+}
+
+pub(crate) fn _set_default_refill_policy() -> DispatchResult {
+    _set_refill_policy(None, None, None)
+}
+
+pub(crate) fn _set_refill_policy(
+    origin: Option<Origin>,
+    faucet_account: Option<AccountId>,
+    policy: Option<RefillPolicy<Test>>,
+) -> DispatchResult {
+    let policy = policy.unwrap_or_else(default_refill_policy);
+    Faucets::set_refill_policy(
+        origin.unwrap_or_else(Origin::root),
+        faucet_account.unwrap_or(FAUCET1),
+        policy.threshold,
+        policy.target_balance,
+        policy.period,
+        policy.period_cap
+    )
+}
+
+pub(crate) fn _remove_default_refill_policy() -> DispatchResult {
+    Faucets::remove_refill_policy(Origin::root(), FAUCET1)
+}
+
+/// Leave only `balance_left` tokens on the default faucet.
+pub(crate) fn _spend_default_faucet_balance(balance_left: Balance) -> DispatchResult {
+    let faucet_balance = Balances::free_balance(FAUCET1);
+    Balances::transfer(Origin::signed(FAUCET1), FAUCET2, faucet_balance - balance_left)
+}
+
+pub(crate) const CLAIM_CODE: &[u8] = b"welcome-to-subsocial";
+
+/// The hash of the default claim code issued for `ACCOUNT1`.
+pub(crate) fn claim_code_hash() -> H256 {
+    Faucets::claim_code_hash(CLAIM_CODE, &ACCOUNT1)
+}
+
+pub(crate) const fn default_claim_rules() -> ClaimRules<Balance> {
+    ClaimRules {
+        claim_amount: 10,
+        min_reputation: 5,
+        profile_required: true,
+        min_following_spaces: 1,
+        accept_claim_codes: true,
+    }
+}
+
+pub(crate) fn _set_default_claim_rules() -> DispatchResult {
+    _set_claim_rules(None, Some(default_claim_rules()))
+}
+
+pub(crate) fn _set_claim_rules(
+    origin: Option<Origin>,
+    rules: Option<ClaimRules<Balance>>,
+) -> DispatchResult {
+    Faucets::set_claim_rules(
+        origin.unwrap_or_else(Origin::root),
+        FAUCET1,
+        rules
+    )
+}
+
+pub(crate) fn _add_default_claim_code() -> DispatchResult {
+    Faucets::add_claim_codes(Origin::root(), FAUCET1, vec![claim_code_hash()])
+}
+
+pub(crate) fn _claim_without_code() -> DispatchResult {
+    _claim(None, None)
+}
+
+pub(crate) fn _claim_with_default_code() -> DispatchResult {
+    _claim(None, Some(CLAIM_CODE.to_vec()))
+}
+
+pub(crate) fn _claim(
+    recipient: Option<AccountId>,
+    claim_code: Option<Vec<u8>>,
+) -> DispatchResult {
+    Faucets::claim(
+        Origin::none(),
+        FAUCET1,
+        recipient.unwrap_or(ACCOUNT1),
+        claim_code
+    )
+}
+
+/// Make the account socially active enough to satisfy the default claim rules.
+pub(crate) fn _make_account_eligible_to_claim(account: AccountId) {
+    let rules = default_claim_rules();
+    pallet_profiles::SocialAccountById::<Test>::insert(account, pallet_profiles::SocialAccount {
+        followers_count: 0,
+        following_accounts_count: 0,
+        following_spaces_count: rules.min_following_spaces,
+        reputation: rules.min_reputation,
+        profile: Some(pallet_profiles::Profile {
+            created: pallet_utils::WhoAndWhen::<Test>::new(account),
+            updated: None,
+            content: pallet_utils::Content::None,
+        }),
+    });
+}
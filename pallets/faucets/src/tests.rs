@@ -1,6 +1,16 @@
-use crate::{Error, mock::*, Faucet, FaucetUpdate};
-use frame_support::{assert_ok, assert_noop};
-use sp_runtime::DispatchError::BadOrigin;
+use codec::Encode;
+use crate::{Error, mock::*, Faucet, FaucetUpdate, OldFaucet, RecipientDrips, RefillPolicy, ClaimRules};
+use frame_support::{
+    assert_ok, assert_noop, assert_err,
+    storage::migration::put_storage_value,
+    traits::{Get, OnInitialize, OnRuntimeUpgrade},
+    StorageHasher, Twox64Concat,
+};
+use sp_runtime::{
+    DispatchError::BadOrigin,
+    traits::ValidateUnsigned,
+    transaction_validity::{InvalidTransaction, TransactionSource},
+};
 
 // Add faucet
 // ----------------------------------------------------------------------------
@@ -63,12 +73,16 @@ fn update_faucet_should_work() {
         let updated_faucet = Faucet::<Test>::new(
             update.period.unwrap_or(faucet.period),
             update.period_limit.unwrap_or(faucet.period_limit),
-            update.drip_limit.unwrap_or(faucet.drip_limit)
+            update.drip_limit.unwrap_or(faucet.drip_limit),
+            update.recipient_period_limit.unwrap_or(faucet.recipient_period_limit),
+            update.recipient_cooldown.unwrap_or(faucet.recipient_cooldown)
         );
 
         assert_eq!(faucet.period, updated_faucet.period);
         assert_eq!(faucet.period_limit, updated_faucet.period_limit);
         assert_eq!(faucet.drip_limit, updated_faucet.drip_limit);
+        assert_eq!(faucet.recipient_period_limit, updated_faucet.recipient_period_limit);
+        assert_eq!(faucet.recipient_cooldown, updated_faucet.recipient_cooldown);
     });
 }
 
@@ -81,7 +95,9 @@ fn update_faucet_should_fail_when_no_updates_provided() {
                     enabled: None,
                     period: None,
                     period_limit: None,
-                    drip_limit: None
+                    drip_limit: None,
+                    recipient_period_limit: None,
+                    recipient_cooldown: None
                 }
             ),
             Error::<Test>::NoUpdatesProvided
@@ -108,7 +124,9 @@ fn update_faucet_should_fail_when_same_active_flag_provided() {
                     enabled: Some(default_faucet().enabled),
                     period: None,
                     period_limit: None,
-                    drip_limit: None
+                    drip_limit: None,
+                    recipient_period_limit: None,
+                    recipient_cooldown: None
                 }
             ),
             Error::<Test>::NothingToUpdate
@@ -125,7 +143,9 @@ fn update_faucet_should_fail_when_same_period_provided() {
                     enabled: None,
                     period: Some(default_faucet().period),
                     period_limit: None,
-                    drip_limit: None
+                    drip_limit: None,
+                    recipient_period_limit: None,
+                    recipient_cooldown: None
                 }
             ),
             Error::<Test>::NothingToUpdate
@@ -142,7 +162,9 @@ fn update_faucet_should_fail_when_same_period_limit_provided() {
                     enabled: None,
                     period: None,
                     period_limit: Some(default_faucet().period_limit),
-                    drip_limit: None
+                    drip_limit: None,
+                    recipient_period_limit: None,
+                    recipient_cooldown: None
                 }
             ),
             Error::<Test>::NothingToUpdate
@@ -159,7 +181,9 @@ fn update_faucet_should_fail_when_same_drip_limit_provided() {
                     enabled: None,
                     period: None,
                     period_limit: None,
-                    drip_limit: Some(default_faucet().drip_limit)
+                    drip_limit: Some(default_faucet().drip_limit),
+                    recipient_period_limit: None,
+                    recipient_cooldown: None
                 }
             ),
             Error::<Test>::NothingToUpdate
@@ -393,7 +417,9 @@ fn drip_should_fail_when_faucet_is_disabled_and_work_again_after_faucet_enabled(
                 enabled: Some(false),
                 period: None,
                 period_limit: None,
-                drip_limit: None
+                drip_limit: None,
+                recipient_period_limit: None,
+                recipient_cooldown: None
             }
         ));
 
@@ -412,7 +438,9 @@ fn drip_should_fail_when_faucet_is_disabled_and_work_again_after_faucet_enabled(
                 enabled: Some(true),
                 period: None,
                 period_limit: None,
-                drip_limit: None
+                drip_limit: None,
+                recipient_period_limit: None,
+                recipient_cooldown: None
             }
         ));
 
@@ -423,3 +451,460 @@ fn drip_should_fail_when_faucet_is_disabled_and_work_again_after_faucet_enabled(
         assert_eq!(Balances::free_balance(ACCOUNT1), default_faucet().drip_limit);
     });
 }
+
+#[test]
+fn drip_should_record_what_recipient_received() {
+    ExtBuilder::build_with_one_default_drip().execute_with(|| {
+        let Faucet { period, drip_limit, .. } = default_faucet();
+
+        assert_eq!(
+            Faucets::drips_by_faucet_and_recipient(FAUCET1, ACCOUNT1).unwrap(),
+            RecipientDrips {
+                last_drip_at: INITIAL_BLOCK_NUMBER,
+                period_ends_at: INITIAL_BLOCK_NUMBER + period,
+                received_in_current_period: drip_limit,
+                total_received: drip_limit,
+            }
+        );
+        assert!(Faucets::drips_by_faucet_and_recipient(FAUCET1, ACCOUNT2).is_none());
+    });
+}
+
+#[test]
+fn drip_should_reset_recipient_period_stats_in_next_period() {
+    ExtBuilder::build_with_one_default_drip().execute_with(|| {
+        let Faucet { period, drip_limit, .. } = default_faucet();
+
+        // Move to the next period
+        let next_period_block = INITIAL_BLOCK_NUMBER + period;
+        System::set_block_number(next_period_block);
+        assert_ok!(_do_default_drip());
+
+        let drips = Faucets::drips_by_faucet_and_recipient(FAUCET1, ACCOUNT1).unwrap();
+        assert_eq!(drips.last_drip_at, next_period_block);
+        assert_eq!(drips.period_ends_at, next_period_block + period);
+        assert_eq!(drips.received_in_current_period, drip_limit);
+        assert_eq!(drips.total_received, drip_limit * 2);
+    });
+}
+
+#[test]
+fn drip_should_fail_when_recipient_period_limit_reached() {
+    ExtBuilder::build_with_faucet().execute_with(|| {
+        let drip_limit = default_faucet().drip_limit;
+        assert_ok!(_update_recipient_limits(Some(drip_limit), None));
+
+        System::set_block_number(INITIAL_BLOCK_NUMBER);
+        assert_ok!(_do_default_drip());
+
+        // The second drip to the same recipient exceeds the recipient's period limit
+        assert_err!(
+            _do_default_drip(),
+            Error::<Test>::RecipientPeriodLimitReached
+        );
+        assert_eq!(Balances::free_balance(ACCOUNT1), drip_limit);
+
+        // Another recipient still can receive tokens in the same period
+        assert_ok!(_drip(None, Some(ACCOUNT2), None));
+        assert_eq!(Balances::free_balance(ACCOUNT2), drip_limit);
+    });
+}
+
+#[test]
+fn drip_should_fail_when_recipient_cooldown_not_passed_and_work_after_it() {
+    ExtBuilder::build_with_faucet().execute_with(|| {
+        let cooldown = 10;
+        assert_ok!(_update_recipient_limits(None, Some(cooldown)));
+
+        System::set_block_number(INITIAL_BLOCK_NUMBER);
+        assert_ok!(_do_default_drip());
+
+        System::set_block_number(INITIAL_BLOCK_NUMBER + cooldown - 1);
+        assert_err!(
+            _do_default_drip(),
+            Error::<Test>::RecipientCooldownNotPassed
+        );
+
+        System::set_block_number(INITIAL_BLOCK_NUMBER + cooldown);
+        assert_ok!(_do_default_drip());
+
+        assert_eq!(Balances::free_balance(ACCOUNT1), default_faucet().drip_limit * 2);
+    });
+}
+
+#[test]
+fn update_faucet_should_fail_when_zero_recipient_period_limit_provided() {
+    ExtBuilder::build_with_faucet().execute_with(|| {
+        assert_noop!(
+            _update_recipient_limits(Some(0), None),
+            Error::<Test>::ZeroRecipientPeriodLimitProvided
+        );
+    });
+}
+
+// Refill faucets from treasury
+// ----------------------------------------------------------------------------
+
+#[test]
+fn set_refill_policy_should_work() {
+    ExtBuilder::build_with_faucet().execute_with(|| {
+        assert_ok!(_set_default_refill_policy());
+
+        let policy = Faucets::refill_policy_by_faucet(FAUCET1).unwrap();
+        assert_eq!(policy, default_refill_policy());
+        assert_eq!(Faucets::faucets_with_refill_policy(), vec![FAUCET1]);
+
+        // Setting the policy again should not add the faucet twice
+        assert_ok!(_set_default_refill_policy());
+        assert_eq!(Faucets::faucets_with_refill_policy(), vec![FAUCET1]);
+    });
+}
+
+#[test]
+fn set_refill_policy_should_fail_when_origin_is_not_root() {
+    ExtBuilder::build_with_faucet().execute_with(|| {
+        let not_root = Origin::signed(ACCOUNT1);
+        assert_noop!(
+            _set_refill_policy(Some(not_root), None, None),
+            BadOrigin
+        );
+    });
+}
+
+#[test]
+fn set_refill_policy_should_fail_when_faucet_not_found() {
+    ExtBuilder::build().execute_with(|| {
+        assert_noop!(
+            _set_default_refill_policy(),
+            Error::<Test>::FaucetNotFound
+        );
+    });
+}
+
+#[test]
+fn set_refill_policy_should_fail_when_target_balance_is_not_above_threshold() {
+    ExtBuilder::build_with_faucet().execute_with(|| {
+        let policy = default_refill_policy();
+        assert_noop!(
+            _set_refill_policy(None, None, Some(
+                RefillPolicy { target_balance: policy.threshold, ..policy }
+            )),
+            Error::<Test>::RefillTargetBalanceNotAboveThreshold
+        );
+    });
+}
+
+#[test]
+fn remove_refill_policy_should_work() {
+    ExtBuilder::build_with_faucet().execute_with(|| {
+        assert_ok!(_set_default_refill_policy());
+        assert_ok!(_remove_default_refill_policy());
+
+        assert!(Faucets::refill_policy_by_faucet(FAUCET1).is_none());
+        assert!(Faucets::faucets_with_refill_policy().is_empty());
+        assert_noop!(
+            _remove_default_refill_policy(),
+            Error::<Test>::RefillPolicyNotFound
+        );
+    });
+}
+
+#[test]
+fn remove_faucets_should_remove_refill_policy() {
+    ExtBuilder::build_with_faucet().execute_with(|| {
+        assert_ok!(_set_default_refill_policy());
+        assert_ok!(_remove_default_faucet());
+
+        assert!(Faucets::refill_policy_by_faucet(FAUCET1).is_none());
+        assert!(Faucets::faucets_with_refill_policy().is_empty());
+    });
+}
+
+#[test]
+fn remove_faucets_should_remove_claim_rules_and_codes() {
+    ExtBuilder::build_with_faucet().execute_with(|| {
+        assert_ok!(_set_default_claim_rules());
+        assert_ok!(_add_default_claim_code());
+        assert_ok!(_remove_default_faucet());
+
+        assert!(Faucets::claim_rules_by_faucet(FAUCET1).is_none());
+        assert!(!Faucets::claim_code_is_valid(FAUCET1, claim_code_hash()));
+    });
+}
+
+#[test]
+fn faucet_should_not_be_refilled_when_balance_is_above_threshold() {
+    ExtBuilder::build_with_faucet().execute_with(|| {
+        assert_ok!(_set_default_refill_policy());
+
+        Faucets::on_initialize(INITIAL_BLOCK_NUMBER);
+
+        assert_eq!(Balances::free_balance(FAUCET1), FAUCET_INITIAL_BALANCE);
+        assert_eq!(Balances::free_balance(TREASURY), TREASURY_INITIAL_BALANCE);
+    });
+}
+
+#[test]
+fn faucet_should_be_refilled_from_treasury_up_to_period_cap() {
+    ExtBuilder::build_with_faucet().execute_with(|| {
+        assert_ok!(_set_default_refill_policy());
+        let RefillPolicy { period, period_cap, .. } = default_refill_policy();
+
+        assert_ok!(_spend_default_faucet_balance(50));
+
+        // The faucet needs 350 tokens to reach the target balance,
+        // but only the period cap can be moved from the treasury.
+        Faucets::on_initialize(INITIAL_BLOCK_NUMBER);
+        assert_eq!(Balances::free_balance(FAUCET1), 50 + period_cap);
+        assert_eq!(Balances::free_balance(TREASURY), TREASURY_INITIAL_BALANCE - period_cap);
+
+        let policy = Faucets::refill_policy_by_faucet(FAUCET1).unwrap();
+        assert_eq!(policy.next_period_at, INITIAL_BLOCK_NUMBER + period);
+        assert_eq!(policy.refilled_in_current_period, period_cap);
+
+        // Nothing should be refilled until the next period
+        Faucets::on_initialize(INITIAL_BLOCK_NUMBER + 1);
+        assert_eq!(Balances::free_balance(FAUCET1), 50 + period_cap);
+
+        Faucets::on_initialize(INITIAL_BLOCK_NUMBER + period);
+        assert_eq!(Balances::free_balance(FAUCET1), 50 + period_cap * 2);
+        assert_eq!(Balances::free_balance(TREASURY), TREASURY_INITIAL_BALANCE - period_cap * 2);
+    });
+}
+
+#[test]
+fn faucets_should_be_checked_for_refill_in_turns() {
+    ExtBuilder::build_with_faucet().execute_with(|| {
+        assert_ok!(_add_faucet(None, Some(FAUCET2)));
+        assert_ok!(_set_default_refill_policy());
+        assert_ok!(_set_refill_policy(None, Some(FAUCET2), None));
+
+        assert_ok!(_spend_default_faucet_balance(50));
+        let faucet2_balance = Balances::free_balance(FAUCET2);
+        assert_ok!(Balances::transfer(Origin::signed(FAUCET2), ACCOUNT1, faucet2_balance - 50));
+
+        // Only one faucet is checked per block
+        Faucets::on_initialize(INITIAL_BLOCK_NUMBER);
+        assert!(Balances::free_balance(FAUCET1) > 50);
+        assert_eq!(Balances::free_balance(FAUCET2), 50);
+
+        Faucets::on_initialize(INITIAL_BLOCK_NUMBER + 1);
+        assert!(Balances::free_balance(FAUCET2) > 50);
+    });
+}
+
+#[test]
+fn faucet_should_be_refilled_only_up_to_target_balance() {
+    ExtBuilder::build_with_faucet().execute_with(|| {
+        assert_ok!(_set_default_refill_policy());
+        let RefillPolicy { threshold, target_balance, .. } = default_refill_policy();
+
+        assert_ok!(_spend_default_faucet_balance(threshold - 1));
+
+        Faucets::on_initialize(INITIAL_BLOCK_NUMBER);
+        assert_eq!(Balances::free_balance(FAUCET1), target_balance);
+        assert_eq!(
+            Balances::free_balance(TREASURY),
+            TREASURY_INITIAL_BALANCE - (target_balance - threshold + 1)
+        );
+    });
+}
+
+// Claim from faucet
+// ----------------------------------------------------------------------------
+
+#[test]
+fn set_claim_rules_should_work() {
+    ExtBuilder::build_with_faucet().execute_with(|| {
+        assert_ok!(_set_default_claim_rules());
+        assert_eq!(Faucets::claim_rules_by_faucet(FAUCET1), Some(default_claim_rules()));
+
+        // Disable claiming
+        assert_ok!(_set_claim_rules(None, None));
+        assert!(Faucets::claim_rules_by_faucet(FAUCET1).is_none());
+    });
+}
+
+#[test]
+fn set_claim_rules_should_fail_when_origin_is_not_root() {
+    ExtBuilder::build_with_faucet().execute_with(|| {
+        let not_root = Origin::signed(ACCOUNT1);
+        assert_noop!(
+            _set_claim_rules(Some(not_root), Some(default_claim_rules())),
+            BadOrigin
+        );
+    });
+}
+
+#[test]
+fn set_claim_rules_should_fail_when_claim_amount_is_above_drip_limit() {
+    ExtBuilder::build_with_faucet().execute_with(|| {
+        let rules = ClaimRules {
+            claim_amount: default_faucet().drip_limit + 1,
+            ..default_claim_rules()
+        };
+        assert_noop!(
+            _set_claim_rules(None, Some(rules)),
+            Error::<Test>::DripLimitReached
+        );
+    });
+}
+
+#[test]
+fn claim_should_work_for_socially_active_account() {
+    ExtBuilder::build_with_faucet().execute_with(|| {
+        assert_ok!(_set_default_claim_rules());
+        _make_account_eligible_to_claim(ACCOUNT1);
+
+        assert_ok!(_claim_without_code());
+
+        let claim_amount = default_claim_rules().claim_amount;
+        assert_eq!(Balances::free_balance(ACCOUNT1), claim_amount);
+        assert_eq!(
+            Faucets::drips_by_faucet_and_recipient(FAUCET1, ACCOUNT1).unwrap().total_received,
+            claim_amount
+        );
+    });
+}
+
+#[test]
+fn claim_should_fail_when_origin_is_signed() {
+    ExtBuilder::build_with_faucet().execute_with(|| {
+        assert_ok!(_set_default_claim_rules());
+        _make_account_eligible_to_claim(ACCOUNT1);
+
+        assert_noop!(
+            Faucets::claim(Origin::signed(ACCOUNT1), FAUCET1, ACCOUNT1, None),
+            BadOrigin
+        );
+    });
+}
+
+#[test]
+fn claim_should_fail_when_claiming_is_disabled() {
+    ExtBuilder::build_with_faucet().execute_with(|| {
+        _make_account_eligible_to_claim(ACCOUNT1);
+
+        assert_noop!(
+            _claim_without_code(),
+            Error::<Test>::ClaimingIsDisabled
+        );
+    });
+}
+
+#[test]
+fn claim_should_fail_when_account_is_not_socially_active() {
+    ExtBuilder::build_with_faucet().execute_with(|| {
+        assert_ok!(_set_default_claim_rules());
+
+        assert_noop!(
+            _claim_without_code(),
+            Error::<Test>::NotEligibleToClaim
+        );
+        assert_eq!(Balances::free_balance(ACCOUNT1), 0);
+    });
+}
+
+#[test]
+fn claim_should_work_with_claim_code_only_once() {
+    ExtBuilder::build_with_faucet().execute_with(|| {
+        assert_ok!(_set_default_claim_rules());
+        assert_ok!(_add_default_claim_code());
+
+        // The account is not socially active, but it has a claim code
+        assert_ok!(_claim_with_default_code());
+        assert_eq!(Balances::free_balance(ACCOUNT1), default_claim_rules().claim_amount);
+        assert!(!Faucets::claim_code_is_valid(FAUCET1, claim_code_hash()));
+
+        assert_noop!(
+            _claim(Some(ACCOUNT2), Some(CLAIM_CODE.to_vec())),
+            Error::<Test>::InvalidClaimCode
+        );
+    });
+}
+
+#[test]
+fn claim_should_fail_when_claim_code_was_issued_for_another_recipient() {
+    ExtBuilder::build_with_faucet().execute_with(|| {
+        assert_ok!(_set_default_claim_rules());
+        assert_ok!(_add_default_claim_code());
+
+        // Someone saw the code of ACCOUNT1 in the transaction pool.
+        assert_noop!(
+            _claim(Some(ACCOUNT2), Some(CLAIM_CODE.to_vec())),
+            Error::<Test>::InvalidClaimCode
+        );
+        assert!(Faucets::claim_code_is_valid(FAUCET1, claim_code_hash()));
+
+        assert_ok!(_claim_with_default_code());
+    });
+}
+
+#[test]
+fn claim_should_fail_when_claim_codes_are_not_accepted() {
+    ExtBuilder::build_with_faucet().execute_with(|| {
+        assert_ok!(_set_claim_rules(None, Some(
+            ClaimRules { accept_claim_codes: false, ..default_claim_rules() }
+        )));
+        assert_ok!(_add_default_claim_code());
+
+        assert_noop!(
+            _claim_with_default_code(),
+            Error::<Test>::InvalidClaimCode
+        );
+    });
+}
+
+#[test]
+fn validate_unsigned_should_accept_only_eligible_claims() {
+    ExtBuilder::build_with_faucet().execute_with(|| {
+        assert_ok!(_set_default_claim_rules());
+        let claim_call = crate::Call::<Test>::claim(FAUCET1, ACCOUNT1, None);
+
+        assert_eq!(
+            Faucets::validate_unsigned(TransactionSource::External, &claim_call),
+            InvalidTransaction::Call.into()
+        );
+
+        _make_account_eligible_to_claim(ACCOUNT1);
+
+        let valid_tx = Faucets::validate_unsigned(TransactionSource::External, &claim_call).unwrap();
+        assert_eq!(valid_tx.priority, ClaimPriority::get());
+        assert_eq!(valid_tx.longevity, ClaimLongevity::get());
+    });
+}
+
+// Migration
+// ----------------------------------------------------------------------------
+
+#[test]
+fn on_runtime_upgrade_should_migrate_faucets_without_recipient_limits() {
+    ExtBuilder::build_with_faucet().execute_with(|| {
+        let old_faucet = OldFaucet::<Test> {
+            enabled: true,
+            period: 100,
+            period_limit: 50,
+            drip_limit: 25,
+            next_period_at: 120,
+            dripped_in_current_period: 10,
+        };
+        let key = Twox64Concat::hash(&FAUCET2.encode());
+        put_storage_value(b"FaucetsModule", b"FaucetByAccount", &key, old_faucet);
+
+        Faucets::on_runtime_upgrade();
+
+        assert_eq!(
+            Faucets::faucet_by_account(FAUCET2).unwrap(),
+            Faucet {
+                recipient_period_limit: 50,
+                recipient_cooldown: 0,
+                next_period_at: 120,
+                dripped_in_current_period: 10,
+                ..default_faucet()
+            }
+        );
+
+        // A faucet in the new layout stays untouched.
+        assert_eq!(Faucets::faucet_by_account(FAUCET1).unwrap(), default_faucet());
+    });
+}
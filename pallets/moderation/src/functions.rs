@@ -1,16 +1,22 @@
 use crate::*;
 
 use frame_support::dispatch::DispatchError;
+use sp_runtime::traits::Zero;
+use sp_std::collections::btree_set::BTreeSet;
 use pallet_posts::Module as Posts;
 use pallet_spaces::Space;
 use pallet_space_follows::Module as SpaceFollows;
-use df_traits::moderation::*;
+use df_traits::{SpaceCleaner, moderation::*};
 
 impl<T: Trait> Module<T> {
     pub fn require_report(report_id: ReportId) -> Result<Report<T>, DispatchError> {
         Ok(Self::report_by_id(report_id).ok_or(Error::<T>::ReportNotFound)?)
     }
 
+    pub fn require_appeal(appeal_id: AppealId) -> Result<Appeal<T>, DispatchError> {
+        Ok(Self::appeal_by_id(appeal_id).ok_or(Error::<T>::AppealNotFound)?)
+    }
+
     /// Get entity space_id if it exists.
     /// Content and Account has no scope, consider check with `if let Some`
     fn get_entity_scope(entity: &EntityId<T::AccountId>) -> Result<Option<SpaceId>, DispatchError> {
@@ -45,17 +51,172 @@ impl<T: Trait> Module<T> {
         }.map_err(|_| Error::<T>::EntityNotFound.into())
     }
 
+    /// Block the entity in the space. Side effects of the block are postponed
+    /// until the appeal period is over, unless there is no appeal period at all.
     pub(crate) fn block_entity_in_scope(entity: &EntityId<T::AccountId>, scope: SpaceId) -> DispatchResult {
+        let appeal_period = T::AppealPeriod::get();
+
+        if appeal_period.is_zero() {
+            Self::apply_block_side_effects(entity, scope)?;
+        } else {
+            let appealable_until = <system::Module<T>>::block_number() + appeal_period;
+            AppealableUntilByEntityInSpace::<T>::insert(entity, scope, appealable_until);
+            BlocksByAppealDeadline::<T>::mutate(appealable_until, |blocks| blocks.push((entity.clone(), scope)));
+        }
+
+        StatusByEntityInSpace::<T>::insert(entity, scope, EntityStatus::Blocked);
+        Self::track_moderated_entity(entity, scope);
+        Ok(())
+    }
+
+    /// Remember that the entity has a status or suggestions in the space.
+    pub(crate) fn track_moderated_entity(entity: &EntityId<T::AccountId>, scope: SpaceId) {
+        ModeratedEntitiesBySpaceId::<T>::mutate(scope, |entities| {
+            if !entities.contains(entity) {
+                entities.push(entity.clone());
+            }
+        });
+    }
+
+    /// Remove what is known about the entity in a deleted space.
+    fn remove_entity_in_space(entity: &EntityId<T::AccountId>, space_id: SpaceId) {
+        <ReportIdsByEntityInSpace<T>>::remove(entity, space_id);
+        <StatusByEntityInSpace<T>>::remove(entity, space_id);
+        <SuggestedStatusesByEntityInSpace<T>>::remove(entity, space_id);
+        <AutoblockStateByEntityInSpace<T>>::remove(entity, space_id);
+        <AppealableUntilByEntityInSpace<T>>::remove(entity, space_id);
+    }
+
+    /// Apply what a block of the entity implies for the space, if the entity is still in it.
+    /// The block cannot be appealed after that.
+    pub(crate) fn apply_block_side_effects(entity: &EntityId<T::AccountId>, scope: SpaceId) -> DispatchResult {
+        AppealableUntilByEntityInSpace::<T>::remove(entity, scope);
+
+        if Self::ensure_entity_in_scope(entity, scope).is_err() {
+            return Ok(());
+        }
+
         // TODO: update counters, when entity is moved
         // TODO: think, what and where we should change something if entity is moved
         match entity {
             EntityId::Content(_) => (),
-            EntityId::Account(account_id)
-                => SpaceFollows::<T>::unfollow_space_by_account(account_id.clone(), scope)?,
+            EntityId::Account(account_id) => {
+                // A blocked account should not stay among the space followers.
+                if SpaceFollows::<T>::space_followed_by_account((account_id.clone(), scope)) {
+                    SpaceFollows::<T>::unfollow_space_by_account(account_id.clone(), scope)?;
+                }
+            },
             EntityId::Space(space_id) => Spaces::<T>::try_move_space_to_root(*space_id)?,
             EntityId::Post(post_id) => Posts::<T>::delete_post_from_space(*post_id)?,
         }
-        StatusByEntityInSpace::<T>::insert(entity, scope, EntityStatus::Blocked);
+        Ok(())
+    }
+
+    /// Set the final status of the entity in the space, as decided by a space moderator.
+    /// Unresolved reports about this entity get resolved with the new status.
+    pub(crate) fn set_final_entity_status(
+        entity: &EntityId<T::AccountId>,
+        scope: SpaceId,
+        status_opt: Option<EntityStatus>
+    ) -> DispatchResult {
+        // A previous block is replaced by this decision, so is its appeal period.
+        AppealableUntilByEntityInSpace::<T>::remove(entity, scope);
+
+        if let Some(status) = status_opt {
+            if status == EntityStatus::Blocked {
+                Self::block_entity_in_scope(entity, scope)?;
+            } else {
+                StatusByEntityInSpace::<T>::insert(entity, scope, status);
+                Self::track_moderated_entity(entity, scope);
+            }
+
+            Self::resolve_reports_on_entity(entity, scope, status);
+        } else {
+            StatusByEntityInSpace::<T>::remove(entity, scope);
+        }
+
+        // This is a final decision, so the entity is not under review anymore.
+        AutoblockStateByEntityInSpace::<T>::remove(entity, scope);
+        Ok(())
+    }
+
+    fn resolve_reports_on_entity(entity: &EntityId<T::AccountId>, scope: SpaceId, status: EntityStatus) {
+        let mut resolved_report_ids = Vec::new();
+
+        for report_id in Self::report_ids_by_entity_in_space(entity, scope) {
+            if let Some(mut report) = Self::report_by_id(report_id) {
+                if report.is_unresolved() {
+                    report.status = ReportStatus::Resolved(status);
+                    ReportById::<T>::insert(report_id, report);
+                    resolved_report_ids.push(report_id);
+                }
+            }
+        }
+
+        if !resolved_report_ids.is_empty() {
+            UnresolvedReportIdsBySpaceId::mutate(scope, |ids| ids.retain(|id| !resolved_report_ids.contains(id)));
+        }
+    }
+
+    /// Block the entity in the space automatically, if the number of distinct accounts
+    /// that reported it or suggested to block it reached the autoblock threshold of the space.
+    /// Only unresolved reports and suggestions based on them (or on no report) are counted,
+    /// so reports that moderators have already dismissed or resolved cannot block the entity again.
+    pub(crate) fn maybe_autoblock_entity(entity: &EntityId<T::AccountId>, scope: SpaceId) -> DispatchResult {
+        // Neither override a decision of moderators, nor block the entity under review.
+        let has_status = Self::status_by_entity_in_space(entity, scope).is_some();
+        if has_status || Self::autoblock_state(entity, scope).is_some() {
+            return Ok(());
+        }
+
+        let autoblock_threshold = match Self::moderation_settings(scope)
+            .unwrap_or_else(Self::default_autoblock_threshold_as_settings)
+            .autoblock_threshold
+        {
+            Some(threshold) => threshold,
+            None => return Ok(()),
+        };
+
+        let mut accounts: BTreeSet<T::AccountId> = Self::report_ids_by_entity_in_space(entity, scope)
+            .into_iter()
+            .filter_map(Self::report_by_id)
+            .filter(|report| report.is_unresolved())
+            .map(|report| report.created.account)
+            .collect();
+
+        let is_based_on_closed_report = |report_id_opt: Option<ReportId>| {
+            report_id_opt
+                .and_then(Self::report_by_id)
+                .map_or(false, |report| !report.is_unresolved())
+        };
+
+        accounts.extend(
+            Self::suggested_statuses(entity, scope).into_iter()
+                .filter(|suggestion| suggestion.status == Some(EntityStatus::Blocked))
+                .filter(|suggestion| !is_based_on_closed_report(suggestion.report_id))
+                .map(|suggestion| suggestion.suggested.account)
+        );
+
+        if accounts.len() >= autoblock_threshold as usize {
+            Self::block_entity_in_scope(entity, scope)?;
+            AutoblockStateByEntityInSpace::<T>::insert(entity, scope, AutoblockState::Blocked);
+            Self::deposit_event(RawEvent::EntityAutoBlocked(scope, entity.clone()));
+        }
+
+        Ok(())
+    }
+
+    /// Ensure that the account is affected by the entity status: it's either this account itself
+    /// or an owner of this space or post.
+    pub(crate) fn ensure_entity_owner(who: &T::AccountId, entity: &EntityId<T::AccountId>) -> DispatchResult {
+        let is_owner = match entity {
+            EntityId::Content(_) => false,
+            EntityId::Account(account_id) => account_id == who,
+            EntityId::Space(space_id) => Spaces::<T>::require_space(*space_id)?.is_owner(who),
+            EntityId::Post(post_id) => Posts::<T>::require_post(*post_id)?.is_owner(who),
+        };
+
+        ensure!(is_owner, Error::<T>::NotAnEntityOwner);
         Ok(())
     }
 
@@ -77,9 +238,24 @@ impl<T: Trait> Module<T> {
 
     pub fn default_autoblock_threshold_as_settings() -> SpaceModerationSettings {
         SpaceModerationSettings {
-            autoblock_threshold: Some(T::DefaultAutoblockThreshold::get())
+            autoblock_threshold: Some(T::DefaultAutoblockThreshold::get()),
+            inherit_global_blocklist: true,
         }
     }
+
+    /// Whether globally blocked entities are considered blocked in this space.
+    pub fn inherits_global_blocklist(scope: SpaceId) -> bool {
+        Self::moderation_settings(scope).map_or(true, |settings| settings.inherit_global_blocklist)
+    }
+
+    /// Get an entity status in the space, taking the global blocklist into account first.
+    pub fn effective_entity_status(entity: &EntityId<T::AccountId>, scope: SpaceId) -> Option<EntityStatus> {
+        if Self::is_blocked_globally(entity) && Self::inherits_global_blocklist(scope) {
+            return Some(EntityStatus::Blocked);
+        }
+
+        Self::status_by_entity_in_space(entity, scope)
+    }
 }
 
 impl<T: Trait> Report<T> {
@@ -95,7 +271,33 @@ impl<T: Trait> Report<T> {
             created: WhoAndWhen::<T>::new(created_by),
             reported_entity,
             reported_within: scope,
-            reason
+            reason,
+            status: ReportStatus::Open,
+        }
+    }
+
+    /// Whether this report still waits for a final decision of space moderators.
+    pub fn is_unresolved(&self) -> bool {
+        self.status == ReportStatus::Open || self.status == ReportStatus::UnderReview
+    }
+}
+
+impl<T: Trait> Appeal<T> {
+    pub fn new(
+        id: AppealId,
+        created_by: T::AccountId,
+        appealed_entity: EntityId<T::AccountId>,
+        scope: SpaceId,
+        reason: Content
+    ) -> Self {
+        Self {
+            id,
+            created: WhoAndWhen::<T>::new(created_by),
+            resolved: None,
+            appealed_entity,
+            appealed_within: scope,
+            reason,
+            status: AppealStatus::Pending,
         }
     }
 }
@@ -115,13 +317,13 @@ impl<T: Trait> IsAccountBlocked<T::AccountId> for Module<T> {
     fn is_blocked_account(account: T::AccountId, scope: SpaceId) -> bool {
         let entity = EntityId::Account(account);
 
-        Self::status_by_entity_in_space(entity, scope) == Some(EntityStatus::Blocked)
+        Self::effective_entity_status(&entity, scope) == Some(EntityStatus::Blocked)
     }
 
     fn is_allowed_account(account: T::AccountId, scope: SpaceId) -> bool {
         let entity = EntityId::Account(account);
 
-        Self::status_by_entity_in_space(entity, scope) != Some(EntityStatus::Blocked)
+        Self::effective_entity_status(&entity, scope) != Some(EntityStatus::Blocked)
     }
 }
 
@@ -129,13 +331,13 @@ impl<T: Trait> IsSpaceBlocked for Module<T> {
     fn is_blocked_space(space_id: SpaceId, scope: SpaceId) -> bool {
         let entity = EntityId::Space(space_id);
 
-        Self::status_by_entity_in_space(entity, scope) == Some(EntityStatus::Blocked)
+        Self::effective_entity_status(&entity, scope) == Some(EntityStatus::Blocked)
     }
 
     fn is_allowed_space(space_id: SpaceId, scope: SpaceId) -> bool {
         let entity = EntityId::Space(space_id);
 
-        Self::status_by_entity_in_space(entity, scope) != Some(EntityStatus::Blocked)
+        Self::effective_entity_status(&entity, scope) != Some(EntityStatus::Blocked)
     }
 }
 
@@ -143,13 +345,13 @@ impl<T: Trait> IsPostBlocked<PostId> for Module<T> {
     fn is_blocked_post(post_id: PostId, scope: SpaceId) -> bool {
         let entity = EntityId::Post(post_id);
 
-        Self::status_by_entity_in_space(entity, scope) == Some(EntityStatus::Blocked)
+        Self::effective_entity_status(&entity, scope) == Some(EntityStatus::Blocked)
     }
 
     fn is_allowed_post(post_id: PostId, scope: SpaceId) -> bool {
         let entity = EntityId::Post(post_id);
 
-        Self::status_by_entity_in_space(entity, scope) != Some(EntityStatus::Blocked)
+        Self::effective_entity_status(&entity, scope) != Some(EntityStatus::Blocked)
     }
 }
 
@@ -157,12 +359,98 @@ impl<T: Trait> IsContentBlocked for Module<T> {
     fn is_blocked_content(content: Content, scope: SpaceId) -> bool {
         let entity = EntityId::Content(content);
 
-        Self::status_by_entity_in_space(entity, scope) == Some(EntityStatus::Blocked)
+        Self::effective_entity_status(&entity, scope) == Some(EntityStatus::Blocked)
     }
 
     fn is_allowed_content(content: Content, scope: SpaceId) -> bool {
         let entity = EntityId::Content(content);
 
-        Self::status_by_entity_in_space(entity, scope) != Some(EntityStatus::Blocked)
+        Self::effective_entity_status(&entity, scope) != Some(EntityStatus::Blocked)
+    }
+}
+
+impl<T: Trait> SpaceCleaner for Module<T> {
+    fn clean_up_space(space_id: SpaceId, limit: u32) -> u32 {
+        let mut removed = 0u32;
+        let mut report_ids = Self::report_ids_by_space_id(space_id);
+
+        while removed < limit {
+            let report_id = match report_ids.pop() {
+                Some(report_id) => report_id,
+                None => break,
+            };
+
+            if let Some(report) = Self::report_by_id(report_id) {
+                let entity = report.reported_entity;
+                let reporter = report.created.account;
+
+                // The same account could report this entity in another space.
+                if Self::report_id_by_account((entity.clone(), reporter.clone())) == Some(report_id) {
+                    <ReportIdByAccount<T>>::remove((entity.clone(), reporter));
+                }
+
+                Self::remove_entity_in_space(&entity, space_id);
+                <ReportById<T>>::remove(report_id);
+            }
+
+            removed += 1;
+        }
+
+        let mut appeal_ids = Self::appeal_ids_by_space_id(space_id);
+
+        while removed < limit {
+            let appeal_id = match appeal_ids.pop() {
+                Some(appeal_id) => appeal_id,
+                None => break,
+            };
+
+            if let Some(appeal) = Self::appeal_by_id(appeal_id) {
+                <PendingAppealIdByEntityInSpace<T>>::remove(&appeal.appealed_entity, space_id);
+                <AppealById<T>>::remove(appeal_id);
+            }
+
+            removed += 1;
+        }
+
+        // Statuses and suggestions can be set without any report.
+        let mut entities = Self::moderated_entities_by_space_id(space_id);
+
+        while removed < limit {
+            let entity = match entities.pop() {
+                Some(entity) => entity,
+                None => break,
+            };
+
+            Self::remove_entity_in_space(&entity, space_id);
+            removed += 1;
+        }
+
+        let is_space_cleaned_up = report_ids.is_empty() && appeal_ids.is_empty() && entities.is_empty();
+
+        if report_ids.is_empty() {
+            ReportIdsBySpaceId::remove(space_id);
+            UnresolvedReportIdsBySpaceId::remove(space_id);
+        } else {
+            ReportIdsBySpaceId::insert(space_id, report_ids);
+        }
+
+        if appeal_ids.is_empty() {
+            AppealIdsBySpaceId::remove(space_id);
+            PendingAppealIdsBySpaceId::remove(space_id);
+        } else {
+            AppealIdsBySpaceId::insert(space_id, appeal_ids);
+        }
+
+        if entities.is_empty() {
+            <ModeratedEntitiesBySpaceId<T>>::remove(space_id);
+        } else {
+            <ModeratedEntitiesBySpaceId<T>>::insert(space_id, entities);
+        }
+
+        if is_space_cleaned_up {
+            ModerationSettings::remove(space_id);
+        }
+
+        removed
     }
 }
@@ -7,9 +7,27 @@
 //! `Block` or `Allowed`. A space owner can make a final decision: either block or allow any entity
 //! within the space they control.
 //!
-//! This pallet also has a setting to auto-block the content after a specific number of statuses
-//! from moderators that suggest to block the entity. If the entity is added to allow list,
-//! then the entity cannot be blocked.
+//! This pallet also has a setting to auto-block the entity after a specific number of distinct
+//! accounts reported it or suggested to block it. If the entity is added to allow list,
+//! then the entity cannot be blocked. The affected account can appeal an automatic block:
+//! the entity is unblocked and stays under review until a space moderator makes a final decision.
+//!
+//! A block can be appealed within `AppealPeriod` blocks. Until then, the blocked entity stays
+//! where it is: a blocked post is not removed from the space, a blocked subspace is not moved
+//! to the root and a blocked account keeps following the space. These side effects are applied
+//! when the appeal period is over and the entity has not been appealed, or when a space moderator
+//! rejects the appeal. So an accepted appeal leaves nothing to restore.
+//!
+//! Every report goes through a lifecycle: it's `Open` until a moderator suggests a status based
+//! on it (`UnderReview`), then it's either `Resolved` with a final entity status or `Dismissed`.
+//! An account affected by a block can appeal it with a reason, and space moderators resolve
+//! such appeals. Unresolved reports and pending appeals are indexed per space,
+//! so moderators can see their queue.
+//!
+//! Besides the space-level statuses, there is a network-wide blocklist maintained by
+//! `GlobalModeratorOrigin`. Globally blocked entities are considered blocked in every space,
+//! unless a space opted out of inheriting the global blocklist via its moderation settings.
+//! A global block cannot be appealed: only `GlobalModeratorOrigin` can unblock the entity.
 //!
 //! The next rules applied to the blocked entities:
 //!
@@ -24,7 +42,7 @@ use sp_runtime::RuntimeDebug;
 use frame_support::{
     decl_module, decl_storage, decl_event, decl_error, ensure,
     dispatch::DispatchResult,
-    traits::Get,
+    traits::{Get, EnsureOrigin},
 };
 use frame_system::{self as system, ensure_signed};
 
@@ -42,6 +60,7 @@ mod tests;
 pub mod functions;
 
 pub type ReportId = u64;
+pub type AppealId = u64;
 
 #[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
 pub enum EntityId<AccountId> {
@@ -70,6 +89,55 @@ pub struct Report<T: Trait> {
     reported_within: SpaceId, // TODO rename: reported_in_space
     /// A reason should describe why this entity should be blocked in this space.
     reason: Content,
+    status: ReportStatus,
+}
+
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq, RuntimeDebug)]
+pub enum ReportStatus {
+    /// No moderator has suggested any entity status based on this report yet.
+    Open,
+    /// At least one moderator has suggested an entity status based on this report.
+    UnderReview,
+    /// A final status has been set for the reported entity.
+    Resolved(EntityStatus),
+    /// A moderator rejected this report, e.g. as invalid or a duplicate.
+    Dismissed,
+}
+
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+pub struct Appeal<T: Trait> {
+    id: AppealId,
+    created: WhoAndWhen<T>,
+    /// An account id of a moderator who resolved this appeal.
+    resolved: Option<WhoAndWhen<T>>,
+    /// A blocked entity this appeal is about.
+    appealed_entity: EntityId<T::AccountId>,
+    /// Within what space (scope) this entity has been blocked.
+    appealed_within: SpaceId,
+    /// A reason should describe why this entity should not be blocked in this space.
+    reason: Content,
+    status: AppealStatus,
+}
+
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq, RuntimeDebug)]
+pub enum AppealStatus {
+    /// Waiting for a space moderator to resolve it.
+    Pending,
+    /// The entity is not blocked in the space anymore.
+    Accepted,
+    /// The entity stays blocked in the space.
+    Rejected,
+}
+
+/// A state of an entity that was blocked automatically, because the number of accounts
+/// that reported it or suggested to block it reached the autoblock threshold of a space.
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq, RuntimeDebug)]
+pub enum AutoblockState {
+    /// The entity is blocked, but the affected account still can appeal it.
+    Blocked,
+    /// The affected account appealed the autoblock. The entity is not blocked
+    /// and cannot be blocked automatically until a space moderator reviews it.
+    UnderReview,
 }
 
 // TODO rename to SuggestedEntityStatus
@@ -87,13 +155,16 @@ pub struct SuggestedStatus<T: Trait> {
 // TODO rename to ModerationSettings?
 #[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
 pub struct SpaceModerationSettings {
-    autoblock_threshold: Option<u16>
+    autoblock_threshold: Option<u16>,
+    /// Whether entities blocked globally are considered blocked in this space too.
+    inherit_global_blocklist: bool,
 }
 
 // TODO rename to ModerationSettingsUpdate?
 #[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
 pub struct SpaceModerationSettingsUpdate {
-    pub autoblock_threshold: Option<Option<u16>>
+    pub autoblock_threshold: Option<Option<u16>>,
+    pub inherit_global_blocklist: Option<bool>,
 }
 
 /// The pallet's configuration trait.
@@ -107,6 +178,13 @@ pub trait Trait: system::Trait
     type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
 
     type DefaultAutoblockThreshold: Get<u16>;
+
+    /// An origin that can block and unblock entities network-wide.
+    type GlobalModeratorOrigin: EnsureOrigin<Self::Origin>;
+
+    /// How many blocks an affected account has to appeal a block of an entity in a space.
+    /// If zero, blocks cannot be appealed and their side effects are applied immediately.
+    type AppealPeriod: Get<Self::BlockNumber>;
 }
 
 // This pallet's storage items.
@@ -149,6 +227,64 @@ decl_storage! {
             hasher(twox_64_concat) SpaceId
             => Vec<SuggestedStatus<T>>;
 
+        /// A state of an entity (key 1) that was blocked automatically in this space (key 2).
+        pub AutoblockStateByEntityInSpace get(fn autoblock_state): double_map
+            hasher(twox_64_concat) EntityId<T::AccountId>,
+            hasher(twox_64_concat) SpaceId
+            => Option<AutoblockState>;
+
+        /// Ids of reports in this space (key) that are neither resolved nor dismissed yet.
+        pub UnresolvedReportIdsBySpaceId get(fn unresolved_report_ids_by_space_id):
+            map hasher(twox_64_concat) SpaceId
+            => Vec<ReportId>;
+
+        /// An id for the next appeal.
+        pub NextAppealId get(fn next_appeal_id): AppealId = 1;
+
+        /// Appeal details by its id (key).
+        pub AppealById get(fn appeal_by_id):
+            map hasher(twox_64_concat) AppealId
+            => Option<Appeal<T>>;
+
+        /// Ids of all appeals in this space (key).
+        pub AppealIdsBySpaceId get(fn appeal_ids_by_space_id):
+            map hasher(twox_64_concat) SpaceId
+            => Vec<AppealId>;
+
+        /// Ids of appeals in this space (key) that wait for a space moderator to resolve them.
+        pub PendingAppealIdsBySpaceId get(fn pending_appeal_ids_by_space_id):
+            map hasher(twox_64_concat) SpaceId
+            => Vec<AppealId>;
+
+        /// An id of a pending appeal of the entity (key 1) status in this space (key 2).
+        pub PendingAppealIdByEntityInSpace get(fn pending_appeal_id_by_entity_in_space): double_map
+            hasher(twox_64_concat) EntityId<T::AccountId>,
+            hasher(twox_64_concat) SpaceId
+            => Option<AppealId>;
+
+        /// A block number until which a block of the entity (key 1) in this space (key 2)
+        /// can be appealed. Side effects of the block are applied after that.
+        pub AppealableUntilByEntityInSpace get(fn appealable_until): double_map
+            hasher(twox_64_concat) EntityId<T::AccountId>,
+            hasher(twox_64_concat) SpaceId
+            => Option<T::BlockNumber>;
+
+        /// Blocked entities and their spaces, whose appeal period ends at this block number (key).
+        BlocksByAppealDeadline get(fn blocks_by_appeal_deadline):
+            map hasher(twox_64_concat) T::BlockNumber
+            => Vec<(EntityId<T::AccountId>, SpaceId)>;
+
+        /// Whether an entity (key) is blocked network-wide.
+        pub BlockedGlobally get(fn is_blocked_globally):
+            map hasher(twox_64_concat) EntityId<T::AccountId>
+            => bool;
+
+        /// Entities that got a status or a suggested status in this space (key),
+        /// so that their moderation data can be removed together with the space.
+        pub ModeratedEntitiesBySpaceId get(fn moderated_entities_by_space_id):
+            map hasher(twox_64_concat) SpaceId
+            => Vec<EntityId<T::AccountId>>;
+
         /// A custom moderation settings for a certain space (key).
         pub ModerationSettings get(fn moderation_settings):
             map hasher(twox_64_concat) SpaceId
@@ -166,7 +302,13 @@ decl_event!(
         EntityStatusSuggested(AccountId, SpaceId, EntityId, Option<EntityStatus>),
         EntityStatusUpdated(AccountId, SpaceId, EntityId, Option<EntityStatus>),
         EntityStatusDeleted(AccountId, SpaceId, EntityId),
+        EntityAutoBlocked(SpaceId, EntityId),
+        EntityStatusAppealed(AccountId, SpaceId, EntityId, AppealId),
+        AppealResolved(AccountId, SpaceId, AppealId, AppealStatus),
+        ReportDismissed(AccountId, SpaceId, ReportId),
         ModerationSettingsUpdated(AccountId, SpaceId),
+        EntityBlockedGlobally(EntityId),
+        EntityUnblockedGlobally(EntityId),
     }
 );
 
@@ -202,6 +344,25 @@ decl_error! {
         SuggestedStatusInWrongScope,
         /// Entity status has already been suggested by this moderator account.
         AlreadySuggestedEntityStatus,
+        /// Only a blocked entity can be appealed.
+        EntityIsNotBlocked,
+        /// The entity status in this space has already been appealed and waits for a decision.
+        AlreadyAppealedEntityStatus,
+        /// Appeal was not found by its id.
+        AppealNotFound,
+        /// Appeal has already been resolved.
+        AppealIsAlreadyResolved,
+        /// Report has already been resolved or dismissed.
+        ReportIsAlreadyClosed,
+        /// The entity is already blocked network-wide.
+        EntityIsAlreadyBlockedGlobally,
+        /// The entity is not blocked network-wide. Nothing to unblock.
+        EntityIsNotBlockedGlobally,
+        /// Only an affected account can appeal: the blocked account itself
+        /// or an owner of the blocked space or post.
+        NotAnEntityOwner,
+        /// The appeal period of this block is over.
+        AppealPeriodIsOver,
     }
 }
 
@@ -212,12 +373,35 @@ decl_module! {
 
         const DefaultAutoblockThreshold: u16 = T::DefaultAutoblockThreshold::get();
 
+        /// How many blocks an affected account has to appeal a block of an entity in a space.
+        const AppealPeriod: T::BlockNumber = T::AppealPeriod::get();
+
         // Initializing errors
         type Error = Error<T>;
 
         // Initializing events
         fn deposit_event() = default;
 
+        fn on_finalize(block_number: T::BlockNumber) {
+            for (entity, scope) in BlocksByAppealDeadline::<T>::take(block_number) {
+                // The entity could be unblocked or blocked again since then.
+                if Self::appealable_until(&entity, scope) != Some(block_number) {
+                    continue;
+                }
+
+                AppealableUntilByEntityInSpace::<T>::remove(&entity, scope);
+
+                let is_blocked = Self::status_by_entity_in_space(&entity, scope) == Some(EntityStatus::Blocked);
+                let is_appealed = Self::pending_appeal_id_by_entity_in_space(&entity, scope).is_some();
+
+                // A pending appeal postpones the side effects until a space moderator resolves it.
+                if is_blocked && !is_appealed {
+                    // The block stays in force even if the entity cannot be moved anymore.
+                    let _ = Self::apply_block_side_effects(&entity, scope);
+                }
+            }
+        }
+
         /// Report any entity by any person with mandatory reason.
         /// `entity` scope and the `scope` provided mustn't differ
         #[weight = 10_000 + T::DbWeight::get().reads_writes(6, 5)]
@@ -246,10 +430,13 @@ decl_module! {
             ReportById::<T>::insert(report_id, new_report);
             ReportIdByAccount::<T>::insert((&entity, &who), report_id);
             ReportIdsBySpaceId::mutate(scope, |ids| ids.push(report_id));
+            UnresolvedReportIdsBySpaceId::mutate(scope, |ids| ids.push(report_id));
             ReportIdsByEntityInSpace::<T>::mutate(&entity, scope, |ids| ids.push(report_id));
             NextReportId::mutate(|n| { *n += 1; });
 
-            Self::deposit_event(RawEvent::EntityReported(who, scope, entity, report_id));
+            Self::deposit_event(RawEvent::EntityReported(who, scope, entity.clone(), report_id));
+
+            Self::maybe_autoblock_entity(&entity, scope)?;
             Ok(())
         }
 
@@ -265,8 +452,8 @@ decl_module! {
         ) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
-            if let Some(report_id) = report_id_opt {
-                let report = Self::require_report(report_id)?;
+            let report_opt = report_id_opt.map(Self::require_report).transpose()?;
+            if let Some(report) = &report_opt {
                 ensure!(scope == report.reported_within, Error::<T>::SuggestedStatusInWrongScope);
             }
 
@@ -286,23 +473,19 @@ decl_module! {
             ensure!(!is_already_suggested, Error::<T>::AlreadySuggestedEntityStatus);
             suggestions.push(SuggestedStatus::new(who.clone(), status.clone(), report_id_opt));
 
-            let block_suggestions_total = suggestions.iter()
-                .filter(|suggestion| suggestion.status == Some(EntityStatus::Blocked))
-                .count();
-
-            let autoblock_threshold_opt = Self::moderation_settings(scope)
-                .unwrap_or_else(Self::default_autoblock_threshold_as_settings)
-                .autoblock_threshold;
+            SuggestedStatusesByEntityInSpace::<T>::insert(entity.clone(), scope, suggestions);
+            Self::track_moderated_entity(&entity, scope);
 
-            if let Some(autoblock_threshold) = autoblock_threshold_opt {
-                if block_suggestions_total >= autoblock_threshold as usize {
-                    Self::block_entity_in_scope(&entity, scope)?;
+            if let Some(mut report) = report_opt {
+                if report.status == ReportStatus::Open {
+                    report.status = ReportStatus::UnderReview;
+                    ReportById::<T>::insert(report.id, report);
                 }
             }
 
-            SuggestedStatusesByEntityInSpace::<T>::insert(entity.clone(), scope, suggestions);
+            Self::deposit_event(RawEvent::EntityStatusSuggested(who, scope, entity.clone(), status));
 
-            Self::deposit_event(RawEvent::EntityStatusSuggested(who, scope, entity, status));
+            Self::maybe_autoblock_entity(&entity, scope)?;
             Ok(())
         }
 
@@ -322,17 +505,7 @@ decl_module! {
             let space = Spaces::<T>::require_space(scope).map_err(|_| Error::<T>::ScopeNotFound)?;
             Self::ensure_account_status_manager(who.clone(), &space)?;
 
-            if let Some(status) = &status_opt {
-                let is_entity_in_scope = Self::ensure_entity_in_scope(&entity, scope).is_ok();
-
-                if is_entity_in_scope && status == &EntityStatus::Blocked {
-                    Self::block_entity_in_scope(&entity, scope)?;
-                } else {
-                    StatusByEntityInSpace::<T>::insert(entity.clone(), scope, status);
-                }
-            } else {
-                StatusByEntityInSpace::<T>::remove(entity.clone(), scope);
-            }
+            Self::set_final_entity_status(&entity, scope, status_opt)?;
 
             Self::deposit_event(RawEvent::EntityStatusUpdated(who, scope, entity, status_opt));
             Ok(())
@@ -354,11 +527,127 @@ decl_module! {
             Self::ensure_account_status_manager(who.clone(), &space)?;
 
             StatusByEntityInSpace::<T>::remove(&entity, scope);
+            AutoblockStateByEntityInSpace::<T>::remove(&entity, scope);
+            AppealableUntilByEntityInSpace::<T>::remove(&entity, scope);
 
             Self::deposit_event(RawEvent::EntityStatusDeleted(who, scope, entity));
             Ok(())
         }
 
+        /// Appeal a block of an entity in a space with a mandatory reason.
+        /// Only the affected account can appeal: the blocked account itself
+        /// or an owner of the blocked space or post. A block can be appealed
+        /// only within `AppealPeriod` blocks, before its side effects are applied.
+        ///
+        /// If the entity was blocked automatically, it gets unblocked and stays under review
+        /// until a space moderator resolves the appeal.
+        ///
+        /// Only a block in this space can be appealed. An entity in `BlockedGlobally` stays
+        /// blocked even if its appeal is accepted, until `GlobalModeratorOrigin` unblocks it.
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(6, 6)]
+        pub fn appeal_entity_status(
+            origin,
+            entity: EntityId<T::AccountId>,
+            scope: SpaceId,
+            reason: Content
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            Utils::<T>::ensure_content_is_some(&reason).map_err(|_| Error::<T>::ReasonIsEmpty)?;
+            Utils::<T>::is_valid_content(reason.clone())?;
+
+            let not_appealed_yet = Self::pending_appeal_id_by_entity_in_space(&entity, scope).is_none();
+            ensure!(not_appealed_yet, Error::<T>::AlreadyAppealedEntityStatus);
+
+            let is_blocked = Self::status_by_entity_in_space(&entity, scope) == Some(EntityStatus::Blocked);
+            ensure!(is_blocked, Error::<T>::EntityIsNotBlocked);
+
+            let is_appealable = Self::appealable_until(&entity, scope).is_some();
+            ensure!(is_appealable, Error::<T>::AppealPeriodIsOver);
+
+            Self::ensure_entity_owner(&who, &entity)?;
+
+            if Self::autoblock_state(&entity, scope) == Some(AutoblockState::Blocked) {
+                StatusByEntityInSpace::<T>::remove(&entity, scope);
+                AutoblockStateByEntityInSpace::<T>::insert(&entity, scope, AutoblockState::UnderReview);
+            }
+
+            let appeal_id = Self::next_appeal_id();
+            let new_appeal = Appeal::<T>::new(appeal_id, who.clone(), entity.clone(), scope, reason);
+
+            AppealById::<T>::insert(appeal_id, new_appeal);
+            AppealIdsBySpaceId::mutate(scope, |ids| ids.push(appeal_id));
+            PendingAppealIdsBySpaceId::mutate(scope, |ids| ids.push(appeal_id));
+            PendingAppealIdByEntityInSpace::<T>::insert(&entity, scope, appeal_id);
+            NextAppealId::mutate(|n| { *n += 1; });
+
+            Self::deposit_event(RawEvent::EntityStatusAppealed(who, scope, entity, appeal_id));
+            Ok(())
+        }
+
+        /// Allows a space owner/admin to resolve a pending appeal by setting
+        /// the final status of the appealed entity.
+        /// The appeal is accepted, unless the entity stays blocked. If the appeal is rejected,
+        /// side effects of the block are applied immediately.
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(8, 8)]
+        pub fn resolve_appeal(
+            origin,
+            appeal_id: AppealId,
+            status_opt: Option<EntityStatus>
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let mut appeal = Self::require_appeal(appeal_id)?;
+            ensure!(appeal.status == AppealStatus::Pending, Error::<T>::AppealIsAlreadyResolved);
+
+            let entity = appeal.appealed_entity.clone();
+            let scope = appeal.appealed_within;
+
+            let space = Spaces::<T>::require_space(scope).map_err(|_| Error::<T>::ScopeNotFound)?;
+            Self::ensure_account_status_manager(who.clone(), &space)?;
+
+            Self::set_final_entity_status(&entity, scope, status_opt)?;
+
+            appeal.status = if status_opt == Some(EntityStatus::Blocked) {
+                // Nothing can lift this block anymore, so there is no reason to wait for the appeal period.
+                Self::apply_block_side_effects(&entity, scope)?;
+                AppealStatus::Rejected
+            } else {
+                AppealStatus::Accepted
+            };
+            appeal.resolved = Some(WhoAndWhen::<T>::new(who.clone()));
+
+            let appeal_status = appeal.status;
+            AppealById::<T>::insert(appeal_id, appeal);
+            PendingAppealIdsBySpaceId::mutate(scope, |ids| ids.retain(|id| *id != appeal_id));
+            PendingAppealIdByEntityInSpace::<T>::remove(&entity, scope);
+
+            Self::deposit_event(RawEvent::EntityStatusUpdated(who.clone(), scope, entity, status_opt));
+            Self::deposit_event(RawEvent::AppealResolved(who, scope, appeal_id, appeal_status));
+            Ok(())
+        }
+
+        /// Allows a space owner/admin to dismiss a report that is neither resolved nor dismissed,
+        /// e.g. if it's invalid or a duplicate.
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(3, 2)]
+        pub fn dismiss_report(origin, report_id: ReportId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let mut report = Self::require_report(report_id)?;
+            ensure!(report.is_unresolved(), Error::<T>::ReportIsAlreadyClosed);
+
+            let scope = report.reported_within;
+            let space = Spaces::<T>::require_space(scope).map_err(|_| Error::<T>::ScopeNotFound)?;
+            Self::ensure_account_status_manager(who.clone(), &space)?;
+
+            report.status = ReportStatus::Dismissed;
+            ReportById::<T>::insert(report_id, report);
+            UnresolvedReportIdsBySpaceId::mutate(scope, |ids| ids.retain(|id| *id != report_id));
+
+            Self::deposit_event(RawEvent::ReportDismissed(who, scope, report_id));
+            Ok(())
+        }
+
         // todo: add ability to delete report_ids
 
         // TODO rename to update_settings?
@@ -370,7 +659,9 @@ decl_module! {
         ) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
-            let has_updates = update.autoblock_threshold.is_some();
+            let has_updates =
+                update.autoblock_threshold.is_some() ||
+                update.inherit_global_blocklist.is_some();
             ensure!(has_updates, Error::<T>::NoUpdatesForModerationSettings);
 
             let space = Spaces::<T>::require_space(space_id)?;
@@ -395,11 +686,45 @@ decl_module! {
                 }
             }
 
+            if let Some(inherit_global_blocklist) = update.inherit_global_blocklist {
+                if inherit_global_blocklist != settings.inherit_global_blocklist {
+                    settings.inherit_global_blocklist = inherit_global_blocklist;
+                    should_update = true;
+                }
+            }
+
             if should_update {
                 ModerationSettings::insert(space_id, settings);
                 Self::deposit_event(RawEvent::ModerationSettingsUpdated(who, space_id));
             }
             Ok(())
         }
+
+        /// Block an entity in all spaces that inherit the global blocklist.
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(1, 1)]
+        pub fn block_entity_globally(origin, entity: EntityId<T::AccountId>) -> DispatchResult {
+            T::GlobalModeratorOrigin::ensure_origin(origin)?;
+
+            ensure!(!Self::is_blocked_globally(&entity), Error::<T>::EntityIsAlreadyBlockedGlobally);
+
+            BlockedGlobally::<T>::insert(&entity, true);
+
+            Self::deposit_event(RawEvent::EntityBlockedGlobally(entity));
+            Ok(())
+        }
+
+        /// Remove an entity from the global blocklist.
+        /// Statuses of this entity in specific spaces stay untouched.
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(1, 1)]
+        pub fn unblock_entity_globally(origin, entity: EntityId<T::AccountId>) -> DispatchResult {
+            T::GlobalModeratorOrigin::ensure_origin(origin)?;
+
+            ensure!(Self::is_blocked_globally(&entity), Error::<T>::EntityIsNotBlockedGlobally);
+
+            BlockedGlobally::<T>::remove(&entity);
+
+            Self::deposit_event(RawEvent::EntityUnblockedGlobally(entity));
+            Ok(())
+        }
     }
 }
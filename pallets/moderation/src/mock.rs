@@ -1,8 +1,9 @@
-use crate::{Module, Trait, EntityId, EntityStatus, ReportId, SpaceModerationSettingsUpdate};
+use crate::{Module, Trait, EntityId, EntityStatus, ReportId, AppealId, SpaceModerationSettingsUpdate};
 use sp_core::H256;
 use frame_support::{
     impl_outer_origin, parameter_types, assert_ok, StorageMap,
     weights::Weight,
+    traits::{Get, OnFinalize},
     dispatch::{DispatchResult},
 };
 use sp_runtime::{
@@ -103,6 +104,10 @@ impl pallet_permissions::Trait for Test {
     type DefaultSpacePermissions = DefaultSpacePermissions;
 }
 
+parameter_types! {
+    pub const MaxItemsToCleanUpPerBlock: u16 = 100;
+}
+
 impl pallet_spaces::Trait for Test {
     type Event = ();
     type Currency = Balances;
@@ -110,9 +115,12 @@ impl pallet_spaces::Trait for Test {
     type SpaceFollows = SpaceFollows;
     type BeforeSpaceCreated = SpaceFollows;
     type AfterSpaceUpdated = ();
+    type AfterSpaceOwnerChanged = ();
     type IsAccountBlocked = Moderation;
     type IsContentBlocked = Moderation;
     type HandleDeposit = ();
+    type SpaceCleaner = ();
+    type MaxItemsToCleanUpPerBlock = MaxItemsToCleanUpPerBlock;
 }
 
 impl pallet_space_follows::Trait for Test {
@@ -131,6 +139,8 @@ impl pallet_posts::Trait for Test {
     type PostScores = ();
     type AfterPostUpdated = ();
     type IsPostBlocked = Moderation;
+    type SubscriptionPlans = ();
+    type PostCleaner = ();
 }
 
 parameter_types! {
@@ -153,11 +163,14 @@ impl pallet_profiles::Trait for Test {
 
 parameter_types! {
     pub const DefaultAutoblockThreshold: u16 = 20;
+    pub const AppealPeriod: u64 = 10;
 }
 
 impl Trait for Test {
     type Event = ();
     type DefaultAutoblockThreshold = DefaultAutoblockThreshold;
+    type GlobalModeratorOrigin = system::EnsureRoot<AccountId>;
+    type AppealPeriod = AppealPeriod;
 }
 
 type System = system::Module<Test>;
@@ -235,6 +248,8 @@ impl ExtBuilder {
 
 pub(crate) const ACCOUNT_SCOPE_OWNER: AccountId = 1;
 pub(crate) const ACCOUNT_NOT_MODERATOR: AccountId = 2;
+pub(crate) const ACCOUNT_TO_BLOCK: AccountId = 3;
+pub(crate) const FIRST_REPORTER: AccountId = 10;
 
 pub(crate) const SPACE1: SpaceId = RESERVED_SPACE_COUNT + 1;
 pub(crate) const SPACE2: SpaceId = SPACE1 + 1;
@@ -244,17 +259,21 @@ pub(crate) const POST1: PostId = 1;
 pub(crate) const REPORT1: ReportId = 1;
 pub(crate) const REPORT2: ReportId = 2;
 
+pub(crate) const APPEAL1: AppealId = 1;
+
 pub(crate) const AUTOBLOCK_THRESHOLD: u16 = 5;
 
 pub(crate) const fn new_autoblock_threshold() -> SpaceModerationSettingsUpdate {
     SpaceModerationSettingsUpdate {
-        autoblock_threshold: Some(Some(AUTOBLOCK_THRESHOLD))
+        autoblock_threshold: Some(Some(AUTOBLOCK_THRESHOLD)),
+        inherit_global_blocklist: None,
     }
 }
 
 pub(crate) const fn empty_moderation_settings_update() -> SpaceModerationSettingsUpdate {
     SpaceModerationSettingsUpdate {
-        autoblock_threshold: None
+        autoblock_threshold: None,
+        inherit_global_blocklist: None,
     }
 }
 
@@ -347,6 +366,94 @@ pub(crate) fn _delete_entity_status(
     )
 }
 
+pub(crate) fn _appeal_blocked_account() -> DispatchResult {
+    _appeal_entity_status(
+        Some(Origin::signed(ACCOUNT_TO_BLOCK)),
+        Some(EntityId::Account(ACCOUNT_TO_BLOCK)),
+        None,
+        None
+    )
+}
+
+pub(crate) fn _appeal_entity_status(
+    origin: Option<Origin>,
+    entity: Option<EntityId<AccountId>>,
+    scope: Option<SpaceId>,
+    reason: Option<Content>,
+) -> DispatchResult {
+    Moderation::appeal_entity_status(
+        origin.unwrap_or_else(|| Origin::signed(ACCOUNT_SCOPE_OWNER)),
+        entity.unwrap_or(EntityId::Post(POST1)),
+        scope.unwrap_or(SPACE1),
+        reason.unwrap_or_else(|| valid_content_ipfs()),
+    )
+}
+
+pub(crate) fn _resolve_appeal(
+    origin: Option<Origin>,
+    appeal_id: Option<AppealId>,
+    status_opt: Option<Option<EntityStatus>>,
+) -> DispatchResult {
+    Moderation::resolve_appeal(
+        origin.unwrap_or_else(|| Origin::signed(ACCOUNT_SCOPE_OWNER)),
+        appeal_id.unwrap_or(APPEAL1),
+        status_opt.unwrap_or(None),
+    )
+}
+
+pub(crate) fn _dismiss_report(origin: Option<Origin>, report_id: Option<ReportId>) -> DispatchResult {
+    Moderation::dismiss_report(
+        origin.unwrap_or_else(|| Origin::signed(ACCOUNT_SCOPE_OWNER)),
+        report_id.unwrap_or(REPORT1),
+    )
+}
+
+/// Block `ACCOUNT_TO_BLOCK` in `SPACE1` by a space owner.
+pub(crate) fn block_account_by_space_owner() {
+    assert_ok!(_update_entity_status(
+        None,
+        Some(EntityId::Account(ACCOUNT_TO_BLOCK)),
+        None,
+        Some(Some(EntityStatus::Blocked))
+    ));
+}
+
+/// Report the entity in `SPACE1` from `AUTOBLOCK_THRESHOLD` distinct accounts,
+/// starting from `FIRST_REPORTER`.
+/// Finalize the last block, in which entities blocked in the current block can be appealed.
+pub(crate) fn end_appeal_period() {
+    let appeal_deadline = System::block_number() + AppealPeriod::get();
+    System::set_block_number(appeal_deadline);
+    Moderation::on_finalize(appeal_deadline);
+}
+
+pub(crate) fn report_entity_up_to_autoblock_threshold(entity: EntityId<AccountId>) {
+    for reporter in FIRST_REPORTER..FIRST_REPORTER + AUTOBLOCK_THRESHOLD as AccountId {
+        assert_ok!(_report_entity(Some(Origin::signed(reporter)), Some(entity.clone()), None, None));
+    }
+}
+
+pub(crate) const fn opt_out_of_global_blocklist() -> SpaceModerationSettingsUpdate {
+    SpaceModerationSettingsUpdate {
+        autoblock_threshold: None,
+        inherit_global_blocklist: Some(false),
+    }
+}
+
+pub(crate) fn _block_account_globally(origin: Option<Origin>) -> DispatchResult {
+    Moderation::block_entity_globally(
+        origin.unwrap_or_else(Origin::root),
+        EntityId::Account(ACCOUNT_TO_BLOCK),
+    )
+}
+
+pub(crate) fn _unblock_account_globally(origin: Option<Origin>) -> DispatchResult {
+    Moderation::unblock_entity_globally(
+        origin.unwrap_or_else(Origin::root),
+        EntityId::Account(ACCOUNT_TO_BLOCK),
+    )
+}
+
 pub(crate) fn _update_autoblock_threshold_in_moderation_settings() -> DispatchResult {
     _update_moderation_settings(None, None, None)
 }
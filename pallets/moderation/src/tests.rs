@@ -2,6 +2,8 @@ use crate::{Error, mock::*};
 use crate::*;
 
 use frame_support::{assert_ok, assert_noop};
+use sp_runtime::DispatchError;
+use df_traits::moderation::IsAccountBlocked;
 use pallet_posts::PostById;
 use pallet_utils::{
     Error as UtilsError,
@@ -180,10 +182,17 @@ fn update_entity_status_should_work_for_status_blocked() {
             )
         );
 
+        // The post stays in its space, while its block can be appealed.
+        assert_eq!(Moderation::appealable_until(EntityId::Post(POST1), SPACE1), Some(1 + AppealPeriod::get()));
+        assert_eq!(PostById::<Test>::get(POST1).unwrap().space_id, Some(SPACE1));
+
+        end_appeal_period();
+
         // Check that post was removed from its space, 
         // because when removing a post, we set its space to None
         let post = PostById::<Test>::get(POST1).unwrap();
         assert!(post.space_id.is_none());
+        assert!(Moderation::appealable_until(EntityId::Post(POST1), SPACE1).is_none());
     });
 }
 
@@ -268,8 +277,6 @@ fn update_moderation_settings_should_work() {
     });
 }
 
-// TODO test that autoblock works
-
 #[test]
 fn update_moderation_settings_should_fail_when_no_updates_provided() {
     ExtBuilder::build_with_space_and_post().execute_with(|| {
@@ -305,3 +312,464 @@ fn update_moderation_settings_should_fail_when_origin_has_no_permission() {
         );
     });
 }
+
+// Autoblock
+//----------------------------------------------------------------------------
+
+#[test]
+fn report_entity_should_autoblock_post_when_threshold_reached() {
+    ExtBuilder::build_with_space_and_post().execute_with(|| {
+        assert_ok!(_update_autoblock_threshold_in_moderation_settings());
+
+        report_entity_up_to_autoblock_threshold(EntityId::Post(POST1));
+
+        let entity = EntityId::Post(POST1);
+        assert_eq!(Moderation::status_by_entity_in_space(&entity, SPACE1), Some(EntityStatus::Blocked));
+        assert_eq!(Moderation::autoblock_state(&entity, SPACE1), Some(AutoblockState::Blocked));
+
+        end_appeal_period();
+        assert!(PostById::<Test>::get(POST1).unwrap().space_id.is_none());
+    });
+}
+
+#[test]
+fn report_entity_should_not_autoblock_below_threshold() {
+    ExtBuilder::build_with_space_and_post().execute_with(|| {
+        assert_ok!(_update_autoblock_threshold_in_moderation_settings());
+
+        for reporter in FIRST_REPORTER..FIRST_REPORTER + AUTOBLOCK_THRESHOLD as AccountId - 1 {
+            assert_ok!(_report_entity(Some(Origin::signed(reporter)), None, None, None));
+        }
+
+        let entity = EntityId::Post(POST1);
+        assert!(Moderation::status_by_entity_in_space(&entity, SPACE1).is_none());
+        assert!(Moderation::autoblock_state(&entity, SPACE1).is_none());
+        assert_eq!(PostById::<Test>::get(POST1).unwrap().space_id, Some(SPACE1));
+    });
+}
+
+#[test]
+fn suggest_entity_status_should_count_reporters_and_suggesters_once() {
+    ExtBuilder::build_with_space_and_post().execute_with(|| {
+        assert_ok!(_update_moderation_settings(
+            None,
+            None,
+            Some(SpaceModerationSettingsUpdate {
+                autoblock_threshold: Some(Some(2)),
+                inherit_global_blocklist: None,
+            })
+        ));
+
+        // The same account reports the post and suggests to block it.
+        assert_ok!(_report_default_post());
+        assert_ok!(_suggest_blocked_status_for_post());
+
+        let entity = EntityId::Post(POST1);
+        assert!(Moderation::status_by_entity_in_space(&entity, SPACE1).is_none());
+
+        assert_ok!(_report_entity(Some(Origin::signed(FIRST_REPORTER)), None, None, None));
+        assert_eq!(Moderation::status_by_entity_in_space(&entity, SPACE1), Some(EntityStatus::Blocked));
+        assert_eq!(Moderation::autoblock_state(&entity, SPACE1), Some(AutoblockState::Blocked));
+    });
+}
+
+#[test]
+fn report_entity_should_not_count_dismissed_reports_to_autoblock() {
+    ExtBuilder::build_with_space_and_post().execute_with(|| {
+        assert_ok!(_update_autoblock_threshold_in_moderation_settings());
+
+        let reporters_before_dismiss = AUTOBLOCK_THRESHOLD as AccountId - 1;
+        for reporter in FIRST_REPORTER..FIRST_REPORTER + reporters_before_dismiss {
+            assert_ok!(_report_entity(Some(Origin::signed(reporter)), None, None, None));
+        }
+
+        for report_id in REPORT1..=reporters_before_dismiss {
+            assert_ok!(_dismiss_report(None, Some(report_id)));
+        }
+
+        // One new report should not be enough to block the post.
+        let next_reporter = FIRST_REPORTER + reporters_before_dismiss;
+        assert_ok!(_report_entity(Some(Origin::signed(next_reporter)), None, None, None));
+
+        let entity = EntityId::Post(POST1);
+        assert!(Moderation::status_by_entity_in_space(&entity, SPACE1).is_none());
+        assert!(Moderation::autoblock_state(&entity, SPACE1).is_none());
+        assert_eq!(PostById::<Test>::get(POST1).unwrap().space_id, Some(SPACE1));
+    });
+}
+
+#[test]
+fn report_entity_should_not_autoblock_allowed_entity() {
+    ExtBuilder::build_with_space_and_post().execute_with(|| {
+        assert_ok!(_update_autoblock_threshold_in_moderation_settings());
+        assert_ok!(_update_post_status_to_allowed());
+
+        report_entity_up_to_autoblock_threshold(EntityId::Post(POST1));
+
+        let entity = EntityId::Post(POST1);
+        assert_eq!(Moderation::status_by_entity_in_space(&entity, SPACE1), Some(EntityStatus::Allowed));
+        assert!(Moderation::autoblock_state(&entity, SPACE1).is_none());
+    });
+}
+
+#[test]
+fn appeal_entity_status_should_unblock_autoblocked_entity() {
+    ExtBuilder::build_with_space_and_post().execute_with(|| {
+        let entity = EntityId::Account(ACCOUNT_TO_BLOCK);
+
+        assert_ok!(_update_autoblock_threshold_in_moderation_settings());
+        report_entity_up_to_autoblock_threshold(entity.clone());
+        assert_eq!(Moderation::status_by_entity_in_space(&entity, SPACE1), Some(EntityStatus::Blocked));
+
+        assert_ok!(_appeal_blocked_account());
+
+        assert!(Moderation::status_by_entity_in_space(&entity, SPACE1).is_none());
+        assert_eq!(Moderation::autoblock_state(&entity, SPACE1), Some(AutoblockState::UnderReview));
+
+        // The entity under review should not be blocked automatically again.
+        let next_reporter = FIRST_REPORTER + AUTOBLOCK_THRESHOLD as AccountId;
+        assert_ok!(_report_entity(Some(Origin::signed(next_reporter)), Some(entity.clone()), None, None));
+        assert!(Moderation::status_by_entity_in_space(&entity, SPACE1).is_none());
+
+        // A final decision of a moderator ends the review.
+        assert_ok!(_resolve_appeal(None, None, Some(Some(EntityStatus::Blocked))));
+        assert_eq!(Moderation::status_by_entity_in_space(&entity, SPACE1), Some(EntityStatus::Blocked));
+        assert!(Moderation::autoblock_state(&entity, SPACE1).is_none());
+    });
+}
+
+#[test]
+fn appeal_entity_status_should_work() {
+    ExtBuilder::build_with_space_and_post().execute_with(|| {
+        let entity = EntityId::Account(ACCOUNT_TO_BLOCK);
+        block_account_by_space_owner();
+
+        assert_ok!(_appeal_blocked_account());
+
+        let appeal = Moderation::appeal_by_id(APPEAL1).unwrap();
+        assert_eq!(appeal.created.account, ACCOUNT_TO_BLOCK);
+        assert_eq!(appeal.appealed_entity, entity);
+        assert_eq!(appeal.appealed_within, SPACE1);
+        assert_eq!(appeal.reason, valid_content_ipfs());
+        assert_eq!(appeal.status, AppealStatus::Pending);
+
+        assert_eq!(Moderation::next_appeal_id(), APPEAL1 + 1);
+        assert_eq!(Moderation::appeal_ids_by_space_id(SPACE1), vec![APPEAL1]);
+        assert_eq!(Moderation::pending_appeal_ids_by_space_id(SPACE1), vec![APPEAL1]);
+        assert_eq!(Moderation::pending_appeal_id_by_entity_in_space(&entity, SPACE1), Some(APPEAL1));
+
+        // A block set by a moderator stays until the appeal is resolved.
+        assert_eq!(Moderation::status_by_entity_in_space(&entity, SPACE1), Some(EntityStatus::Blocked));
+    });
+}
+
+#[test]
+fn appeal_entity_status_should_fail_when_no_reason_provided() {
+    ExtBuilder::build_with_space_and_post().execute_with(|| {
+        block_account_by_space_owner();
+
+        assert_noop!(
+            _appeal_entity_status(
+                Some(Origin::signed(ACCOUNT_TO_BLOCK)),
+                Some(EntityId::Account(ACCOUNT_TO_BLOCK)),
+                None,
+                Some(Content::None)
+            ), Error::<Test>::ReasonIsEmpty
+        );
+    });
+}
+
+#[test]
+fn appeal_entity_status_should_fail_when_entity_is_not_blocked() {
+    ExtBuilder::build_with_space_and_post().execute_with(|| {
+        assert_noop!(_appeal_blocked_account(), Error::<Test>::EntityIsNotBlocked);
+    });
+}
+
+#[test]
+fn appeal_entity_status_should_fail_when_already_appealed() {
+    ExtBuilder::build_with_space_and_post().execute_with(|| {
+        block_account_by_space_owner();
+        assert_ok!(_appeal_blocked_account());
+
+        assert_noop!(_appeal_blocked_account(), Error::<Test>::AlreadyAppealedEntityStatus);
+    });
+}
+
+#[test]
+fn appeal_entity_status_should_fail_when_appeal_period_is_over() {
+    ExtBuilder::build_with_space_and_post().execute_with(|| {
+        block_account_by_space_owner();
+        end_appeal_period();
+
+        assert_noop!(_appeal_blocked_account(), Error::<Test>::AppealPeriodIsOver);
+    });
+}
+
+#[test]
+fn appeal_entity_status_should_fail_when_origin_is_not_entity_owner() {
+    ExtBuilder::build_with_space_and_post().execute_with(|| {
+        block_account_by_space_owner();
+
+        assert_noop!(
+            _appeal_entity_status(
+                Some(Origin::signed(ACCOUNT_NOT_MODERATOR)),
+                Some(EntityId::Account(ACCOUNT_TO_BLOCK)),
+                None,
+                None
+            ), Error::<Test>::NotAnEntityOwner
+        );
+    });
+}
+
+// Resolve appeal
+//----------------------------------------------------------------------------
+
+#[test]
+fn resolve_appeal_should_accept_appeal() {
+    ExtBuilder::build_with_space_and_post().execute_with(|| {
+        let entity = EntityId::Account(ACCOUNT_TO_BLOCK);
+        block_account_by_space_owner();
+        assert_ok!(_appeal_blocked_account());
+
+        assert_ok!(_resolve_appeal(None, None, Some(Some(EntityStatus::Allowed))));
+
+        let appeal = Moderation::appeal_by_id(APPEAL1).unwrap();
+        assert_eq!(appeal.status, AppealStatus::Accepted);
+        assert_eq!(appeal.resolved.unwrap().account, ACCOUNT_SCOPE_OWNER);
+
+        assert_eq!(Moderation::status_by_entity_in_space(&entity, SPACE1), Some(EntityStatus::Allowed));
+        assert!(Moderation::pending_appeal_ids_by_space_id(SPACE1).is_empty());
+        assert!(Moderation::pending_appeal_id_by_entity_in_space(&entity, SPACE1).is_none());
+
+        // The entity can be appealed again, once it's blocked again.
+        block_account_by_space_owner();
+        assert_ok!(_appeal_blocked_account());
+    });
+}
+
+#[test]
+fn resolve_appeal_should_reject_appeal() {
+    ExtBuilder::build_with_space_and_post().execute_with(|| {
+        block_account_by_space_owner();
+        assert_ok!(_appeal_blocked_account());
+
+        assert_ok!(_resolve_appeal(None, None, Some(Some(EntityStatus::Blocked))));
+
+        let appeal = Moderation::appeal_by_id(APPEAL1).unwrap();
+        assert_eq!(appeal.status, AppealStatus::Rejected);
+        assert_eq!(
+            Moderation::status_by_entity_in_space(EntityId::Account(ACCOUNT_TO_BLOCK), SPACE1),
+            Some(EntityStatus::Blocked)
+        );
+    });
+}
+
+#[test]
+fn resolve_appeal_should_keep_post_in_space_when_appeal_accepted() {
+    ExtBuilder::build_with_space_and_post().execute_with(|| {
+        let entity = EntityId::Post(POST1);
+        assert_ok!(_update_entity_status(None, None, None, Some(Some(EntityStatus::Blocked))));
+        assert_ok!(_appeal_entity_status(None, None, None, None));
+
+        // A pending appeal postpones the removal of the post from its space.
+        end_appeal_period();
+        assert_eq!(PostById::<Test>::get(POST1).unwrap().space_id, Some(SPACE1));
+
+        assert_ok!(_resolve_appeal(None, None, Some(Some(EntityStatus::Allowed))));
+
+        assert_eq!(PostById::<Test>::get(POST1).unwrap().space_id, Some(SPACE1));
+        assert_eq!(Moderation::status_by_entity_in_space(&entity, SPACE1), Some(EntityStatus::Allowed));
+        assert!(Moderation::appealable_until(&entity, SPACE1).is_none());
+    });
+}
+
+#[test]
+fn resolve_appeal_should_remove_post_from_space_when_appeal_rejected() {
+    ExtBuilder::build_with_space_and_post().execute_with(|| {
+        assert_ok!(_update_entity_status(None, None, None, Some(Some(EntityStatus::Blocked))));
+        assert_ok!(_appeal_entity_status(None, None, None, None));
+
+        assert_ok!(_resolve_appeal(None, None, Some(Some(EntityStatus::Blocked))));
+
+        assert!(PostById::<Test>::get(POST1).unwrap().space_id.is_none());
+        assert!(Moderation::appealable_until(EntityId::Post(POST1), SPACE1).is_none());
+    });
+}
+
+#[test]
+fn resolve_appeal_should_fail_when_appeal_not_found() {
+    ExtBuilder::build_with_space_and_post().execute_with(|| {
+        assert_noop!(_resolve_appeal(None, None, None), Error::<Test>::AppealNotFound);
+    });
+}
+
+#[test]
+fn resolve_appeal_should_fail_when_already_resolved() {
+    ExtBuilder::build_with_space_and_post().execute_with(|| {
+        block_account_by_space_owner();
+        assert_ok!(_appeal_blocked_account());
+        assert_ok!(_resolve_appeal(None, None, None));
+
+        assert_noop!(_resolve_appeal(None, None, None), Error::<Test>::AppealIsAlreadyResolved);
+    });
+}
+
+#[test]
+fn resolve_appeal_should_fail_when_origin_has_no_permission() {
+    ExtBuilder::build_with_space_and_post().execute_with(|| {
+        block_account_by_space_owner();
+        assert_ok!(_appeal_blocked_account());
+
+        assert_noop!(
+            _resolve_appeal(Some(Origin::signed(ACCOUNT_NOT_MODERATOR)), None, None),
+            Error::<Test>::NoPermissionToUpdateEntityStatus
+        );
+    });
+}
+
+// Report lifecycle
+//----------------------------------------------------------------------------
+
+#[test]
+fn report_should_be_open_and_in_moderators_queue() {
+    ExtBuilder::build_with_space_and_post_then_report().execute_with(|| {
+        let report = Moderation::report_by_id(REPORT1).unwrap();
+        assert_eq!(report.status, ReportStatus::Open);
+        assert_eq!(Moderation::unresolved_report_ids_by_space_id(SPACE1), vec![REPORT1]);
+    });
+}
+
+#[test]
+fn suggest_entity_status_should_move_report_under_review() {
+    ExtBuilder::build_with_space_and_post_then_report().execute_with(|| {
+        assert_ok!(_suggest_blocked_status_for_post());
+
+        let report = Moderation::report_by_id(REPORT1).unwrap();
+        assert_eq!(report.status, ReportStatus::UnderReview);
+        assert_eq!(Moderation::unresolved_report_ids_by_space_id(SPACE1), vec![REPORT1]);
+    });
+}
+
+#[test]
+fn update_entity_status_should_resolve_reports() {
+    ExtBuilder::build_with_space_and_post_then_report().execute_with(|| {
+        assert_ok!(_update_post_status_to_allowed());
+
+        let report = Moderation::report_by_id(REPORT1).unwrap();
+        assert_eq!(report.status, ReportStatus::Resolved(EntityStatus::Allowed));
+        assert!(Moderation::unresolved_report_ids_by_space_id(SPACE1).is_empty());
+    });
+}
+
+#[test]
+fn dismiss_report_should_work() {
+    ExtBuilder::build_with_space_and_post_then_report().execute_with(|| {
+        assert_ok!(_dismiss_report(None, None));
+
+        let report = Moderation::report_by_id(REPORT1).unwrap();
+        assert_eq!(report.status, ReportStatus::Dismissed);
+        assert!(Moderation::unresolved_report_ids_by_space_id(SPACE1).is_empty());
+
+        // A dismissed report should not be resolved by a later decision.
+        assert_ok!(_update_post_status_to_allowed());
+        assert_eq!(Moderation::report_by_id(REPORT1).unwrap().status, ReportStatus::Dismissed);
+    });
+}
+
+#[test]
+fn dismiss_report_should_fail_when_report_is_already_closed() {
+    ExtBuilder::build_with_space_and_post_then_report().execute_with(|| {
+        assert_ok!(_update_post_status_to_allowed());
+
+        assert_noop!(_dismiss_report(None, None), Error::<Test>::ReportIsAlreadyClosed);
+    });
+}
+
+#[test]
+fn dismiss_report_should_fail_when_origin_has_no_permission() {
+    ExtBuilder::build_with_space_and_post_then_report().execute_with(|| {
+        assert_noop!(
+            _dismiss_report(Some(Origin::signed(ACCOUNT_NOT_MODERATOR)), None),
+            Error::<Test>::NoPermissionToUpdateEntityStatus
+        );
+    });
+}
+
+// Global blocklist
+//----------------------------------------------------------------------------
+
+#[test]
+fn block_entity_globally_should_work() {
+    ExtBuilder::build_with_space_and_post().execute_with(|| {
+        assert_ok!(_block_account_globally(None));
+
+        assert!(Moderation::is_blocked_globally(EntityId::Account(ACCOUNT_TO_BLOCK)));
+        assert!(Moderation::is_blocked_account(ACCOUNT_TO_BLOCK, SPACE1));
+        assert!(!Moderation::is_allowed_account(ACCOUNT_TO_BLOCK, SPACE1));
+    });
+}
+
+#[test]
+fn block_entity_globally_should_override_space_level_status() {
+    ExtBuilder::build_with_space_and_post().execute_with(|| {
+        assert_ok!(_update_entity_status(
+            None,
+            Some(EntityId::Account(ACCOUNT_TO_BLOCK)),
+            None,
+            Some(Some(EntityStatus::Allowed))
+        ));
+        assert_ok!(_block_account_globally(None));
+
+        assert!(Moderation::is_blocked_account(ACCOUNT_TO_BLOCK, SPACE1));
+    });
+}
+
+#[test]
+fn block_entity_globally_should_not_affect_space_that_opted_out() {
+    ExtBuilder::build_with_space_and_post().execute_with(|| {
+        assert_ok!(_update_moderation_settings(None, None, Some(opt_out_of_global_blocklist())));
+        assert_ok!(_block_account_globally(None));
+
+        assert!(!Moderation::inherits_global_blocklist(SPACE1));
+        assert!(!Moderation::is_blocked_account(ACCOUNT_TO_BLOCK, SPACE1));
+        assert!(Moderation::is_allowed_account(ACCOUNT_TO_BLOCK, SPACE1));
+    });
+}
+
+#[test]
+fn block_entity_globally_should_fail_when_origin_is_not_global_moderator() {
+    ExtBuilder::build_with_space_and_post().execute_with(|| {
+        assert_noop!(
+            _block_account_globally(Some(Origin::signed(ACCOUNT_SCOPE_OWNER))),
+            DispatchError::BadOrigin
+        );
+    });
+}
+
+#[test]
+fn block_entity_globally_should_fail_when_entity_is_already_blocked() {
+    ExtBuilder::build_with_space_and_post().execute_with(|| {
+        assert_ok!(_block_account_globally(None));
+
+        assert_noop!(_block_account_globally(None), Error::<Test>::EntityIsAlreadyBlockedGlobally);
+    });
+}
+
+#[test]
+fn unblock_entity_globally_should_work() {
+    ExtBuilder::build_with_space_and_post().execute_with(|| {
+        assert_ok!(_block_account_globally(None));
+        assert_ok!(_unblock_account_globally(None));
+
+        assert!(!Moderation::is_blocked_globally(EntityId::Account(ACCOUNT_TO_BLOCK)));
+        assert!(!Moderation::is_blocked_account(ACCOUNT_TO_BLOCK, SPACE1));
+    });
+}
+
+#[test]
+fn unblock_entity_globally_should_fail_when_entity_is_not_blocked() {
+    ExtBuilder::build_with_space_and_post().execute_with(|| {
+        assert_noop!(_unblock_account_globally(None), Error::<Test>::EntityIsNotBlockedGlobally);
+    });
+}
@@ -1,6 +1,8 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use codec::{Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
 use frame_support::{
   decl_module,
   traits::Get
@@ -17,6 +19,7 @@ use pallet_utils::SpaceId;
 pub mod default_permissions;
 
 #[derive(Encode, Decode, Ord, PartialOrd, Clone, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub enum SpacePermission {
   /// Create, update, delete, grant and revoke roles in this space.
   ManageRoles,
@@ -87,6 +90,8 @@ pub enum SpacePermission {
 pub type SpacePermissionSet = BTreeSet<SpacePermission>;
 
 #[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
 pub struct SpacePermissions {
   pub none: Option<SpacePermissionSet>,
   pub everyone: Option<SpacePermissionSet>,
@@ -105,6 +110,29 @@ impl Default for SpacePermissions {
   }
 }
 
+/// A level of space permissions that is resolved without roles.
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum SpacePermissionLevel {
+  Everyone,
+  Follower,
+  SpaceOwner,
+}
+
+/// Describes why a user has a permission in a space.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum SpacePermissionSource<RoleId> {
+  /// Granted by the default permissions at this level, because the space has no override for it.
+  Default(SpacePermissionLevel),
+  /// Granted by the space's own override of this level.
+  SpaceOverride(SpacePermissionLevel),
+  /// Granted by a role that is active in this space.
+  Role(RoleId),
+}
+
 #[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
 pub struct SpacePermissionsContext {
   pub space_id: SpaceId,
@@ -124,17 +152,6 @@ decl_module! {
   }
 }
 
-impl SpacePermission {
-  fn is_present_in_role(&self, perms_opt: Option<SpacePermissionSet>) -> bool {
-    if let Some(perms) = perms_opt {
-      if perms.contains(self) {
-        return true
-      }
-    }
-    false
-  }
-}
-
 impl<T: Trait> Module<T> {
 
   fn get_overrides_or_defaults(
@@ -149,45 +166,90 @@ impl<T: Trait> Module<T> {
     }
   }
 
-  fn resolve_space_perms(
-    space_perms: Option<SpacePermissions>,
-  ) -> SpacePermissions {
+  pub fn has_user_a_space_permission(
+    ctx: SpacePermissionsContext,
+    permission: SpacePermission,
+  ) -> Option<bool> {
+
+    // Roles are not resolved here, so the source of a permission is not needed.
+    let (forbidden, granted) = Self::get_space_permissions_by_levels::<()>(ctx);
+
+    // Check if this permission is forbidden:
+    if forbidden.contains(&permission) {
+      return Some(false)
+    }
 
+    if granted.iter().any(|(granted_permission, _)| *granted_permission == permission) {
+      return Some(true)
+    }
+
+    None
+  }
+
+  /// Resolve the effective permissions of every level in a space: a level that is not overridden
+  /// by the space gets the default permissions. Forbidden permissions are excluded from other levels.
+  pub fn resolve_space_perms(space_perms: Option<SpacePermissions>) -> SpacePermissions {
     let defaults = T::DefaultSpacePermissions::get();
     let overrides = space_perms.unwrap_or_default();
 
+    let forbidden = Self::get_overrides_or_defaults(overrides.none, defaults.none).unwrap_or_default();
+
+    let resolve_level = |level_overrides, level_defaults| -> Option<SpacePermissionSet> {
+      Self::get_overrides_or_defaults(level_overrides, level_defaults).map(|perms| {
+        perms.into_iter().filter(|permission| !forbidden.contains(permission)).collect()
+      })
+    };
+
     SpacePermissions {
-      none: Self::get_overrides_or_defaults(overrides.none, defaults.none),
-      everyone: Self::get_overrides_or_defaults(overrides.everyone, defaults.everyone),
-      follower: Self::get_overrides_or_defaults(overrides.follower, defaults.follower),
-      space_owner: Self::get_overrides_or_defaults(overrides.space_owner, defaults.space_owner)
+      everyone: resolve_level(overrides.everyone, defaults.everyone),
+      follower: resolve_level(overrides.follower, defaults.follower),
+      space_owner: resolve_level(overrides.space_owner, defaults.space_owner),
+      none: Some(forbidden),
     }
   }
 
-  pub fn has_user_a_space_permission(
+  /// Get the permissions that a user has in a space by the level they belong to (everyone,
+  /// follower or space owner), together with the source of each permission.
+  ///
+  /// Returns a set of permissions forbidden in this space (the `none` level) as the first element.
+  /// A forbidden permission is never returned as granted.
+  pub fn get_space_permissions_by_levels<RoleId: Clone>(
     ctx: SpacePermissionsContext,
-    permission: SpacePermission,
-  ) -> Option<bool> {
+  ) -> (SpacePermissionSet, Vec<(SpacePermission, SpacePermissionSource<RoleId>)>) {
 
-    let perms_by_role = Self::resolve_space_perms(ctx.space_perms);
+    let defaults = T::DefaultSpacePermissions::get();
+    let overrides = ctx.space_perms.unwrap_or_default();
 
-    // Check if this permission is forbidden:
-    if permission.is_present_in_role(perms_by_role.none) {
-      return Some(false)
-    }
+    let forbidden = Self::get_overrides_or_defaults(overrides.none, defaults.none).unwrap_or_default();
 
     let is_space_owner = ctx.is_space_owner;
     let is_follower = is_space_owner || ctx.is_space_follower;
 
-    if
-      permission.is_present_in_role(perms_by_role.everyone) ||
-      is_follower && permission.is_present_in_role(perms_by_role.follower) ||
-      is_space_owner && permission.is_present_in_role(perms_by_role.space_owner)
-    {
-      return Some(true)
+    let levels = vec![
+      (SpacePermissionLevel::Everyone, true, overrides.everyone, defaults.everyone),
+      (SpacePermissionLevel::Follower, is_follower, overrides.follower, defaults.follower),
+      (SpacePermissionLevel::SpaceOwner, is_space_owner, overrides.space_owner, defaults.space_owner),
+    ];
+
+    let mut granted = Vec::new();
+    for (level, is_applicable, level_overrides, level_defaults) in levels {
+      if !is_applicable {
+        continue;
+      }
+
+      let (perms, source) = match level_overrides {
+        Some(perms) => (perms, SpacePermissionSource::SpaceOverride(level)),
+        None => (level_defaults.unwrap_or_default(), SpacePermissionSource::Default(level)),
+      };
+
+      for permission in perms {
+        if !forbidden.contains(&permission) {
+          granted.push((permission, source.clone()));
+        }
+      }
     }
 
-    None
+    (forbidden, granted)
   }
 
   pub fn override_permissions(mut overrides: SpacePermissions) -> SpacePermissions {
@@ -0,0 +1,27 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::too_many_arguments)]
+#![allow(clippy::unnecessary_mut_passed)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+use pallet_posts::{PostId, rpc::{FlatPost, FlatReplyTree}};
+use pallet_utils::SpaceId;
+
+sp_api::decl_runtime_apis! {
+    pub trait PostsApi<AccountId, BlockNumber> where
+        AccountId: Codec,
+        BlockNumber: Codec
+    {
+        fn get_posts_by_space(
+            space_id: SpaceId,
+            offset: u64,
+            limit: u64,
+            include_hidden: bool,
+        ) -> Vec<FlatPost<AccountId, BlockNumber>>;
+
+        fn get_reply_tree(root_post_id: PostId, max_depth: u32) -> Option<FlatReplyTree<AccountId, BlockNumber>>;
+
+        fn get_shared_posts(original_post_id: PostId) -> Vec<FlatPost<AccountId, BlockNumber>>;
+    }
+}
@@ -0,0 +1,104 @@
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::Result;
+use jsonrpc_derive::rpc;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+use pallet_posts::{PostId, rpc::{FlatPost, FlatReplyTree}};
+use pallet_utils::SpaceId;
+use utils_rpc::map_rpc_error;
+pub use posts_runtime_api::PostsApi as PostsRuntimeApi;
+
+#[rpc]
+pub trait PostsApi<BlockHash, AccountId, BlockNumber> {
+    #[rpc(name = "posts_getPostsBySpace")]
+    fn get_posts_by_space(
+        &self,
+        at: Option<BlockHash>,
+        space_id: SpaceId,
+        offset: u64,
+        limit: u64,
+        include_hidden: bool,
+    ) -> Result<Vec<FlatPost<AccountId, BlockNumber>>>;
+
+    #[rpc(name = "posts_getReplyTree")]
+    fn get_reply_tree(
+        &self,
+        at: Option<BlockHash>,
+        root_post_id: PostId,
+        max_depth: u32,
+    ) -> Result<Option<FlatReplyTree<AccountId, BlockNumber>>>;
+
+    #[rpc(name = "posts_getSharedPosts")]
+    fn get_shared_posts(
+        &self,
+        at: Option<BlockHash>,
+        original_post_id: PostId,
+    ) -> Result<Vec<FlatPost<AccountId, BlockNumber>>>;
+}
+
+/// A struct that implements the [`PostsApi`].
+pub struct Posts<C, M> {
+    client: Arc<C>,
+    _marker: std::marker::PhantomData<M>,
+}
+
+impl<C, M> Posts<C, M> {
+    /// Create new `Posts` with the given reference to the client.
+    pub fn new(client: Arc<C>) -> Self {
+        Self {
+            client,
+            _marker: Default::default(),
+        }
+    }
+}
+
+impl<C, Block, AccountId, BlockNumber> PostsApi<<Block as BlockT>::Hash, AccountId, BlockNumber>
+    for Posts<C, Block>
+where
+    Block: BlockT,
+    AccountId: Codec,
+    BlockNumber: Codec,
+    C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+    C::Api: PostsRuntimeApi<Block, AccountId, BlockNumber>,
+{
+    fn get_posts_by_space(
+        &self,
+        at: Option<<Block as BlockT>::Hash>,
+        space_id: SpaceId,
+        offset: u64,
+        limit: u64,
+        include_hidden: bool,
+    ) -> Result<Vec<FlatPost<AccountId, BlockNumber>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.get_posts_by_space(&at, space_id, offset, limit, include_hidden).map_err(map_rpc_error)
+    }
+
+    fn get_reply_tree(
+        &self,
+        at: Option<<Block as BlockT>::Hash>,
+        root_post_id: PostId,
+        max_depth: u32,
+    ) -> Result<Option<FlatReplyTree<AccountId, BlockNumber>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.get_reply_tree(&at, root_post_id, max_depth).map_err(map_rpc_error)
+    }
+
+    fn get_shared_posts(
+        &self,
+        at: Option<<Block as BlockT>::Hash>,
+        original_post_id: PostId,
+    ) -> Result<Vec<FlatPost<AccountId, BlockNumber>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.get_shared_posts(&at, original_post_id).map_err(map_rpc_error)
+    }
+}
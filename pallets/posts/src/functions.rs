@@ -158,6 +158,96 @@ impl Default for PostUpdate {
 
 impl<T: Trait> Module<T> {
 
+    /// Create a new post on behalf of the `creator` and return its id.
+    /// Used by `create_post` and by other pallets, e.g. to create a comment with a donation.
+    pub fn do_create_post(
+        creator: T::AccountId,
+        space_id_opt: Option<SpaceId>,
+        extension: PostExtension,
+        content: Content
+    ) -> Result<PostId, DispatchError> {
+        Utils::<T>::is_valid_content(content.clone())?;
+
+        let new_post_id = Self::next_post_id();
+        let new_post: Post<T> = Post::new(new_post_id, creator.clone(), space_id_opt, extension, content.clone());
+
+        // Get space from either space_id_opt or Comment if a comment provided
+        let space = &mut new_post.get_space()?;
+        ensure!(!space.hidden, Error::<T>::CannotCreateInHiddenScope);
+
+        ensure!(T::IsAccountBlocked::is_allowed_account(creator.clone(), space.id), UtilsError::<T>::AccountIsBlocked);
+        ensure!(T::IsContentBlocked::is_allowed_content(content, space.id), UtilsError::<T>::ContentIsBlocked);
+
+        let root_post = &mut new_post.get_root_post()?;
+        ensure!(!root_post.hidden, Error::<T>::CannotCreateInHiddenScope);
+
+        if new_post.is_comment() {
+            Self::ensure_account_can_access_post(&creator, root_post)?;
+        }
+
+        // Check whether account has permission to create Post (by extension)
+        let mut permission_to_check = SpacePermission::CreatePosts;
+        let mut error_on_permission_failed = Error::<T>::NoPermissionToCreatePosts;
+
+        if let PostExtension::Comment(_) = extension {
+            permission_to_check = SpacePermission::CreateComments;
+            error_on_permission_failed = Error::<T>::NoPermissionToCreateComments;
+        }
+
+        Spaces::ensure_account_has_space_permission(
+            creator.clone(),
+            &space,
+            permission_to_check,
+            error_on_permission_failed.into()
+        )?;
+
+        match extension {
+            PostExtension::RegularPost => space.inc_posts(),
+            PostExtension::SharedPost(post_id) => Self::create_sharing_post(&creator, new_post_id, post_id, space)?,
+            PostExtension::Comment(comment_ext) => Self::create_comment(&creator, new_post_id, comment_ext, root_post)?,
+        }
+
+        if new_post.is_root_post() {
+            SpaceById::insert(space.id, space.clone());
+            PostIdsBySpaceId::mutate(space.id, |ids| ids.push(new_post_id));
+        }
+
+        PostById::insert(new_post_id, new_post);
+        NextPostId::mutate(|n| { *n += 1; });
+
+        Self::deposit_event(RawEvent::PostCreated(creator, new_post_id));
+        Ok(new_post_id)
+    }
+
+    /// Ensure that the account can interact with the post, e.g. comment or react on it.
+    /// If the root post is available to subscribers only, the account should be either
+    /// its owner or an active subscriber of at least one of its plans.
+    /// Plans that were deactivated or removed from the post space do not limit access anymore.
+    pub fn ensure_account_can_access_post(account: &T::AccountId, post: &Post<T>) -> DispatchResult {
+        let root_post = post.get_root_post()?;
+
+        if root_post.is_owner(account) {
+            return Ok(());
+        }
+
+        let active_plan_ids: Vec<SubscriptionPlanId> = match root_post.space_id {
+            Some(space_id) => Self::subscription_plan_ids_by_post_id(root_post.id).into_iter()
+                .filter(|plan_id| T::SubscriptionPlans::is_active_plan_in_space(*plan_id, space_id))
+                .collect(),
+            None => Vec::new(),
+        };
+
+        if active_plan_ids.is_empty() {
+            return Ok(());
+        }
+
+        let is_subscriber = active_plan_ids.into_iter()
+            .any(|plan_id| T::SubscriptionPlans::is_active_subscriber(account, plan_id));
+
+        ensure!(is_subscriber, Error::<T>::NotASubscriber);
+        Ok(())
+    }
+
     pub fn ensure_account_can_update_post(
         editor: &T::AccountId, 
         post: &Post<T>,
@@ -196,6 +286,44 @@ impl<T: Trait> Module<T> {
         )
     }
 
+    pub fn ensure_account_can_delete_post(
+        editor: &T::AccountId,
+        post: &Post<T>,
+        space: &Space<T>
+    ) -> DispatchResult {
+        let is_owner = post.is_owner(&editor);
+        let is_comment = post.is_comment();
+
+        let permission_to_check: SpacePermission;
+        let permission_error: DispatchError;
+
+        if is_comment {
+          if is_owner {
+            permission_to_check = SpacePermission::DeleteOwnComments;
+            permission_error = Error::<T>::NoPermissionToDeleteOwnComments.into();
+          } else {
+            return Err(Error::<T>::NotACommentAuthor.into());
+          }
+        } else {
+          // Not a comment
+
+          if is_owner {
+            permission_to_check = SpacePermission::DeleteOwnPosts;
+            permission_error = Error::<T>::NoPermissionToDeleteOwnPosts.into();
+          } else {
+            permission_to_check = SpacePermission::DeleteAnyPost;
+            permission_error = Error::<T>::NoPermissionToDeleteAnyPost.into();
+          }
+        }
+
+        Spaces::ensure_account_has_space_permission(
+          editor.clone(),
+          space,
+          permission_to_check,
+          permission_error
+        )
+    }
+
     /// Check that there is a `Post` with such `post_id` in the storage
     /// or return`PostNotFound` error.
     pub fn ensure_post_exists(post_id: PostId) -> DispatchResult {
@@ -328,7 +456,13 @@ impl<T: Trait> Module<T> {
         }
 
         root_post.inc_replies();
-        T::PostScores::score_root_post_on_new_comment(creator.clone(), root_post)?;
+
+        // Score the root post only for the first comment of this account on it.
+        let comments_count = Self::comments_count_by_account_on_post((creator.clone(), root_post.id));
+        if comments_count == 0 {
+            T::PostScores::score_root_post_on_new_comment(creator.clone(), root_post)?;
+        }
+        CommentsCountByAccountOnPost::<T>::insert((creator.clone(), root_post.id), comments_count.saturating_add(1));
 
         Self::for_each_post_ancestor(commented_post_id, |post| post.inc_replies())?;
         PostById::insert(root_post.id, root_post);
@@ -347,11 +481,18 @@ impl<T: Trait> Module<T> {
             .ok_or(Error::<T>::OriginalPostNotFound)?;
 
         ensure!(!original_post.is_sharing_post(), Error::<T>::CannotShareSharingPost);
+        Self::ensure_account_can_access_post(creator, original_post)?;
+
+        let original_space = original_post.get_space()?;
+        ensure!(
+            T::IsAccountBlocked::is_allowed_account(creator.clone(), original_space.id),
+            UtilsError::<T>::AccountIsBlocked
+        );
 
         // Check if it's allowed to share a post from the space of original post.
         Spaces::ensure_account_has_space_permission(
             creator.clone(),
-            &original_post.get_space()?,
+            &original_space,
             SpacePermission::Share,
             Error::<T>::NoPermissionToShare.into()
         )?;
@@ -519,4 +660,236 @@ impl<T: Trait> Module<T> {
 
         Ok(())
     }
+
+    /// A post that has replies, shares or reactions is turned into a tombstone when it is deleted,
+    /// so that deletion never has to walk the reply tree, sharing posts or reactions.
+    fn should_leave_tombstone(post: &Post<T>) -> bool {
+        !Self::reply_ids_by_post_id(post.id).is_empty()
+            || !Self::shared_post_ids_by_original_post_id(post.id).is_empty()
+            || post.upvotes_count > 0
+            || post.downvotes_count > 0
+    }
+
+    /// Erase the content of a post and mark it as deleted, but keep the post itself in the storage.
+    fn turn_post_into_tombstone(editor: T::AccountId, post: &mut Post<T>) -> DispatchResult {
+        ensure!(!Self::is_post_deleted(post.id), Error::<T>::PostIsAlreadyDeleted);
+
+        post.content = Content::None;
+        post.updated = Some(WhoAndWhen::<T>::new(editor));
+        PostById::<T>::insert(post.id, post.clone());
+        IsPostDeleted::insert(post.id, true);
+
+        Ok(())
+    }
+
+    /// Decrease the number of comments of this account on the root post.
+    /// Subtract the weight of CreateComment from the root post and its space,
+    /// when it was the last comment of this account.
+    fn dec_comments_count_by_account(account: T::AccountId, root_post: &mut Post<T>) -> DispatchResult {
+        let comments_count = Self::comments_count_by_account_on_post((account.clone(), root_post.id))
+            .saturating_sub(1);
+
+        if comments_count == 0 {
+            CommentsCountByAccountOnPost::<T>::remove((account.clone(), root_post.id));
+            T::PostScores::score_root_post_on_comment_deleted(account, root_post)?;
+        } else {
+            CommentsCountByAccountOnPost::<T>::insert((account, root_post.id), comments_count);
+        }
+
+        Ok(())
+    }
+
+    /// Remove a comment from the storage or turn it into a tombstone
+    /// if it has replies, shares or reactions.
+    pub(crate) fn delete_comment(editor: T::AccountId, mut comment: Post<T>) -> DispatchResult {
+        let comment_ext = comment.get_comment_ext()?;
+        let root_post = &mut Self::require_post(comment_ext.root_post_id)?;
+
+        // A tombstone can still be removed once it has no replies, but its score is already reverted.
+        let is_tombstone = Self::is_post_deleted(comment.id);
+
+        if Self::should_leave_tombstone(&comment) {
+            // Keep the comment in the reply tree, but erase its content.
+            Self::turn_post_into_tombstone(editor, &mut comment)?;
+        } else {
+            let is_hidden = comment.hidden;
+            let dec_replies_count = move |p: &mut Post<T>| {
+                p.dec_replies();
+                if is_hidden {
+                    p.dec_hidden_replies();
+                }
+            };
+
+            let commented_post_id = comment_ext.parent_id.unwrap_or(root_post.id);
+            ReplyIdsByPostId::mutate(commented_post_id, |reply_ids| remove_from_vec(reply_ids, comment.id));
+
+            if let Some(parent_id) = comment_ext.parent_id {
+                Self::for_each_post_ancestor(parent_id, dec_replies_count)?;
+            }
+            dec_replies_count(root_post);
+
+            Self::remove_post_from_storage(comment.id);
+        }
+
+        if !is_tombstone {
+            Self::dec_comments_count_by_account(comment.owner, root_post)?;
+        }
+        PostById::<T>::insert(root_post.id, root_post.clone());
+
+        Ok(())
+    }
+
+    /// Remove a root post from the storage or turn it into a tombstone
+    /// if it has replies, shares or reactions.
+    pub(crate) fn delete_root_post(editor: T::AccountId, mut post: Post<T>) -> DispatchResult {
+        let is_tombstone = Self::is_post_deleted(post.id);
+
+        if !is_tombstone {
+            if let PostExtension::SharedPost(original_post_id) = post.extension {
+                Self::unshare_original_post(&post, original_post_id)?;
+            }
+        }
+
+        if Self::should_leave_tombstone(&post) {
+            // Keep the post in its space, so its comments still have a scope, but erase its content.
+            return Self::turn_post_into_tombstone(editor, &mut post);
+        }
+
+        if let Some(space_id) = post.space_id {
+            // Decrease the number of posts on the space
+            Self::mutate_posts_count_on_space(
+                space_id,
+                &post,
+                |counter| *counter = counter.saturating_sub(1)
+            )?;
+
+            Spaces::<T>::mutate_space_by_id(
+                space_id,
+                |space| space.score = space.score.saturating_sub(post.score)
+            )?;
+
+            PostIdsBySpaceId::mutate(space_id, |post_ids| remove_from_vec(post_ids, post.id));
+        }
+
+        Self::remove_post_from_storage(post.id);
+
+        Ok(())
+    }
+
+    /// Remove a sharing post from the shares of the original post and revert the share score,
+    /// unless the owner of the sharing post shares the original post elsewhere too.
+    fn unshare_original_post(sharing_post: &Post<T>, original_post_id: PostId) -> DispatchResult {
+        SharedPostIdsByOriginalPostId::mutate(original_post_id, |ids| remove_from_vec(ids, sharing_post.id));
+
+        if let Some(original_post) = &mut Self::post_by_id(original_post_id) {
+            original_post.dec_shares();
+            PostById::<T>::insert(original_post_id, original_post.clone());
+
+            let account_shares_original_post_elsewhere =
+                Self::shared_post_ids_by_original_post_id(original_post_id).iter()
+                    .filter_map(Self::post_by_id)
+                    .any(|post| post.owner == sharing_post.owner);
+
+            // A score of a root post cannot be changed without its space, e.g. if the space was deleted.
+            let can_be_scored = original_post.is_comment() || original_post.space_id.is_some();
+
+            if !account_shares_original_post_elsewhere && can_be_scored {
+                T::PostScores::score_post_on_share_deleted(sharing_post.owner.clone(), original_post)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Remove a post of a deleted space, or turn it into a tombstone if other posts share it,
+    /// so that the sharing posts do not point to a missing post.
+    fn remove_post_of_deleted_space(mut post: Post<T>) {
+        if let PostExtension::SharedPost(original_post_id) = post.extension {
+            // A deleted sharing post was already unshared.
+            if !Self::is_post_deleted(post.id) {
+                // The space is deleted anyway, so a failed score revert should not stop the clean-up.
+                let _ = Self::unshare_original_post(&post, original_post_id);
+            }
+        }
+
+        if let PostExtension::Comment(comment_ext) = post.extension {
+            // The root post is removed together with the space, so this counter is not needed anymore.
+            CommentsCountByAccountOnPost::<T>::remove((post.owner.clone(), comment_ext.root_post_id));
+        }
+
+        if Self::shared_post_ids_by_original_post_id(post.id).is_empty() {
+            Self::remove_post_from_storage(post.id);
+        } else {
+            post.space_id = None;
+            post.content = Content::None;
+            post.replies_count = 0;
+            post.hidden_replies_count = 0;
+            post.upvotes_count = 0;
+            post.downvotes_count = 0;
+
+            ReplyIdsByPostId::remove(post.id);
+            SubscriptionPlanIdsByPostId::remove(post.id);
+            IsPostDeleted::insert(post.id, true);
+            PostById::<T>::insert(post.id, post);
+        }
+    }
+
+    fn remove_post_from_storage(post_id: PostId) {
+        PostById::<T>::remove(post_id);
+        ReplyIdsByPostId::remove(post_id);
+        SharedPostIdsByOriginalPostId::remove(post_id);
+        SubscriptionPlanIdsByPostId::remove(post_id);
+        IsPostDeleted::remove(post_id);
+    }
+}
+
+impl<T: Trait> SpaceCleaner for Module<T> {
+    /// Posts are removed one by one: root posts of the space first, then their replies,
+    /// which are queued in `PostIdsToCleanUpBySpaceId` when their parent is removed.
+    /// Reactions and other data related to a post are removed by `PostCleaner` before the post.
+    ///
+    /// TODO: scores and reputation earned with posts of a deleted space are not reverted,
+    ///  because a root post cannot be scored without its space.
+    fn clean_up_space(space_id: SpaceId, limit: u32) -> u32 {
+        let mut removed = 0u32;
+        let mut root_post_ids = Self::post_ids_by_space_id(space_id);
+        let mut post_ids = Self::post_ids_to_clean_up_by_space_id(space_id);
+
+        while removed < limit {
+            let post_id = match post_ids.pop().or_else(|| root_post_ids.pop()) {
+                Some(post_id) => post_id,
+                None => break,
+            };
+
+            let items_left = limit - removed;
+            let cleaned_up = T::PostCleaner::clean_up_post(post_id, items_left);
+            removed += cleaned_up;
+
+            if cleaned_up >= items_left {
+                // There could be more data related to this post, so the post waits for the next turn.
+                post_ids.push(post_id);
+                break;
+            }
+
+            post_ids.extend(ReplyIdsByPostId::take(post_id));
+            if let Some(post) = Self::post_by_id(post_id) {
+                Self::remove_post_of_deleted_space(post);
+            }
+            removed += 1;
+        }
+
+        if root_post_ids.is_empty() {
+            PostIdsBySpaceId::remove(space_id);
+        } else {
+            PostIdsBySpaceId::insert(space_id, root_post_ids);
+        }
+
+        if post_ids.is_empty() {
+            PostIdsToCleanUpBySpaceId::remove(space_id);
+        } else {
+            PostIdsToCleanUpBySpaceId::insert(space_id, post_ids);
+        }
+
+        removed
+    }
 }
@@ -1,6 +1,8 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use codec::{Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
 use frame_support::{
     decl_error, decl_event, decl_module, decl_storage, fail,
     dispatch::{DispatchError, DispatchResult}, ensure, traits::Get,
@@ -9,7 +11,11 @@ use sp_runtime::RuntimeDebug;
 use sp_std::prelude::*;
 use frame_system::{self as system, ensure_signed};
 
-use df_traits::moderation::{IsAccountBlocked, IsContentBlocked, IsPostBlocked};
+use df_traits::{
+    SpaceCleaner,
+    moderation::{IsAccountBlocked, IsContentBlocked, IsPostBlocked},
+    subscriptions::{SubscriptionPlanId, SubscriptionPlansProvider},
+};
 use pallet_permissions::SpacePermission;
 use pallet_spaces::{Module as Spaces, Space, SpaceById};
 use pallet_utils::{
@@ -18,6 +24,7 @@ use pallet_utils::{
 };
 
 pub mod functions;
+pub mod rpc;
 
 pub type PostId = u64;
 
@@ -56,6 +63,8 @@ pub struct PostUpdate {
 }
 
 #[derive(Encode, Decode, Clone, Copy, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
 pub enum PostExtension {
     RegularPost,
     Comment(Comment),
@@ -63,6 +72,8 @@ pub enum PostExtension {
 }
 
 #[derive(Encode, Decode, Clone, Copy, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
 pub struct Comment {
     pub parent_id: Option<PostId>,
     pub root_post_id: PostId,
@@ -90,11 +101,19 @@ pub trait Trait: system::Trait
     type AfterPostUpdated: AfterPostUpdated<Self>;
 
     type IsPostBlocked: IsPostBlocked<PostId>;
+
+    /// Used to check who can interact with posts available to subscribers only.
+    type SubscriptionPlans: SubscriptionPlansProvider<Self::AccountId>;
+
+    /// Removes reactions and other data related to posts of deleted spaces.
+    type PostCleaner: PostCleaner;
 }
 
 pub trait PostScores<T: Trait> {
     fn score_post_on_new_share(account: T::AccountId, original_post: &mut Post<T>) -> DispatchResult;
     fn score_root_post_on_new_comment(account: T::AccountId, root_post: &mut Post<T>) -> DispatchResult;
+    fn score_post_on_share_deleted(account: T::AccountId, original_post: &mut Post<T>) -> DispatchResult;
+    fn score_root_post_on_comment_deleted(account: T::AccountId, root_post: &mut Post<T>) -> DispatchResult;
 }
 
 impl<T: Trait> PostScores<T> for () {
@@ -104,6 +123,12 @@ impl<T: Trait> PostScores<T> for () {
     fn score_root_post_on_new_comment(_account: T::AccountId, _root_post: &mut Post<T>) -> DispatchResult {
         Ok(())
     }
+    fn score_post_on_share_deleted(_account: T::AccountId, _original_post: &mut Post<T>) -> DispatchResult {
+        Ok(())
+    }
+    fn score_root_post_on_comment_deleted(_account: T::AccountId, _root_post: &mut Post<T>) -> DispatchResult {
+        Ok(())
+    }
 }
 
 #[impl_trait_for_tuples::impl_for_tuples(10)]
@@ -111,6 +136,26 @@ pub trait AfterPostUpdated<T: Trait> {
     fn after_post_updated(account: T::AccountId, post: &Post<T>, old_data: PostUpdate);
 }
 
+/// Removes the data that a pallet keeps about a post, before the post is removed
+/// together with its deleted space.
+pub trait PostCleaner {
+    /// Remove at most `limit` items related to a post.
+    /// Returns how many items were removed. If it's less than `limit`, there is nothing left to remove.
+    fn clean_up_post(post_id: PostId, limit: u32) -> u32;
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(10)]
+impl PostCleaner for Tuple {
+    fn clean_up_post(post_id: PostId, limit: u32) -> u32 {
+        let mut removed = 0u32;
+        // Every next cleaner gets only what is left from the limit.
+        for_tuples!( #(
+            removed = removed.saturating_add(Tuple::clean_up_post(post_id, limit.saturating_sub(removed)));
+        )* );
+        removed
+    }
+}
+
 // This pallet's storage items.
 decl_storage! {
     trait Store for Module<T: Trait> as PostsModule {
@@ -127,6 +172,24 @@ decl_storage! {
         // TODO rename 'Shared...' to 'Sharing...'
         pub SharedPostIdsByOriginalPostId get(fn shared_post_ids_by_original_post_id):
             map hasher(twox_64_concat) PostId => Vec<PostId>;
+
+        /// Whether a post or a comment (key) was deleted and is kept only as a tombstone.
+        pub IsPostDeleted get(fn is_post_deleted): map hasher(twox_64_concat) PostId => bool;
+
+        /// The number of comments that an account (key 1) has on a root post (key 2).
+        /// The root post is scored for comments of this account only while this number is above zero.
+        pub CommentsCountByAccountOnPost get(fn comments_count_by_account_on_post):
+            map hasher(twox_64_concat) (T::AccountId, PostId) => u32;
+
+        /// Ids of posts and comments of a deleted space (key) that are left to remove,
+        /// after their root posts were taken from `PostIdsBySpaceId`.
+        pub PostIdsToCleanUpBySpaceId get(fn post_ids_to_clean_up_by_space_id):
+            map hasher(twox_64_concat) SpaceId => Vec<PostId>;
+
+        /// Ids of subscription plans that give access to a root post (key).
+        /// If this list is empty, the post is available to everyone.
+        pub SubscriptionPlanIdsByPostId get(fn subscription_plan_ids_by_post_id):
+            map hasher(twox_64_concat) PostId => Vec<SubscriptionPlanId>;
     }
 }
 
@@ -139,6 +202,7 @@ decl_event!(
         PostDeleted(AccountId, PostId),
         PostShared(AccountId, PostId),
         PostMoved(AccountId, PostId),
+        PostSubscriptionPlansUpdated(AccountId, PostId),
     }
 );
 
@@ -161,6 +225,17 @@ decl_error! {
         NoRepliesOnPost,
         /// Cannot move a post to the same space.
         CannotMoveToSameSpace,
+        /// Post or comment was already turned into a tombstone.
+        PostIsAlreadyDeleted,
+
+        // Subscriptions related errors:
+
+        /// Only a root post can be available to subscribers only.
+        CannotLimitCommentToSubscribers,
+        /// Subscription plan was not found among active plans of the post space.
+        SubscriptionPlanNotFoundInSpace,
+        /// The post is available to subscribers only, and the account is not one of them.
+        NotASubscriber,
 
         // Sharing related errors:
 
@@ -198,6 +273,12 @@ decl_error! {
         NoPermissionToUpdateOwnPosts,
         /// A comment owner is not allowed to update their own comments in this space.
         NoPermissionToUpdateOwnComments,
+        /// User is not a post author and has no permission to delete posts in this space.
+        NoPermissionToDeleteAnyPost,
+        /// A post owner is not allowed to delete their own posts in this space.
+        NoPermissionToDeleteOwnPosts,
+        /// A comment owner is not allowed to delete their own comments in this space.
+        NoPermissionToDeleteOwnComments,
     }
 }
 
@@ -220,53 +301,7 @@ decl_module! {
       content: Content
     ) -> DispatchResult {
       let creator = ensure_signed(origin)?;
-
-      Utils::<T>::is_valid_content(content.clone())?;
-
-      let new_post_id = Self::next_post_id();
-      let new_post: Post<T> = Post::new(new_post_id, creator.clone(), space_id_opt, extension, content.clone());
-
-      // Get space from either space_id_opt or Comment if a comment provided
-      let space = &mut new_post.get_space()?;
-      ensure!(!space.hidden, Error::<T>::CannotCreateInHiddenScope);
-
-      ensure!(T::IsAccountBlocked::is_allowed_account(creator.clone(), space.id), UtilsError::<T>::AccountIsBlocked);
-      ensure!(T::IsContentBlocked::is_allowed_content(content, space.id), UtilsError::<T>::ContentIsBlocked);
-
-      let root_post = &mut new_post.get_root_post()?;
-      ensure!(!root_post.hidden, Error::<T>::CannotCreateInHiddenScope);
-
-      // Check whether account has permission to create Post (by extension)
-      let mut permission_to_check = SpacePermission::CreatePosts;
-      let mut error_on_permission_failed = Error::<T>::NoPermissionToCreatePosts;
-
-      if let PostExtension::Comment(_) = extension {
-        permission_to_check = SpacePermission::CreateComments;
-        error_on_permission_failed = Error::<T>::NoPermissionToCreateComments;
-      }
-
-      Spaces::ensure_account_has_space_permission(
-        creator.clone(),
-        &space,
-        permission_to_check,
-        error_on_permission_failed.into()
-      )?;
-
-      match extension {
-        PostExtension::RegularPost => space.inc_posts(),
-        PostExtension::SharedPost(post_id) => Self::create_sharing_post(&creator, new_post_id, post_id, space)?,
-        PostExtension::Comment(comment_ext) => Self::create_comment(&creator, new_post_id, comment_ext, root_post)?,
-      }
-
-      if new_post.is_root_post() {
-        SpaceById::insert(space.id, space.clone());
-        PostIdsBySpaceId::mutate(space.id, |ids| ids.push(new_post_id));
-      }
-
-      PostById::insert(new_post_id, new_post);
-      NextPostId::mutate(|n| { *n += 1; });
-
-      Self::deposit_event(RawEvent::PostCreated(creator, new_post_id));
+      Self::do_create_post(creator, space_id_opt, extension, content)?;
       Ok(())
     }
 
@@ -379,5 +414,71 @@ decl_module! {
       Self::deposit_event(RawEvent::PostMoved(who, post_id));
       Ok(())
     }
+
+    /// Delete a post or a comment.
+    ///
+    /// A post or a comment that has replies, shares or reactions is turned into a tombstone
+    /// (its content is erased), so the reply tree, sharing posts and reactions stay intact.
+    /// Otherwise it is removed from the storage.
+    #[weight = 100_000 + T::DbWeight::get().reads_writes(10, 8)]
+    pub fn delete_post(origin, post_id: PostId) -> DispatchResult {
+      let who = ensure_signed(origin)?;
+
+      let post = Self::require_post(post_id)?;
+
+      if let Some(space) = post.try_get_space() {
+        Self::ensure_account_can_delete_post(&who, &post, &space)?;
+      } else {
+        post.ensure_owner(&who)?;
+      }
+
+      if post.is_comment() {
+        Self::delete_comment(who.clone(), post)?;
+      } else {
+        Self::delete_root_post(who.clone(), post)?;
+      }
+
+      Self::deposit_event(RawEvent::PostDeleted(who, post_id));
+      Ok(())
+    }
+
+    /// Make a root post available only to the subscribers of any of `plan_ids`.
+    /// An empty list of plans makes the post available to everyone again.
+    #[weight = 50_000 + T::DbWeight::get().reads_writes(4, 1)]
+    pub fn update_post_subscription_plans(
+      origin,
+      post_id: PostId,
+      plan_ids: Vec<SubscriptionPlanId>
+    ) -> DispatchResult {
+      let editor = ensure_signed(origin)?;
+
+      let post = Self::require_post(post_id)?;
+      ensure!(!post.is_comment(), Error::<T>::CannotLimitCommentToSubscribers);
+
+      let space = post.get_space()?;
+      Self::ensure_account_can_update_post(&editor, &post, &space)?;
+
+      let mut plan_ids = plan_ids;
+      plan_ids.sort_unstable();
+      plan_ids.dedup();
+
+      for plan_id in plan_ids.iter() {
+        ensure!(
+          T::SubscriptionPlans::is_active_plan_in_space(*plan_id, space.id),
+          Error::<T>::SubscriptionPlanNotFoundInSpace
+        );
+      }
+
+      ensure!(plan_ids != Self::subscription_plan_ids_by_post_id(post_id), Error::<T>::NoUpdatesForPost);
+
+      if plan_ids.is_empty() {
+        SubscriptionPlanIdsByPostId::remove(post_id);
+      } else {
+        SubscriptionPlanIdsByPostId::insert(post_id, plan_ids);
+      }
+
+      Self::deposit_event(RawEvent::PostSubscriptionPlansUpdated(editor, post_id));
+      Ok(())
+    }
   }
 }
@@ -0,0 +1,135 @@
+use codec::{Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_runtime::RuntimeDebug;
+use sp_std::prelude::*;
+
+use pallet_utils::{SpaceId, rpc::{FlatContent, FlatWhoAndWhen}};
+
+use crate::{Module, Post, PostExtension, PostId, Trait};
+
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct FlatPost<AccountId, BlockNumber> {
+    pub id: PostId,
+
+    #[cfg_attr(feature = "std", serde(flatten))]
+    pub who_and_when: FlatWhoAndWhen<AccountId, BlockNumber>,
+
+    pub owner_id: AccountId,
+
+    pub extension: PostExtension,
+
+    #[cfg_attr(feature = "std", serde(skip_serializing_if = "Option::is_none"))]
+    pub space_id: Option<SpaceId>,
+
+    #[cfg_attr(feature = "std", serde(flatten))]
+    pub content: FlatContent,
+
+    pub hidden: bool,
+
+    pub replies_count: u16,
+    pub hidden_replies_count: u16,
+    pub visible_replies_count: u16,
+
+    pub shares_count: u16,
+    pub upvotes_count: u16,
+    pub downvotes_count: u16,
+
+    pub score: i32,
+}
+
+/// A post with its replies, where every reply has its own replies and so on.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct FlatReplyTree<AccountId, BlockNumber> {
+    pub post: FlatPost<AccountId, BlockNumber>,
+    pub replies: Vec<FlatReplyTree<AccountId, BlockNumber>>,
+}
+
+impl<T: Trait> From<Post<T>> for FlatPost<T::AccountId, T::BlockNumber> {
+    fn from(from: Post<T>) -> Self {
+        let Post {
+            id, created, updated, owner,
+            extension, space_id, content, hidden, replies_count,
+            hidden_replies_count, shares_count, upvotes_count, downvotes_count, score
+        } = from;
+
+        Self {
+            id,
+            who_and_when: (created, updated).into(),
+            owner_id: owner,
+            extension,
+            space_id,
+            content: content.into(),
+            hidden,
+            replies_count,
+            hidden_replies_count,
+            visible_replies_count: replies_count.saturating_sub(hidden_replies_count),
+            shares_count,
+            upvotes_count,
+            downvotes_count,
+            score,
+        }
+    }
+}
+
+impl<T: Trait> Module<T> {
+
+    /// Get a page of posts in a space, starting from the newest one.
+    /// Hidden posts are skipped unless `include_hidden` is `true`.
+    pub fn get_posts_by_space(
+        space_id: SpaceId,
+        offset: u64,
+        limit: u64,
+        include_hidden: bool,
+    ) -> Vec<FlatPost<T::AccountId, T::BlockNumber>> {
+        let mut post_ids = Self::post_ids_by_space_id(space_id);
+        // Post ids only grow, so the newest posts have the biggest ids.
+        post_ids.sort_unstable_by(|a, b| b.cmp(a));
+
+        post_ids.into_iter()
+            .filter_map(Self::post_by_id)
+            .filter(|post| include_hidden || !post.hidden)
+            .skip(offset as usize)
+            .take(limit as usize)
+            .map(|post| post.into())
+            .collect()
+    }
+
+    /// Get a post together with its visible replies up to `max_depth` levels deep.
+    /// Returns `None` if there is no post with such id.
+    pub fn get_reply_tree(
+        root_post_id: PostId,
+        max_depth: u32,
+    ) -> Option<FlatReplyTree<T::AccountId, T::BlockNumber>> {
+        Self::post_by_id(root_post_id).map(|post| Self::build_reply_tree(post, max_depth))
+    }
+
+    fn build_reply_tree(post: Post<T>, depth_left: u32) -> FlatReplyTree<T::AccountId, T::BlockNumber> {
+        let mut replies = Vec::new();
+
+        if depth_left > 0 {
+            replies = Self::reply_ids_by_post_id(post.id).into_iter()
+                .filter_map(Self::post_by_id)
+                .filter(|reply| !reply.hidden)
+                .map(|reply| Self::build_reply_tree(reply, depth_left - 1))
+                .collect();
+        }
+
+        FlatReplyTree {
+            post: post.into(),
+            replies,
+        }
+    }
+
+    /// Get all posts that share the post with `original_post_id`.
+    pub fn get_shared_posts(original_post_id: PostId) -> Vec<FlatPost<T::AccountId, T::BlockNumber>> {
+        Self::shared_post_ids_by_original_post_id(original_post_id).into_iter()
+            .filter_map(Self::post_by_id)
+            .map(|post| post.into())
+            .collect()
+    }
+}
@@ -12,7 +12,7 @@ use frame_system::{self as system, ensure_signed};
 
 use df_traits::moderation::IsAccountBlocked;
 use pallet_permissions::SpacePermission;
-use pallet_posts::{Module as Posts, Post, PostById, PostId};
+use pallet_posts::{Module as Posts, Post, PostById, PostCleaner, PostId};
 use pallet_spaces::Module as Spaces;
 use pallet_utils::{Error as UtilsError, remove_from_vec, WhoAndWhen};
 
@@ -125,6 +125,7 @@ decl_module! {
       ensure!(Posts::<T>::is_root_post_visible(post_id)?, Error::<T>::CannotReactWhenPostHidden);
 
       ensure!(T::IsAccountBlocked::is_allowed_account(owner.clone(), space.id), UtilsError::<T>::AccountIsBlocked);
+      Posts::<T>::ensure_account_can_access_post(&owner, post)?;
 
       let reaction_id = Self::insert_new_reaction(owner.clone(), kind);
 
@@ -162,7 +163,7 @@ decl_module! {
       Ok(())
     }
 
-    #[weight = 10_000 + T::DbWeight::get().reads_writes(3, 2)]
+    #[weight = 10_000 + T::DbWeight::get().reads_writes(6, 2)]
     pub fn update_post_reaction(origin, post_id: PostId, reaction_id: ReactionId, new_kind: ReactionKind) -> DispatchResult {
       let owner = ensure_signed(origin)?;
 
@@ -181,6 +182,8 @@ decl_module! {
         ensure!(T::IsAccountBlocked::is_allowed_account(owner.clone(), space_id), UtilsError::<T>::AccountIsBlocked);
       }
 
+      Posts::<T>::ensure_account_can_access_post(&owner, post)?;
+
       let old_kind = reaction.kind;
       reaction.kind = new_kind;
       reaction.updated = Some(WhoAndWhen::<T>::new(owner.clone()));
@@ -261,6 +264,35 @@ impl<T: Trait> Module<T> {
     }
 }
 
+impl<T: Trait> PostCleaner for Module<T> {
+    fn clean_up_post(post_id: PostId, limit: u32) -> u32 {
+        let mut removed = 0u32;
+        let mut reaction_ids = Self::reaction_ids_by_post_id(post_id);
+
+        while removed < limit {
+            let reaction_id = match reaction_ids.pop() {
+                Some(reaction_id) => reaction_id,
+                None => break,
+            };
+
+            if let Some(reaction) = Self::reaction_by_id(reaction_id) {
+                <PostReactionIdByAccount<T>>::remove((reaction.created.account, post_id));
+                <ReactionById<T>>::remove(reaction_id);
+            }
+
+            removed += 1;
+        }
+
+        if reaction_ids.is_empty() {
+            ReactionIdsByPostId::remove(post_id);
+        } else {
+            ReactionIdsByPostId::insert(post_id, reaction_ids);
+        }
+
+        removed
+    }
+}
+
 /// Handler that will be called right before the post reaction is toggled.
 pub trait PostReactionScores<T: Trait> {
     fn score_post_on_reaction(actor: T::AccountId, post: &mut Post<T>, reaction_kind: ReactionKind) -> DispatchResult;
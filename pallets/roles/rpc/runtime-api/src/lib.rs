@@ -0,0 +1,19 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::too_many_arguments)]
+#![allow(clippy::unnecessary_mut_passed)]
+
+use codec::Codec;
+
+use pallet_roles::rpc::FlatSpacePermissions;
+use pallet_utils::{SpaceId, User};
+
+sp_api::decl_runtime_apis! {
+    pub trait RolesApi<AccountId> where
+        AccountId: Codec
+    {
+        fn get_space_permissions_by_user(
+            user: User<AccountId>,
+            space_id: SpaceId,
+        ) -> Option<FlatSpacePermissions>;
+    }
+}
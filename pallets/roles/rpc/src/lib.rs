@@ -0,0 +1,61 @@
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::Result;
+use jsonrpc_derive::rpc;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+use pallet_roles::rpc::FlatSpacePermissions;
+use pallet_utils::{SpaceId, User};
+use utils_rpc::map_rpc_error;
+pub use roles_runtime_api::RolesApi as RolesRuntimeApi;
+
+#[rpc]
+pub trait RolesApi<BlockHash, AccountId> {
+    #[rpc(name = "roles_getSpacePermissionsByUser")]
+    fn get_space_permissions_by_user(
+        &self,
+        at: Option<BlockHash>,
+        user: User<AccountId>,
+        space_id: SpaceId,
+    ) -> Result<Option<FlatSpacePermissions>>;
+}
+
+/// A struct that implements the [`RolesApi`].
+pub struct Roles<C, M> {
+    client: Arc<C>,
+    _marker: std::marker::PhantomData<M>,
+}
+
+impl<C, M> Roles<C, M> {
+    /// Create new `Roles` with the given reference to the client.
+    pub fn new(client: Arc<C>) -> Self {
+        Self {
+            client,
+            _marker: Default::default(),
+        }
+    }
+}
+
+impl<C, Block, AccountId> RolesApi<<Block as BlockT>::Hash, AccountId>
+    for Roles<C, Block>
+where
+    Block: BlockT,
+    AccountId: Codec,
+    C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+    C::Api: RolesRuntimeApi<Block, AccountId>,
+{
+    fn get_space_permissions_by_user(
+        &self,
+        at: Option<<Block as BlockT>::Hash>,
+        user: User<AccountId>,
+        space_id: SpaceId,
+    ) -> Result<Option<FlatSpacePermissions>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.get_space_permissions_by_user(&at, user, space_id).map_err(map_rpc_error)
+    }
+}
@@ -31,19 +31,18 @@ impl<T: Trait> Module<T> {
     )
   }
 
-  fn ensure_user_has_space_permission_with_load_space(
-    user: User<T::AccountId>,
+  /// Load a space and build a permissions context for this user in it.
+  pub(crate) fn load_space_permissions_context(
+    user: &User<T::AccountId>,
     space_id: SpaceId,
-    permission: SpacePermission,
-    error: DispatchError,
-  ) -> DispatchResult {
+  ) -> Result<SpacePermissionsContext, DispatchError> {
 
     let space = T::Spaces::get_space(space_id)?;
 
     let mut is_owner = false;
     let mut is_follower = false;
 
-    match &user {
+    match user {
       User::Account(account) => {
         is_owner = *account == space.owner;
 
@@ -53,14 +52,26 @@ impl<T: Trait> Module<T> {
       User::Space(_) => (/* Not implemented yet. */),
     }
 
+    Ok(SpacePermissionsContext {
+      space_id,
+      is_space_owner: is_owner,
+      is_space_follower: is_follower,
+      space_perms: space.permissions
+    })
+  }
+
+  fn ensure_user_has_space_permission_with_load_space(
+    user: User<T::AccountId>,
+    space_id: SpaceId,
+    permission: SpacePermission,
+    error: DispatchError,
+  ) -> DispatchResult {
+
+    let ctx = Self::load_space_permissions_context(&user, space_id)?;
+
     Self::ensure_user_has_space_permission(
       user,
-      SpacePermissionsContext {
-        space_id,
-        is_space_owner: is_owner,
-        is_space_follower: is_follower,
-        space_perms: space.permissions
-      },
+      ctx,
       permission,
       error
     )
@@ -101,18 +112,7 @@ impl<T: Trait> Module<T> {
 
     for role_id in role_ids {
       if let Some(role) = Self::role_by_id(role_id) {
-        if role.disabled {
-          continue;
-        }
-
-        let mut is_expired = false;
-        if let Some(expires_at) = role.expires_at {
-          if expires_at <= <system::Module<T>>::block_number() {
-            is_expired = true;
-          }
-        }
-
-        if !is_expired && role.permissions.contains(&permission) {
+        if role.is_active() && role.permissions.contains(&permission) {
           return Ok(());
         }
       }
@@ -153,6 +153,18 @@ impl<T: Trait> Role<T> {
     Ok(new_role)
   }
 
+  pub fn is_expired(&self) -> bool {
+    if let Some(expires_at) = self.expires_at {
+      return expires_at <= <system::Module<T>>::block_number();
+    }
+    false
+  }
+
+  /// A role grants its permissions only if it is neither disabled nor expired.
+  pub fn is_active(&self) -> bool {
+    !self.disabled && !self.is_expired()
+  }
+
   pub fn set_disabled(&mut self, disable: bool) -> DispatchResult {
     if self.disabled && disable {
       return Err(Error::<T>::RoleAlreadyDisabled.into());
@@ -165,6 +177,17 @@ impl<T: Trait> Role<T> {
     Ok(())
   }
 
+  pub fn grant_to_users(&self, users: Vec<User<T::AccountId>>) {
+    for user in users.iter() {
+      if !Module::<T>::users_by_role_id(self.id).contains(&user) {
+        <UsersByRoleId<T>>::mutate(self.id, |users| { users.push(user.clone()); });
+      }
+      if !Module::<T>::role_ids_by_user_in_space(user.clone(), self.space_id).contains(&self.id) {
+        <RoleIdsByUserInSpace<T>>::mutate(user.clone(), self.space_id, |roles| { roles.push(self.id); })
+      }
+    }
+  }
+
   pub fn revoke_from_users(&self, users: Vec<User<T::AccountId>>) {
     let mut users_by_role = <UsersByRoleId<T>>::take(self.id);
 
@@ -204,3 +227,45 @@ impl<T: Trait> PermissionChecker for Module<T> {
     )
   }
 }
+
+impl<T: Trait> SpaceCleaner for Module<T> {
+  fn clean_up_space(space_id: SpaceId, limit: u32) -> u32 {
+    let mut removed = 0u32;
+    let mut role_ids = Self::role_ids_by_space_id(space_id);
+
+    while removed < limit {
+      let role_id = match role_ids.last() {
+        Some(role_id) => *role_id,
+        None => break,
+      };
+
+      if let Some(role) = Self::role_by_id(role_id) {
+        // Revoke the role from as many users as the limit allows.
+        let users: Vec<User<T::AccountId>> = Self::users_by_role_id(role_id).into_iter()
+          .take((limit - removed) as usize)
+          .collect();
+
+        removed += users.len() as u32;
+        role.revoke_from_users(users);
+
+        if removed >= limit || !Self::users_by_role_id(role_id).is_empty() {
+          break;
+        }
+
+        <RoleById<T>>::remove(role_id);
+        <UsersByRoleId<T>>::remove(role_id);
+        removed += 1;
+      }
+
+      role_ids.pop();
+    }
+
+    if role_ids.is_empty() {
+      RoleIdsBySpaceId::remove(space_id);
+    } else {
+      RoleIdsBySpaceId::insert(space_id, role_ids);
+    }
+
+    removed
+  }
+}
@@ -12,13 +12,14 @@ use sp_std::{collections::btree_set::BTreeSet, iter::FromIterator, prelude::*};
 use frame_system::{self as system, ensure_signed};
 
 use df_traits::{
-    PermissionChecker, SpaceFollowsProvider, SpaceForRolesProvider,
+    PermissionChecker, SpaceFollowsProvider, SpaceForRolesProvider, SpaceCleaner,
     moderation::{IsAccountBlocked, IsContentBlocked},
 };
 use pallet_permissions::{Module as Permissions, SpacePermission, SpacePermissionSet};
 use pallet_utils::{Module as Utils, Error as UtilsError, SpaceId, User, WhoAndWhen, Content};
 
 pub mod functions;
+pub mod rpc;
 
 #[cfg(test)]
 mod mock;
@@ -26,7 +27,7 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
-type RoleId = u64;
+pub type RoleId = u64;
 
 #[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
 pub struct Role<T: Trait> {
@@ -276,14 +277,7 @@ decl_module! {
 
       Self::ensure_role_manager(who.clone(), role.space_id)?;
 
-      for user in users_set.iter() {
-        if !Self::users_by_role_id(role_id).contains(&user) {
-          <UsersByRoleId<T>>::mutate(role_id, |users| { users.push(user.clone()); });
-        }
-        if !Self::role_ids_by_user_in_space(user.clone(), role.space_id).contains(&role_id) {
-          <RoleIdsByUserInSpace<T>>::mutate(user.clone(), role.space_id, |roles| { roles.push(role_id); })
-        }
-      }
+      role.grant_to_users(users_set.iter().cloned().collect());
 
       Self::deposit_event(RawEvent::RoleGranted(who, role_id, users_set.iter().cloned().collect()));
       Ok(())
@@ -0,0 +1,75 @@
+use codec::{Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_runtime::RuntimeDebug;
+use sp_std::{collections::btree_map::BTreeMap, prelude::*};
+
+use pallet_permissions::{
+  Module as Permissions, SpacePermission, SpacePermissionSet, SpacePermissionSource,
+};
+use pallet_utils::{SpaceId, User};
+
+use crate::{Module, RoleId, Trait};
+
+/// A permission that a user has in a space and all the reasons why they have it.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct FlatPermissionWithSources {
+  pub permission: SpacePermission,
+  pub sources: Vec<SpacePermissionSource<RoleId>>,
+}
+
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct FlatSpacePermissions {
+  /// The effective set of permissions the user has in this space.
+  pub permissions: SpacePermissionSet,
+  /// Where each of the effective permissions came from.
+  pub sources: Vec<FlatPermissionWithSources>,
+  /// Permissions forbidden for everyone in this space, even if granted by a role.
+  pub forbidden: SpacePermissionSet,
+}
+
+impl<T: Trait> Module<T> {
+
+  /// Get the effective permissions of a user in a space, resolved the same way
+  /// `ensure_user_has_space_permission` does it. Returns `None` if the space was not found.
+  pub fn get_space_permissions_by_user(
+    user: User<T::AccountId>,
+    space_id: SpaceId,
+  ) -> Option<FlatSpacePermissions> {
+
+    let ctx = Self::load_space_permissions_context(&user, space_id).ok()?;
+
+    let (forbidden, granted_by_levels) = Permissions::<T>::get_space_permissions_by_levels(ctx);
+
+    let mut sources_by_permission: BTreeMap<SpacePermission, Vec<SpacePermissionSource<RoleId>>> = BTreeMap::new();
+    for (permission, source) in granted_by_levels {
+      sources_by_permission.entry(permission).or_default().push(source);
+    }
+
+    for role_id in Self::role_ids_by_user_in_space(&user, space_id) {
+      if let Some(role) = Self::role_by_id(role_id) {
+        if !role.is_active() {
+          continue;
+        }
+
+        for permission in role.permissions {
+          if !forbidden.contains(&permission) {
+            sources_by_permission.entry(permission).or_default().push(SpacePermissionSource::Role(role_id));
+          }
+        }
+      }
+    }
+
+    Some(FlatSpacePermissions {
+      permissions: sources_by_permission.keys().cloned().collect(),
+      sources: sources_by_permission.into_iter()
+        .map(|(permission, sources)| FlatPermissionWithSources { permission, sources })
+        .collect(),
+      forbidden,
+    })
+  }
+}
@@ -4,6 +4,7 @@ use crate::*;
 use frame_support::{
     assert_ok, assert_noop,
 };
+use pallet_permissions::{SpacePermissionLevel, SpacePermissionSource};
 use pallet_utils::{Error as UtilsError};
 
 #[test]
@@ -590,3 +591,65 @@ fn delete_role_should_fail_with_a_few_roles_no_permission() {
         );
     });
 }
+
+#[test]
+fn get_space_permissions_by_user_should_work() {
+    ExtBuilder::build_with_a_few_roles_granted_to_account2().execute_with(|| {
+        let perms = Roles::get_space_permissions_by_user(User::Account(ACCOUNT2), SPACE1).unwrap();
+
+        let sources_of = |permission: SpacePermission| {
+            perms.sources.iter()
+                .find(|p| p.permission == permission)
+                .map(|p| p.sources.clone())
+        };
+
+        assert!(perms.permissions.contains(&SpacePermission::CreatePosts));
+        assert!(perms.permissions.contains(&SpacePermission::ManageRoles));
+        assert!(!perms.permissions.contains(&SpacePermission::UpdateSpace));
+        assert!(perms.forbidden.is_empty());
+
+        assert_eq!(sources_of(SpacePermission::CreatePosts), Some(vec![SpacePermissionSource::Role(ROLE1)]));
+        assert_eq!(sources_of(SpacePermission::ManageRoles), Some(vec![SpacePermissionSource::Role(ROLE2)]));
+        assert_eq!(
+            sources_of(SpacePermission::Upvote),
+            Some(vec![SpacePermissionSource::Default(SpacePermissionLevel::Everyone)])
+        );
+        // `UpdateOwnPosts` is granted by both the default permissions and the RoleId 1
+        assert_eq!(
+            sources_of(SpacePermission::UpdateOwnPosts),
+            Some(vec![
+                SpacePermissionSource::Default(SpacePermissionLevel::Everyone),
+                SpacePermissionSource::Role(ROLE1),
+            ])
+        );
+    });
+}
+
+#[test]
+fn get_space_permissions_by_user_should_include_space_owner_level() {
+    ExtBuilder::build().execute_with(|| {
+        let perms = Roles::get_space_permissions_by_user(User::Account(ACCOUNT1), SPACE1).unwrap();
+
+        let manage_roles = perms.sources.iter()
+            .find(|p| p.permission == SpacePermission::ManageRoles)
+            .unwrap();
+        assert_eq!(manage_roles.sources, vec![SpacePermissionSource::Default(SpacePermissionLevel::SpaceOwner)]);
+    });
+}
+
+#[test]
+fn get_space_permissions_by_user_should_skip_disabled_roles() {
+    ExtBuilder::build_with_a_few_roles_granted_to_account2().execute_with(|| {
+        assert_ok!(_update_role(None, Some(ROLE2), Some(role_update(Some(true), None, None))));
+
+        let perms = Roles::get_space_permissions_by_user(User::Account(ACCOUNT2), SPACE1).unwrap();
+        assert!(!perms.permissions.contains(&SpacePermission::ManageRoles));
+    });
+}
+
+#[test]
+fn get_space_permissions_by_user_should_return_none_when_space_not_found() {
+    ExtBuilder::build().execute_with(|| {
+        assert!(Roles::get_space_permissions_by_user(User::Account(ACCOUNT1), SPACE2).is_none());
+    });
+}
@@ -283,6 +283,20 @@ impl<T: Trait> Module<T> {
         Ok(())
     }
 
+    /// Revert a score diff that this account added to the post with this action, if any.
+    fn revert_post_score(
+        account: T::AccountId,
+        post: &mut Post<T>,
+        action: ScoringAction,
+    ) -> DispatchResult {
+        if Self::post_score_by_account((account.clone(), post.id, action)).is_some() {
+            // Changing a score with the same action one more time reverts it.
+            Self::change_post_score(account, post, action)
+        } else {
+            Ok(())
+        }
+    }
+
     // TODO change order of args to: actor (scorer), subject (account), ...
     pub fn change_social_account_reputation(
         account: T::AccountId,
@@ -423,6 +437,18 @@ impl<T: Trait> PostScores<T> for Module<T> {
     fn score_root_post_on_new_comment(account: T::AccountId, root_post: &mut Post<T>) -> DispatchResult {
         Self::change_post_score(account, root_post, ScoringAction::CreateComment)
     }
+
+    fn score_post_on_share_deleted(account: T::AccountId, original_post: &mut Post<T>) -> DispatchResult {
+        let action =
+            if original_post.is_comment() { ScoringAction::ShareComment }
+            else { ScoringAction::SharePost };
+
+        Self::revert_post_score(account, original_post, action)
+    }
+
+    fn score_root_post_on_comment_deleted(account: T::AccountId, root_post: &mut Post<T>) -> DispatchResult {
+        Self::revert_post_score(account, root_post, ScoringAction::CreateComment)
+    }
 }
 
 impl<T: Trait> PostReactionScores<T> for Module<T> {
@@ -9,6 +9,9 @@
 //! to create a utility proxy account that will act (sign txs) on behalf of the main account
 //! so that UI will not ask a "Sign tx" confirmation modal for a specific set of extrinsic
 //! initiated by this proxy session key.
+//!
+//! A session key can be narrowed down to a set of allowed pallets/calls
+//! and a set of spaces it may act in.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::boxed_local)]
@@ -16,24 +19,26 @@
 use codec::{Decode, Encode};
 use sp_std::prelude::*;
 use sp_runtime::RuntimeDebug;
-use sp_runtime::traits::{Zero, Dispatchable, Saturating};
-use pallet_transaction_payment::Trait as TransactionPaymentTrait;
+use sp_runtime::{FixedPointOperand, traits::{Zero, Dispatchable, Saturating}};
+use pallet_transaction_payment::{Module as TransactionPayment, Trait as TransactionPaymentTrait};
 use frame_support::{
     decl_error, decl_event, decl_module, decl_storage, ensure, fail,
     weights::{
-        GetDispatchInfo, DispatchClass, WeighData, WeightToFeePolynomial,
+        GetDispatchInfo, DispatchInfo, DispatchClass, WeighData,
         Weight, ClassifyDispatch, PaysFee, Pays,
     },
-    dispatch::{DispatchError, DispatchResult, PostDispatchInfo},
+    dispatch::{DispatchError, DispatchResult, DispatchResultWithPostInfo, PostDispatchInfo},
+    storage::{with_transaction, TransactionOutcome},
     traits::{
         Currency, Get, ExistenceRequirement,
         OriginTrait, IsType, Filter,
+        CallMetadata, GetCallMetadata,
     },
     Parameter,
 };
 use frame_system::{self as system, ensure_signed};
 
-use pallet_utils::WhoAndWhen;
+use pallet_utils::{SpaceId, WhoAndWhen};
 
 #[cfg(test)]
 mod mock;
@@ -64,7 +69,26 @@ impl<T: Trait> PaysFee<(&Box<<T as Trait>::Call>,)> for CalculateProxyWeight<T>
 type BalanceOf<T> =
     <<T as TransactionPaymentTrait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
 
-// TODO define session key permissions
+/// A call (or a set of calls) that a session key is allowed to dispatch.
+/// Names are the same as in the runtime metadata, e.g. `Posts` and `create_post`.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+pub enum CallPermission {
+    /// Any call of the pallet with this name.
+    AnyCallOf(/* pallet name */ Vec<u8>),
+    /// A specific call of the pallet.
+    Call(/* pallet name */ Vec<u8>, /* call name */ Vec<u8>),
+}
+
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, Default)]
+pub struct SessionKeyPermissions {
+    /// Calls this session key can dispatch in addition to `BaseFilter`.
+    /// `None` means any call allowed by `BaseFilter`.
+    pub allowed_calls: Option<Vec<CallPermission>>,
+
+    /// Spaces this session key can act in. A call that does not act in any space
+    /// cannot be dispatched if this list is provided. `None` means any space.
+    pub allowed_spaces: Option<Vec<SpaceId>>,
+}
 
 #[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
 pub struct SessionKey<T: Trait> {
@@ -80,10 +104,23 @@ pub struct SessionKey<T: Trait> {
     /// Max amount of tokens allowed to spend with this session key.
     pub limit: Option<BalanceOf<T>>,
 
-    /// How many tokens this session key already spent.
+    /// How many tokens this session key already spent:
+    /// fees of proxied calls plus the tokens these calls moved from the owner's account.
     pub spent: BalanceOf<T>,
 
-    // TODO allowed_actions: ...
+    /// What calls this session key can dispatch and in what spaces.
+    pub permissions: SessionKeyPermissions,
+}
+
+/// Finds out in what space a call acts, if any.
+pub trait CallSpaceResolver<Call> {
+    fn call_space(call: &Call) -> Option<SpaceId>;
+}
+
+impl<Call> CallSpaceResolver<Call> for () {
+    fn call_space(_call: &Call) -> Option<SpaceId> {
+        None
+    }
 }
 
 /// The pallet's configuration trait.
@@ -97,7 +134,7 @@ pub trait Trait: system::Trait
     /// The overarching call type.
     type Call: Parameter
         + Dispatchable<Origin=Self::Origin, PostInfo=PostDispatchInfo>
-        + GetDispatchInfo + From<frame_system::Call<Self>>
+        + GetDispatchInfo + GetCallMetadata + From<frame_system::Call<Self>>
         + IsType<<Self as frame_system::Trait>::Call>;
 
     /// The maximum amount of session keys allowed for a single account.
@@ -108,14 +145,22 @@ pub trait Trait: system::Trait
 
     /// The amount of money transferred to session key
     type BaseSessionKeyBond: Get<BalanceOf<Self>>;
+
+    /// Resolves a space of a call to check it against the spaces allowed for a session key.
+    type CallSpaceResolver: CallSpaceResolver<<Self as Trait>::Call>;
 }
 
 decl_event!(
     pub enum Event<T> where
-        <T as system::Trait>::AccountId
+        <T as system::Trait>::AccountId,
+        <T as system::Trait>::BlockNumber,
+        Balance = BalanceOf<T>
     {
         SessionKeyAdded(/* owner */ AccountId, /* session key */ AccountId),
         SessionKeyRemoved(/* session key */ AccountId),
+        SessionKeyExtended(/* session key */ AccountId, /* expires at */ BlockNumber),
+        SessionKeyRotated(/* old session key */ AccountId, /* new session key */ AccountId),
+        SessionKeyToppedUp(/* session key */ AccountId, /* new limit */ Balance),
         AllSessionKeysRemoved(/* owner */ AccountId),
         /// A proxy was executed correctly, with the given result.
 		ProxyExecuted(DispatchResult),
@@ -140,6 +185,14 @@ decl_error! {
         SessionKeyLimitReached,
         /// Only a session key owner can manage their keys.
         NotASessionKeyOwner,
+        /// Session key has no limit, so there is nothing to top up.
+        SessionKeyHasNoLimit,
+        /// A list of allowed calls or spaces cannot be empty. Use `None` to allow any.
+        EmptyPermissionsList,
+        /// This call is not allowed for the session key.
+        CallNotAllowedForSessionKey,
+        /// The session key cannot act in the space of this call.
+        SpaceNotAllowedForSessionKey,
     }
 }
 
@@ -167,7 +220,11 @@ decl_storage! {
 
 // The pallet's dispatchable functions.
 decl_module! {
-    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+    pub struct Module<T: Trait> for enum Call where
+        origin: T::Origin,
+        BalanceOf<T>: FixedPointOperand,
+        <T as frame_system::Trait>::Call: Dispatchable<Info=DispatchInfo, PostInfo=PostDispatchInfo>
+    {
 
         const MaxSessionKeysPerAccount: u16 = T::MaxSessionKeysPerAccount::get();
 
@@ -183,15 +240,17 @@ decl_module! {
 
         /// Add a new SessionKey for `origin` bonding `BaseSessionKeyBond` to keep session alive
         #[weight = 10_000 + T::DbWeight::get().reads_writes(3, 3)]
-        fn add_key(origin,
+        pub fn add_key(origin,
             key_account: T::AccountId,
             time_to_live: T::BlockNumber,
             limit: Option<BalanceOf<T>>,
+            permissions: SessionKeyPermissions,
         ) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
             ensure!(time_to_live > Zero::zero(), Error::<T>::ZeroTimeToLive);
             ensure!(limit != Some(Zero::zero()), Error::<T>::ZeroLimit);
+            ensure!(permissions.has_no_empty_lists(), Error::<T>::EmptyPermissionsList);
             ensure!(!KeyDetails::<T>::contains_key(key_account.clone()), Error::<T>::SessionKeyAlreadyAdded);
 
             let mut keys = KeysByOwner::<T>::get(who.clone());
@@ -203,7 +262,7 @@ decl_module! {
             keys.insert(i, key_account.clone());
             KeysByOwner::<T>::insert(&who, keys);
 
-            let details = SessionKey::<T>::new(who.clone(), time_to_live, limit);
+            let details = SessionKey::<T>::new(who.clone(), time_to_live, limit, permissions);
             KeyDetails::<T>::insert(key_account.clone(), details);
 
             let current_block = system::Module::<T>::block_number();
@@ -220,7 +279,7 @@ decl_module! {
 
         /// A key could be removed either the origin is an owner or key is expired.
         #[weight = 10_000 + T::DbWeight::get().reads_writes(2, 2)]
-        fn remove_key(origin, key_account: T::AccountId) -> DispatchResult {
+        pub fn remove_key(origin, key_account: T::AccountId) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
             let key = Self::require_key(key_account.clone())?;
@@ -231,9 +290,78 @@ decl_module! {
             Ok(())
         }
 
+        /// Extend the time to live of a session key by `extend_by` blocks.
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(3, 3)]
+        pub fn extend_key(origin, key_account: T::AccountId, extend_by: T::BlockNumber) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            ensure!(extend_by > Zero::zero(), Error::<T>::ZeroTimeToLive);
+
+            let mut details = Self::require_key(key_account.clone())?;
+            ensure!(details.is_owner(&who), Error::<T>::NotASessionKeyOwner);
+            ensure!(!details.is_expired(), Error::<T>::SessionKeyExpired);
+
+            let old_expiration_block = details.expires_at;
+            let new_expiration_block = old_expiration_block.saturating_add(extend_by);
+
+            Self::remove_from_expiration_index(old_expiration_block, &key_account);
+            SessionKeysByExpireBlock::<T>::mutate(
+                new_expiration_block,
+                |keys| keys.push((who.clone(), key_account.clone()))
+            );
+
+            details.expires_at = new_expiration_block;
+            details.updated = Some(WhoAndWhen::<T>::new(who));
+            KeyDetails::<T>::insert(key_account.clone(), details);
+
+            Self::deposit_event(RawEvent::SessionKeyExtended(key_account, new_expiration_block));
+            Ok(())
+        }
+
+        /// Replace a session key with a new key account. The new key gets the balance left
+        /// on the old key account, as well as the expiration, limit, spent tokens and permissions.
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(5, 6)]
+        pub fn rotate_key(origin, old_key_account: T::AccountId, new_key_account: T::AccountId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let mut details = Self::require_key(old_key_account.clone())?;
+            ensure!(details.is_owner(&who), Error::<T>::NotASessionKeyOwner);
+            ensure!(!details.is_expired(), Error::<T>::SessionKeyExpired);
+            ensure!(!KeyDetails::<T>::contains_key(new_key_account.clone()), Error::<T>::SessionKeyAlreadyAdded);
+
+            let mut keys = KeysByOwner::<T>::get(who.clone());
+            let old_index = keys.binary_search(&old_key_account).ok().ok_or(Error::<T>::SessionKeyNotFound)?;
+
+            // This is the only fallible step, so nothing is changed if the transfer fails.
+            <T as TransactionPaymentTrait>::Currency::transfer(
+                &old_key_account,
+                &new_key_account,
+                <T as TransactionPaymentTrait>::Currency::free_balance(&old_key_account),
+                ExistenceRequirement::AllowDeath
+            )?;
+
+            keys.remove(old_index);
+            let new_index = keys.binary_search(&new_key_account).unwrap_or_else(|i| i);
+            keys.insert(new_index, new_key_account.clone());
+            KeysByOwner::<T>::insert(&who, keys);
+
+            Self::remove_from_expiration_index(details.expires_at, &old_key_account);
+            SessionKeysByExpireBlock::<T>::mutate(
+                details.expires_at,
+                |keys| keys.push((who.clone(), new_key_account.clone()))
+            );
+
+            details.updated = Some(WhoAndWhen::<T>::new(who));
+            KeyDetails::<T>::remove(old_key_account.clone());
+            KeyDetails::<T>::insert(new_key_account.clone(), details);
+
+            Self::deposit_event(RawEvent::SessionKeyRotated(old_key_account, new_key_account));
+            Ok(())
+        }
+
         /// Unregister all session keys for the sender.
         #[weight = 10_000 + T::DbWeight::get().reads_writes(1, 2) * T::MaxSessionKeysPerAccount::get() as u64]
-        fn remove_keys(origin) -> DispatchResult {
+        pub fn remove_keys(origin) -> DispatchResult {
             let who = ensure_signed(origin)?;
             let keys = KeysByOwner::<T>::take(&who);
             for key in keys {
@@ -247,7 +375,7 @@ decl_module! {
 
         /// Execute the call by a session key (`origin`) on behalf of its owner.
         #[weight = CalculateProxyWeight::<T>(call.clone())]
-        fn proxy(origin, call: Box<<T as Trait>::Call>) -> DispatchResult {
+        pub fn proxy(origin, call: Box<<T as Trait>::Call>) -> DispatchResultWithPostInfo {
             let key = ensure_signed(origin)?;
 
             let mut details = Self::require_key(key.clone())?;
@@ -258,38 +386,96 @@ decl_module! {
             }
 
             let real = details.owner();
-            let can_spend: BalanceOf<T>;
 
-            // TODO check that this call is among allowed calls per this account/session key.
+            Self::ensure_call_allowed(&details.permissions, &call)?;
+
+            let permissions = details.permissions.clone();
             let mut origin: T::Origin = frame_system::RawOrigin::Signed(real.clone()).into();
 			origin.add_filter(move |c: &<T as frame_system::Trait>::Call| {
 				let c = <T as Trait>::Call::from_ref(c);
-				T::BaseFilter::filter(c)
+				// Nested calls (if any) should satisfy the permissions of the session key too.
+				T::BaseFilter::filter(c) && Self::ensure_call_allowed(&permissions, c).is_ok()
 			});
 
-            let call_dispatch_info = call.get_dispatch_info();
-            if call_dispatch_info.pays_fee == Pays::Yes {
-                let spent_on_call = Self::get_extrinsic_fees(call.clone());
+            // The key account pays for this `proxy` extrinsic, not for the proxied call itself.
+            let (proxy_length, proxy_info) = Self::proxy_call_info(call.clone());
+            let estimated_fee = TransactionPayment::<T>::compute_fee(proxy_length, &proxy_info, Zero::zero());
+
+            // `None` if this session key has no limit.
+            let can_spend = details.limit.map(|limit| limit.saturating_sub(details.spent));
+            if let Some(can_spend) = can_spend {
+                ensure!(can_spend >= estimated_fee, Error::<T>::SessionKeyLimitReached);
+            }
+
+            let balance_before_call = <T as TransactionPaymentTrait>::Currency::free_balance(&real);
+
+            let (result, spent_on_call) = with_transaction(|| {
+                let result = call.dispatch(origin);
 
-                // TODO get limit from account settings
-                if let Some(limit) = details.limit {
-                    can_spend = limit.saturating_sub(details.spent);
-                    ensure!(can_spend >= spent_on_call, Error::<T>::SessionKeyLimitReached);
+                // Tokens that the proxied call moved from the owner's account, e.g. a transfer or a deposit.
+                let moved_by_call = balance_before_call
+                    .saturating_sub(<T as TransactionPaymentTrait>::Currency::free_balance(&real));
+
+                // The key account has paid the fee for the actual weight of this call,
+                // so the owner reimburses exactly this fee.
+                let actual_weight = match &result {
+                    Ok(post_info) => post_info.actual_weight,
+                    Err(err) => err.post_info.actual_weight,
+                };
+                let actual_fee = TransactionPayment::<T>::compute_actual_fee(
+                    proxy_length, &proxy_info, &PostDispatchInfo::from(actual_weight), Zero::zero()
+                );
+
+                let spent_on_call = moved_by_call.saturating_add(actual_fee);
+                if can_spend.map_or(false, |can_spend| spent_on_call > can_spend) {
+                    return TransactionOutcome::Rollback(Err(Error::<T>::SessionKeyLimitReached.into()));
                 }
 
-                <T as TransactionPaymentTrait>::Currency::transfer(&real, &key, spent_on_call, ExistenceRequirement::KeepAlive)?;
+                let reimbursed = <T as TransactionPaymentTrait>::Currency::transfer(
+                    &real, &key, actual_fee, ExistenceRequirement::KeepAlive
+                );
 
-                // TODO: what if balance left is less than fees on the next call?
+                match reimbursed {
+                    Ok(_) => TransactionOutcome::Commit(Ok((result, spent_on_call))),
+                    Err(err) => TransactionOutcome::Rollback(Err(err)),
+                }
+            })?;
 
+            if !spent_on_call.is_zero() {
                 details.spent = details.spent.saturating_add(spent_on_call);
                 details.updated = Some(WhoAndWhen::<T>::new(key.clone()));
-
                 KeyDetails::<T>::insert(key, details);
             }
 
-            let e = call.dispatch(origin);
-            Self::deposit_event(RawEvent::ProxyExecuted(e.map(|_| ()).map_err(|e| e.error)));
+            let actual_weight = match &result {
+                Ok(post_info) => post_info.actual_weight,
+                Err(err) => err.post_info.actual_weight,
+            };
+
+            Self::deposit_event(RawEvent::ProxyExecuted(result.map(|_| ()).map_err(|e| e.error)));
+            Ok(actual_weight.into())
+        }
+
+        /// Raise the limit of tokens that a session key can spend by `amount`.
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(1, 1)]
+        pub fn top_up_key(origin, key_account: T::AccountId, amount: BalanceOf<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            ensure!(amount > Zero::zero(), Error::<T>::ZeroLimit);
+
+            let mut details = Self::require_key(key_account.clone())?;
+            ensure!(details.is_owner(&who), Error::<T>::NotASessionKeyOwner);
+            ensure!(!details.is_expired(), Error::<T>::SessionKeyExpired);
 
+            let new_limit = details.limit
+                .ok_or(Error::<T>::SessionKeyHasNoLimit)?
+                .saturating_add(amount);
+
+            details.limit = Some(new_limit);
+            details.updated = Some(WhoAndWhen::<T>::new(who));
+            KeyDetails::<T>::insert(key_account.clone(), details);
+
+            Self::deposit_event(RawEvent::SessionKeyToppedUp(key_account, new_limit));
             Ok(())
         }
 
@@ -309,6 +495,7 @@ impl<T: Trait> SessionKey<T> {
         created_by: T::AccountId,
         time_to_live: T::BlockNumber,
         limit: Option<BalanceOf<T>>,
+        permissions: SessionKeyPermissions,
     ) -> Self {
         SessionKey::<T> {
             created: WhoAndWhen::new(created_by),
@@ -316,6 +503,7 @@ impl<T: Trait> SessionKey<T> {
             expires_at: time_to_live + <system::Module<T>>::block_number(),
             limit,
             spent: Zero::zero(),
+            permissions,
         }
     }
 
@@ -332,7 +520,29 @@ impl<T: Trait> SessionKey<T> {
     }
 }
 
-impl<T: Trait> Module<T> {
+impl CallPermission {
+    pub fn allows(&self, call: &CallMetadata) -> bool {
+        match self {
+            CallPermission::AnyCallOf(pallet) =>
+                pallet.as_slice() == call.pallet_name.as_bytes(),
+            CallPermission::Call(pallet, function) =>
+                pallet.as_slice() == call.pallet_name.as_bytes()
+                    && function.as_slice() == call.function_name.as_bytes(),
+        }
+    }
+}
+
+impl SessionKeyPermissions {
+    pub fn has_no_empty_lists(&self) -> bool {
+        self.allowed_calls.as_ref().map_or(true, |calls| !calls.is_empty())
+            && self.allowed_spaces.as_ref().map_or(true, |spaces| !spaces.is_empty())
+    }
+}
+
+impl<T: Trait> Module<T> where
+    BalanceOf<T>: FixedPointOperand,
+    <T as frame_system::Trait>::Call: Dispatchable<Info=DispatchInfo, PostInfo=PostDispatchInfo>
+{
 
     /// Get `SessionKey` details by `key_account` from the storage
     /// or return `SessionKeyNotFound` error.
@@ -355,6 +565,23 @@ impl<T: Trait> Module<T> {
         Ok(())
     }
 
+    /// Remove a session key from the list of keys that expire at `expiration_block`.
+    fn remove_from_expiration_index(expiration_block: T::BlockNumber, key_account: &T::AccountId) {
+        SessionKeysByExpireBlock::<T>::mutate_exists(expiration_block, |keys_opt| {
+            let is_empty = match keys_opt {
+                Some(keys) => {
+                    keys.retain(|(_, key)| key != key_account);
+                    keys.is_empty()
+                },
+                None => false,
+            };
+
+            if is_empty {
+                *keys_opt = None;
+            }
+        });
+    }
+
     /// Transfer tokens amount/entire free balance (if amount is `None`) from key account to owner
     fn withdraw_key_account_to_owner(
         key_account: &T::AccountId,
@@ -380,12 +607,40 @@ impl<T: Trait> Module<T> {
         )
     }
 
-    fn get_extrinsic_fees(call: Box<<T as Trait>::Call>) -> BalanceOf<T> {
-        let byte_fee = T::TransactionByteFee::get();
-        let call_length = call.encode().len() as u32;
-        let length_fee = BalanceOf::<T>::from(call_length).saturating_mul(byte_fee);
-        let weight_fee = T::WeightToFee::calc(&call.get_dispatch_info().weight);
+    /// Check that `call` is among the calls allowed for a session key
+    /// and that it acts in one of the spaces allowed for this key.
+    fn ensure_call_allowed(
+        permissions: &SessionKeyPermissions,
+        call: &<T as Trait>::Call
+    ) -> DispatchResult {
+        if let Some(allowed_calls) = &permissions.allowed_calls {
+            let call_metadata = call.get_call_metadata();
+            ensure!(
+                allowed_calls.iter().any(|allowed| allowed.allows(&call_metadata)),
+                Error::<T>::CallNotAllowedForSessionKey
+            );
+        }
+
+        if let Some(allowed_spaces) = &permissions.allowed_spaces {
+            let is_allowed_space = T::CallSpaceResolver::call_space(call)
+                .map_or(false, |space_id| allowed_spaces.contains(&space_id));
+
+            ensure!(is_allowed_space, Error::<T>::SpaceNotAllowedForSessionKey);
+        }
+
+        Ok(())
+    }
+
+    /// Length and pre-dispatch info of a `proxy` extrinsic with this call.
+    /// The length of a signature and signed extensions is not known here, so it's not included.
+    fn proxy_call_info(call: Box<<T as Trait>::Call>) -> (u32, DispatchInfo) {
+        let proxy_call = Call::<T>::proxy(call);
+        (proxy_call.encode().len() as u32, proxy_call.get_dispatch_info())
+    }
 
-        length_fee + weight_fee
+    /// Estimate fees that a session key pays to proxy a call, by its pre-dispatch weight.
+    pub fn get_extrinsic_fees(call: Box<<T as Trait>::Call>) -> BalanceOf<T> {
+        let (proxy_length, proxy_info) = Self::proxy_call_info(call);
+        TransactionPayment::<T>::compute_fee(proxy_length, &proxy_info, Zero::zero())
     }
 }
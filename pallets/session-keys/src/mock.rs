@@ -1,4 +1,4 @@
-use crate::{Module, Trait};
+use crate::{Module, Trait, CallSpaceResolver, SessionKeyPermissions};
 
 use sp_io::TestExternalities;
 use sp_core::H256;
@@ -13,7 +13,9 @@ use frame_support::{
     dispatch::{DispatchResult},
 };
 
+use pallet_balances::Call as BalancesCall;
 use pallet_profile_follows::Call as ProfileFollowsCall;
+use pallet_utils::SpaceId;
 use frame_support::traits::Currency;
 pub use pallet_transaction_payment::{Multiplier, TargetedFeeAdjustment};
 
@@ -154,12 +156,25 @@ impl Trait for Test {
     type MaxSessionKeysPerAccount = MaxSessionKeysPerAccount;
     type BaseFilter = ();
     type BaseSessionKeyBond = BaseSessionKeyBond;
+    type CallSpaceResolver = MockCallSpaceResolver;
+}
+
+/// Profile follows act in no space in fact, but here they are treated as calls in `SPACE1`
+/// to test how the spaces allowed for a session key are checked.
+pub struct MockCallSpaceResolver;
+impl CallSpaceResolver<Call> for MockCallSpaceResolver {
+    fn call_space(call: &Call) -> Option<SpaceId> {
+        match call {
+            Call::ProfileFollows(..) => Some(SPACE1),
+            _ => None,
+        }
+    }
 }
 
 pub(crate) type System = system::Module<Test>;
 pub(crate) type SessionKeys = Module<Test>;
 pub(crate) type Balances = pallet_balances::Module<Test>;
-type ProfileFollows = pallet_profile_follows::Module<Test>;
+pub(crate) type ProfileFollows = pallet_profile_follows::Module<Test>;
 
 pub(crate) type AccountId = u64;
 pub(crate) type BlockNumber = u64;
@@ -200,6 +215,9 @@ pub(crate) const ACCOUNT_PROXY: AccountId = 2;
 pub(crate) const ACCOUNT3: AccountId = 3;
 pub(crate) const ACCOUNT4: AccountId = 4;
 
+pub(crate) const SPACE1: SpaceId = 1001;
+pub(crate) const SPACE2: SpaceId = 1002;
+
 pub(crate) const DEFAULT_SESSION_KEY_BALANCE: Balance = 1 * DOLLARS;
 pub(crate) const BLOCKS_TO_LIVE: BlockNumber = 20;
 
@@ -222,6 +240,17 @@ pub(crate) fn _add_key(
         key_account.unwrap_or(ACCOUNT_PROXY),
         time_to_live.unwrap_or(BLOCKS_TO_LIVE),
         limit.unwrap_or(Some(DEFAULT_SESSION_KEY_BALANCE)),
+        SessionKeyPermissions::default(),
+    )
+}
+
+pub(crate) fn _add_key_with_permissions(permissions: SessionKeyPermissions) -> DispatchResult {
+    SessionKeys::add_key(
+        Origin::signed(ACCOUNT_MAIN),
+        ACCOUNT_PROXY,
+        BLOCKS_TO_LIVE,
+        Some(DEFAULT_SESSION_KEY_BALANCE),
+        permissions,
     )
 }
 
@@ -262,5 +291,25 @@ pub(crate) fn _proxy(
     SessionKeys::proxy(
         origin.unwrap_or_else(|| Origin::signed(ACCOUNT_PROXY)),
         Box::new(call.unwrap_or(follow_account_proxy_call())),
+    ).map(|_| ()).map_err(|err| err.error)
+}
+
+pub(crate) fn transfer_proxy_call(amount: Balance) -> Call {
+    Call::Balances(BalancesCall::transfer(ACCOUNT3, amount))
+}
+
+pub(crate) fn _top_up_default_key(amount: Balance) -> DispatchResult {
+    SessionKeys::top_up_key(Origin::signed(ACCOUNT_MAIN), ACCOUNT_PROXY, amount)
+}
+
+pub(crate) fn _extend_default_key(extend_by: BlockNumber) -> DispatchResult {
+    SessionKeys::extend_key(Origin::signed(ACCOUNT_MAIN), ACCOUNT_PROXY, extend_by)
+}
+
+pub(crate) fn _rotate_key(origin: Option<Origin>, new_key_account: AccountId) -> DispatchResult {
+    SessionKeys::rotate_key(
+        origin.unwrap_or_else(|| Origin::signed(ACCOUNT_MAIN)),
+        ACCOUNT_PROXY,
+        new_key_account,
     )
-}
\ No newline at end of file
+}
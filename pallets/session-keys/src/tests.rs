@@ -1,6 +1,10 @@
-use crate::{Error, mock::*};
+use crate::{Error, mock::*, CallPermission, SessionKeyPermissions, SessionKeysByExpireBlock};
 
-use frame_support::{assert_ok, assert_noop, assert_err};
+use frame_support::{
+    assert_ok, assert_noop, assert_err,
+    storage::StorageMap,
+    traits::OnFinalize,
+};
 use pallet_balances::Error as BalancesError;
 
 #[test]
@@ -202,4 +206,248 @@ fn proxy_should_fail_with_session_key_limit_reached() {
         assert_ok!(_default_proxy());
         assert_noop!(_default_proxy(), Error::<Test>::SessionKeyLimitReached);
     });
-}
\ No newline at end of file
+}
+#[test]
+fn add_key_should_fail_with_empty_permissions_list() {
+    ExtBuilder::build_with_balance().execute_with(|| {
+        assert_noop!(
+            _add_key_with_permissions(SessionKeyPermissions {
+                allowed_calls: Some(vec![]),
+                allowed_spaces: None,
+            }),
+            Error::<Test>::EmptyPermissionsList
+        );
+        assert_noop!(
+            _add_key_with_permissions(SessionKeyPermissions {
+                allowed_calls: None,
+                allowed_spaces: Some(vec![]),
+            }),
+            Error::<Test>::EmptyPermissionsList
+        );
+    });
+}
+
+#[test]
+fn proxy_should_work_with_allowed_pallet_and_call() {
+    ExtBuilder::build_with_balance().execute_with(|| {
+        assert_ok!(_add_key_with_permissions(SessionKeyPermissions {
+            allowed_calls: Some(vec![
+                CallPermission::AnyCallOf(b"Balances".to_vec()),
+                CallPermission::Call(b"ProfileFollows".to_vec(), b"follow_account".to_vec()),
+            ]),
+            allowed_spaces: None,
+        }));
+
+        assert_ok!(_default_proxy());
+        assert!(SessionKeys::key_details(ACCOUNT_PROXY).unwrap().spent > 0);
+    });
+}
+
+#[test]
+fn proxy_should_fail_when_call_is_not_allowed_for_key() {
+    ExtBuilder::build_with_balance().execute_with(|| {
+        assert_ok!(_add_key_with_permissions(SessionKeyPermissions {
+            allowed_calls: Some(vec![
+                CallPermission::AnyCallOf(b"Balances".to_vec()),
+                CallPermission::Call(b"ProfileFollows".to_vec(), b"unfollow_account".to_vec()),
+            ]),
+            allowed_spaces: None,
+        }));
+
+        assert_noop!(_default_proxy(), Error::<Test>::CallNotAllowedForSessionKey);
+    });
+}
+
+#[test]
+fn proxy_should_work_in_allowed_space() {
+    ExtBuilder::build_with_balance().execute_with(|| {
+        assert_ok!(_add_key_with_permissions(SessionKeyPermissions {
+            allowed_calls: None,
+            allowed_spaces: Some(vec![SPACE1]),
+        }));
+
+        assert_ok!(_default_proxy());
+    });
+}
+
+#[test]
+fn proxy_should_fail_when_space_is_not_allowed_for_key() {
+    ExtBuilder::build_with_balance().execute_with(|| {
+        assert_ok!(_add_key_with_permissions(SessionKeyPermissions {
+            allowed_calls: None,
+            allowed_spaces: Some(vec![SPACE2]),
+        }));
+
+        assert_noop!(_default_proxy(), Error::<Test>::SpaceNotAllowedForSessionKey);
+    });
+}
+
+//---------------------------------------------------------------------------------------------
+
+#[test]
+fn extend_key_should_work() {
+    ExtBuilder::build_with_balance().execute_with(|| {
+        assert_ok!(_add_default_key());
+        let old_expiration_block = SessionKeys::key_details(ACCOUNT_PROXY).unwrap().expires_at;
+
+        assert_ok!(_extend_default_key(BLOCKS_TO_LIVE));
+
+        let new_expiration_block = old_expiration_block + BLOCKS_TO_LIVE;
+        assert_eq!(SessionKeys::key_details(ACCOUNT_PROXY).unwrap().expires_at, new_expiration_block);
+        assert!(!SessionKeysByExpireBlock::<Test>::contains_key(old_expiration_block));
+        assert_eq!(
+            SessionKeysByExpireBlock::<Test>::get(new_expiration_block),
+            vec![(ACCOUNT_MAIN, ACCOUNT_PROXY)]
+        );
+
+        // The key should not be removed at the old expiration block
+        SessionKeys::on_finalize(old_expiration_block);
+        assert!(SessionKeys::key_details(ACCOUNT_PROXY).is_some());
+
+        SessionKeys::on_finalize(new_expiration_block);
+        assert!(SessionKeys::key_details(ACCOUNT_PROXY).is_none());
+    });
+}
+
+#[test]
+fn extend_key_should_fail_with_not_session_key_owner() {
+    ExtBuilder::build_with_balance().execute_with(|| {
+        assert_ok!(_add_default_key());
+        assert_noop!(
+            SessionKeys::extend_key(Origin::signed(ACCOUNT3), ACCOUNT_PROXY, BLOCKS_TO_LIVE),
+            Error::<Test>::NotASessionKeyOwner
+        );
+    });
+}
+
+#[test]
+fn extend_key_should_fail_with_session_key_expired() {
+    ExtBuilder::build_with_balance().execute_with(|| {
+        assert_ok!(_add_default_key());
+        System::set_block_number(BLOCKS_TO_LIVE + 1);
+
+        assert_noop!(_extend_default_key(BLOCKS_TO_LIVE), Error::<Test>::SessionKeyExpired);
+    });
+}
+
+#[test]
+fn rotate_key_should_work() {
+    ExtBuilder::build_with_balance().execute_with(|| {
+        assert_ok!(_add_default_key());
+        assert_ok!(_default_proxy());
+
+        let old_details = SessionKeys::key_details(ACCOUNT_PROXY).unwrap();
+        let old_key_balance = Balances::free_balance(ACCOUNT_PROXY);
+
+        assert_ok!(_rotate_key(None, ACCOUNT3));
+
+        assert!(SessionKeys::key_details(ACCOUNT_PROXY).is_none());
+        assert_eq!(Balances::free_balance(ACCOUNT_PROXY), 0);
+        assert_eq!(Balances::free_balance(ACCOUNT3), old_key_balance);
+
+        let new_details = SessionKeys::key_details(ACCOUNT3).unwrap();
+        assert_eq!(new_details.expires_at, old_details.expires_at);
+        assert_eq!(new_details.limit, old_details.limit);
+        assert_eq!(new_details.spent, old_details.spent);
+        assert_eq!(new_details.permissions, old_details.permissions);
+
+        assert_eq!(SessionKeys::keys_by_owner(ACCOUNT_MAIN), vec![ACCOUNT3]);
+        assert_eq!(
+            SessionKeysByExpireBlock::<Test>::get(old_details.expires_at),
+            vec![(ACCOUNT_MAIN, ACCOUNT3)]
+        );
+    });
+}
+
+#[test]
+fn rotate_key_should_fail_with_session_key_already_added() {
+    ExtBuilder::build_with_balance().execute_with(|| {
+        assert_ok!(_add_default_key());
+        assert_ok!(_add_key(None, Some(ACCOUNT3), None, None));
+
+        assert_noop!(_rotate_key(None, ACCOUNT3), Error::<Test>::SessionKeyAlreadyAdded);
+    });
+}
+
+#[test]
+fn rotate_key_should_fail_with_not_session_key_owner() {
+    ExtBuilder::build_with_balance().execute_with(|| {
+        assert_ok!(_add_default_key());
+
+        assert_noop!(
+            _rotate_key(Some(Origin::signed(ACCOUNT3)), ACCOUNT4),
+            Error::<Test>::NotASessionKeyOwner
+        );
+    });
+}
+
+//---------------------------------------------------------------------------------------------
+
+#[test]
+fn proxy_should_count_tokens_moved_by_call_as_spent() {
+    ExtBuilder::build_with_balance().execute_with(|| {
+        assert_ok!(_add_key(None, None, None, Some(None)));
+        let amount = DOLLARS;
+
+        let call_fees = SessionKeys::get_extrinsic_fees(Box::new(transfer_proxy_call(amount)));
+        assert_ok!(_proxy(None, Some(transfer_proxy_call(amount))));
+
+        assert_eq!(Balances::free_balance(ACCOUNT3), amount);
+        assert_eq!(SessionKeys::key_details(ACCOUNT_PROXY).unwrap().spent, amount + call_fees);
+    });
+}
+
+#[test]
+fn proxy_should_fail_and_revert_call_when_moved_tokens_exceed_limit() {
+    ExtBuilder::build_with_balance().execute_with(|| {
+        let amount = DOLLARS;
+        let call_fees = SessionKeys::get_extrinsic_fees(Box::new(transfer_proxy_call(amount)));
+
+        // The limit is enough to pay fees, but not to transfer the amount
+        assert_ok!(_add_key(None, None, None, Some(Some(call_fees + amount - 1))));
+
+        assert_noop!(
+            _proxy(None, Some(transfer_proxy_call(amount))),
+            Error::<Test>::SessionKeyLimitReached
+        );
+        assert_eq!(Balances::free_balance(ACCOUNT3), 0);
+    });
+}
+
+#[test]
+fn top_up_key_should_work() {
+    ExtBuilder::build_with_balance().execute_with(|| {
+        let fees_expected: Balance = SessionKeys::get_extrinsic_fees(Box::new(follow_account_proxy_call()));
+        assert_ok!(_add_key(None, None, None, Some(Some(fees_expected))));
+        assert_ok!(_default_proxy());
+        assert_noop!(_default_proxy(), Error::<Test>::SessionKeyLimitReached);
+
+        assert_ok!(_top_up_default_key(fees_expected));
+        assert_eq!(SessionKeys::key_details(ACCOUNT_PROXY).unwrap().limit, Some(fees_expected * 2));
+
+        // Unfollow to be able to follow the same account again
+        assert_ok!(ProfileFollows::unfollow_account(Origin::signed(ACCOUNT_MAIN), ACCOUNT_PROXY));
+        assert_ok!(_default_proxy());
+    });
+}
+
+#[test]
+fn top_up_key_should_fail_when_key_has_no_limit() {
+    ExtBuilder::build_with_balance().execute_with(|| {
+        assert_ok!(_add_key(None, None, None, Some(None)));
+
+        assert_noop!(_top_up_default_key(DOLLARS), Error::<Test>::SessionKeyHasNoLimit);
+    });
+}
+
+#[test]
+fn top_up_key_should_fail_with_not_session_key_owner() {
+    ExtBuilder::build_with_balance().execute_with(|| {
+        assert_ok!(_add_default_key());
+
+        assert_noop!(
+            SessionKeys::top_up_key(Origin::signed(ACCOUNT3), ACCOUNT_PROXY, DOLLARS),
+            Error::<Test>::NotASessionKeyOwner
+        );
+    });
+}
@@ -9,7 +9,7 @@ use sp_std::prelude::*;
 use frame_system::{self as system, ensure_signed};
 
 use df_traits::{
-    SpaceFollowsProvider,
+    SpaceFollowsProvider, SpaceCleaner,
     moderation::IsAccountBlocked,
 };
 use pallet_profiles::{Module as Profiles, SocialAccountById};
@@ -152,6 +152,37 @@ impl<T: Trait> SpaceFollowsProvider for Module<T> {
     }
 }
 
+impl<T: Trait> SpaceCleaner for Module<T> {
+    fn clean_up_space(space_id: SpaceId, limit: u32) -> u32 {
+        let mut removed = 0u32;
+        let mut followers = Self::space_followers(space_id);
+
+        while removed < limit {
+            let follower = match followers.pop() {
+                Some(follower) => follower,
+                None => break,
+            };
+
+            if let Some(mut social_account) = Profiles::social_account_by_id(follower.clone()) {
+                social_account.dec_following_spaces();
+                <SocialAccountById<T>>::insert(follower.clone(), social_account);
+            }
+
+            <SpacesFollowedByAccount<T>>::mutate(follower.clone(), |space_ids| remove_from_vec(space_ids, space_id));
+            <SpaceFollowedByAccount<T>>::remove((follower, space_id));
+            removed += 1;
+        }
+
+        if followers.is_empty() {
+            <SpaceFollowers<T>>::remove(space_id);
+        } else {
+            <SpaceFollowers<T>>::insert(space_id, followers);
+        }
+
+        removed
+    }
+}
+
 impl<T: Trait> BeforeSpaceCreated<T> for Module<T> {
     fn before_space_created(creator: T::AccountId, space: &mut Space<T>) -> DispatchResult {
         // Make a space creator the first follower of this space:
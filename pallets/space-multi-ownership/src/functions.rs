@@ -1,51 +1,81 @@
 use super::*;
 
 use sp_std::collections::btree_set::BTreeSet;
-use frame_support::{dispatch::DispatchResult};
+use sp_runtime::{DispatchError, traits::AccountIdConversion};
 
 impl<T: Trait> Module<T> {
 
-  pub fn update_space_owners(who: T::AccountId, mut space_owners: SpaceOwners<T>, change: Change<T>) -> DispatchResult {
-    let space_id = space_owners.space_id;
-    let change_id = change.id;
+  /// The keyless account that owns a space after it was converted to multi-ownership.
+  /// Approved changes of a space are dispatched with the signed origin of this account.
+  pub fn space_account_id(space_id: SpaceId) -> T::AccountId {
+    T::ModuleId::get().into_sub_account(space_id)
+  }
 
-    ensure!(change.confirmed_by.len() >= space_owners.threshold as usize, Error::<T>::NotEnoughConfirms);
-    Self::move_change_from_pending_state_to_executed(space_id, change_id)?;
+  /// Get a change that is related to a given space and is not executed yet.
+  pub fn require_pending_change(space_id: SpaceId, change_id: ChangeId) -> Result<Change<T>, DispatchError> {
+    let change = Self::change_by_id(change_id).ok_or(Error::<T>::ChangeNotFound)?;
+    ensure!(change.space_id == space_id, Error::<T>::ChangeNotRelatedToSpace);
+    ensure!(Self::pending_change_ids().contains(&change_id), Error::<T>::ChangeAlreadyExecuted);
+    Ok(change)
+  }
 
-    space_owners.changes_count = space_owners.changes_count.checked_add(1).ok_or(Error::<T>::ChangesCountOverflow)?;
-    if !change.add_owners.is_empty() || !change.remove_owners.is_empty() {
-      space_owners.owners = Self::transform_new_owners_to_vec(
-        space_owners.owners.clone(), change.add_owners.clone(), change.remove_owners.clone());
+  /// Execute a change that got enough confirmations right away
+  /// or start its timelock if the space has an execution delay.
+  pub fn approve_change(space_owners: SpaceOwners<T>, mut change: Change<T>) {
+    if space_owners.execution_delay.is_zero() {
+      Self::do_execute_change(space_owners, change);
+      return;
     }
 
-    if let Some(threshold) = change.new_threshold {
-      space_owners.threshold = threshold;
-    }
+    let executable_at = <system::Module<T>>::block_number() + space_owners.execution_delay;
+    change.executable_at = Some(executable_at);
+    // Owners get the same time to execute an approved change as they had to approve it.
+    change.expires_at = executable_at + T::BlocksToLive::get();
 
-    for account in &change.add_owners {
-      <SpaceIdsOwnedByAccountId<T>>::mutate(account, |ids| ids.insert(space_id));
-    }
-    for account in &change.remove_owners {
-      <SpaceIdsOwnedByAccountId<T>>::mutate(account, |ids| ids.remove(&space_id));
-    }
+    Self::deposit_event(RawEvent::ChangeApproved(change.space_id, change.id, executable_at));
+    <ChangeById<T>>::insert(change.id, change);
+  }
+
+  /// Dispatch a change on behalf of the space account.
+  /// The change is marked as executed whatever the result of the call is.
+  pub fn do_execute_change(mut space_owners: SpaceOwners<T>, change: Change<T>) {
+    let space_id = change.space_id;
+    let change_id = change.id;
+
+    Self::remove_pending_change(space_id, change_id);
+    ExecutedChangeIdsBySpaceId::mutate(space_id, |ids| ids.push(change_id));
+    <ChangeById<T>>::insert(change_id, &change);
 
+    // Space owners are saved before the call is dispatched, because the call can update them.
+    space_owners.changes_count = space_owners.changes_count.saturating_add(1);
     <SpaceOwnersBySpaceById<T>>::insert(space_id, space_owners);
-    <ChangeById<T>>::insert(change_id, change);
-    Self::deposit_event(RawEvent::SpaceOwnersUpdated(who, space_id, change_id));
 
-    Ok(())
-  }
+    let origin: T::Origin = system::RawOrigin::Signed(Self::space_account_id(space_id)).into();
+    let result = change.call.dispatch(origin).map(|_| ()).map_err(|e| e.error);
 
-  pub fn move_change_from_pending_state_to_executed(space_id: SpaceId, change_id: ChangeId) -> DispatchResult {
-    ensure!(Self::space_owners_by_space_id(space_id).is_some(), Error::<T>::SpaceOwnersNotFound);
-    ensure!(Self::change_by_id(change_id).is_some(), Error::<T>::ChangeNotFound);
-    ensure!(!Self::executed_change_ids_by_space_id(space_id).iter().any(|&x| x == change_id), Error::<T>::ChangeAlreadyExecuted);
+    Self::deposit_event(RawEvent::ChangeExecuted(space_id, change_id, result));
+  }
 
-    PendingChangeIdBySpaceId::remove(&space_id);
+  pub fn remove_pending_change(space_id: SpaceId, change_id: ChangeId) {
+    PendingChangeIdsBySpaceId::mutate(space_id, |ids| ids.remove(&change_id));
     PendingChangeIds::mutate(|set| set.remove(&change_id));
-    ExecutedChangeIdsBySpaceId::mutate(space_id, |ids| ids.push(change_id));
+  }
+
+  /// Remove space owners with their pending changes,
+  /// e.g. when a space is deleted or no longer owned by its space account.
+  pub fn remove_space_owners(space_owners: SpaceOwners<T>) {
+    let space_id = space_owners.space_id;
+
+    for owner in space_owners.owners.iter() {
+      <SpaceIdsOwnedByAccountId<T>>::mutate(owner, |ids| ids.remove(&space_id));
+    }
+
+    for change_id in PendingChangeIdsBySpaceId::take(space_id) {
+      <ChangeById<T>>::remove(change_id);
+      PendingChangeIds::mutate(|set| set.remove(&change_id));
+    }
 
-    Ok(())
+    <SpaceOwnersBySpaceById<T>>::remove(space_id);
   }
 
   pub fn transform_new_owners_to_vec(current_owners: Vec<T::AccountId>, add_owners: Vec<T::AccountId>, remove_owners: Vec<T::AccountId>) -> Vec<T::AccountId> {
@@ -69,9 +99,8 @@ impl<T: Trait> Module<T> {
       for change_id in Self::pending_change_ids() {
         if let Some(change) = Self::change_by_id(change_id) {
           if block_number >= change.expires_at {
-            PendingChangeIdBySpaceId::remove(&change.space_id);
             <ChangeById<T>>::remove(&change_id);
-            PendingChangeIds::mutate(|set| set.remove(&change_id));
+            Self::remove_pending_change(change.space_id, change_id);
           }
         }
       }
@@ -1,16 +1,32 @@
+//! # Space Multi-Ownership Module
+//!
+//! A space can be converted to multi-ownership: from then on it is owned by a keyless
+//! space account derived from the space id, and its owners can only act on behalf of
+//! this account by proposing a change (any call, e.g. `Spaces::update_space`,
+//! `Roles::grant_role` or `Posts::update_post`) and collecting `threshold` confirmations for it.
+//!
+//! An approved change is dispatched with the signed origin of the space account,
+//! right away or after an execution delay (timelock) configured for a space.
+
 #![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::boxed_local)]
 
 use codec::{Decode, Encode};
 use sp_std::prelude::*;
 use sp_std::collections::{btree_map::BTreeMap, btree_set::BTreeSet};
-use sp_runtime::{RuntimeDebug, traits::Zero};
+use sp_runtime::{RuntimeDebug, ModuleId, traits::{Zero, Dispatchable}};
 
 use frame_support::{
   decl_error, decl_event, decl_module, decl_storage, ensure,
-  traits::Get
+  dispatch::{DispatchResult, PostDispatchInfo},
+  traits::{Get, IsType},
+  weights::{GetDispatchInfo, Weight},
+  Parameter,
 };
 use frame_system::{self as system, ensure_signed};
 
+use df_traits::SpaceCleaner;
+use pallet_spaces::Module as Spaces;
 use pallet_utils::{SpaceId, WhoAndWhen};
 
 pub mod functions;
@@ -25,31 +41,45 @@ pub struct SpaceOwners<T: Trait> {
   pub owners: Vec<T::AccountId>,
   pub threshold: u16,
   pub changes_count: u16,
+  /// How many blocks an approved change should wait before it can be executed.
+  pub execution_delay: T::BlockNumber,
 }
 
+/// A call that space owners propose to dispatch on behalf of the space account.
 #[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
 pub struct Change<T: Trait> {
   pub created: WhoAndWhen<T>,
   pub id: ChangeId,
   pub space_id: SpaceId,
-  pub add_owners: Vec<T::AccountId>,
-  pub remove_owners: Vec<T::AccountId>,
-  pub new_threshold: Option<u16>,
+  pub call: Box<<T as Trait>::Call>,
   pub notes: Vec<u8>,
   pub confirmed_by: Vec<T::AccountId>,
   pub expires_at: T::BlockNumber,
+  /// A block since which the change can be executed.
+  /// `None` until the change gets `threshold` confirmations.
+  pub executable_at: Option<T::BlockNumber>,
 }
 
-type ChangeId = u64;
+pub type ChangeId = u64;
 
 /// The pallet's configuration trait.
 pub trait Trait: system::Trait
   + pallet_timestamp::Trait
   + pallet_utils::Trait
+  + pallet_spaces::Trait
 {
   /// The overarching event type.
   type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
 
+  /// The overarching call type.
+  type Call: Parameter
+    + Dispatchable<Origin=Self::Origin, PostInfo=PostDispatchInfo>
+    + GetDispatchInfo + From<frame_system::Call<Self>>
+    + IsType<<Self as frame_system::Trait>::Call>;
+
+  /// The id used to derive accounts of multi-owned spaces.
+  type ModuleId: Get<ModuleId>;
+
   /// Minimum space owners allowed.
   type MinSpaceOwners: Get<u16>;
 
@@ -81,11 +111,15 @@ decl_error! {
     TooManyOwners,
     /// Account is not a space owner
     NotASpaceOwner,
+    /// Only the space account can update space owners
+    NotASpaceAccount,
 
     /// The threshold can not be less than 1
     ZeroThershold,
     /// The required confirmation count can not be greater than owners count"
     TooBigThreshold,
+    /// Execution delay can not be longer than the time to live of a change
+    TooLongExecutionDelay,
     /// Change notes are too long
     ChangeNotesOversize,
     /// No space owners will left in result of change
@@ -99,20 +133,19 @@ decl_error! {
     ChangeAlreadyConfirmed,
     /// There are not enough confirmations for this change
     NotEnoughConfirms,
+    /// Change has already got enough confirmations and waits for execution
+    ChangeAlreadyApproved,
+    /// Execution delay of this change has not passed yet
+    ChangeIsTimelocked,
     /// Change is already executed
     ChangeAlreadyExecuted,
     /// Change is not related to this space
     ChangeNotRelatedToSpace,
-    /// Pending change already exists
-    PendingChangeAlreadyExists,
-    /// Pending change doesn't exist
-    PendingChangeDoesNotExist,
+    /// The weight of a proposed call is greater than the provided max weight
+    MaxWeightTooLow,
 
     /// Account is not a proposal creator
     NotAChangeCreator,
-
-    /// Overflow when incrementing a counter of executed changes
-    ChangesCountOverflow,
   }
 }
 
@@ -130,8 +163,8 @@ decl_storage! {
     ChangeById get(fn change_by_id):
       map hasher(twox_64_concat) ChangeId => Option<Change<T>>;
 
-    PendingChangeIdBySpaceId get(fn pending_change_id_by_space_id):
-      map hasher(twox_64_concat) SpaceId => Option<ChangeId>;
+    PendingChangeIdsBySpaceId get(fn pending_change_ids_by_space_id):
+      map hasher(twox_64_concat) SpaceId => BTreeSet<ChangeId> = BTreeSet::new();
 
     PendingChangeIds get(fn pending_change_ids): BTreeSet<ChangeId> = BTreeSet::new();
 
@@ -158,6 +191,9 @@ decl_module! {
     /// Period in blocks to initialize deleting of pending changes that are outdated.
     const DeleteExpiredChangesPeriod: T::BlockNumber = T::DeleteExpiredChangesPeriod::get();
 
+    /// The id used to derive accounts of multi-owned spaces.
+    const ModuleId: ModuleId = T::ModuleId::get();
+
     // Initializing events
     fn deposit_event() = default;
 
@@ -165,16 +201,28 @@ decl_module! {
       Self::delete_expired_changes(n);
     }
 
-    #[weight = T::DbWeight::get().reads_writes(2, 2) + 10_000]
+    /// Convert a space owned by the caller to multi-ownership.
+    /// The space is handed over to its space account along with a handle deposit (if any).
+    #[weight = T::DbWeight::get().reads_writes(6, 6) + 10_000]
     pub fn create_space_owners(
       origin,
       space_id: SpaceId,
       owners: Vec<T::AccountId>,
-      threshold: u16
+      threshold: u16,
+      execution_delay: T::BlockNumber
     ) {
       let who = ensure_signed(origin)?;
 
-      ensure!(Self::space_owners_by_space_id(space_id).is_none(), Error::<T>::SpaceOwnersAlreadyExist);
+      let space = Spaces::<T>::require_space(space_id)?;
+      let space_account = Self::space_account_id(space_id);
+
+      let old_space_owners = Self::space_owners_by_space_id(space_id);
+      if old_space_owners.is_some() {
+        // Space owners can be left over only if the space was transferred from its space account.
+        ensure!(space.owner != space_account, Error::<T>::SpaceOwnersAlreadyExist);
+      }
+
+      space.ensure_space_owner(who.clone())?;
 
       let mut owners_map: BTreeMap<T::AccountId, bool> = BTreeMap::new();
       let mut unique_owners: Vec<T::AccountId> = Vec::new();
@@ -192,13 +240,21 @@ decl_module! {
 
       ensure!(threshold <= owners_count, Error::<T>::TooBigThreshold);
       ensure!(threshold > 0, Error::<T>::ZeroThershold);
+      ensure!(execution_delay <= T::BlocksToLive::get(), Error::<T>::TooLongExecutionDelay);
+
+      Spaces::<T>::transfer_space_to_new_owner(space, &space_account)?;
+
+      if let Some(space_owners) = old_space_owners {
+        Self::remove_space_owners(space_owners);
+      }
 
       let new_space_owners = SpaceOwners {
         created: WhoAndWhen::<T>::new(who.clone()),
         space_id: space_id,
         owners: unique_owners.clone(),
         threshold,
-        changes_count: 0
+        changes_count: 0,
+        execution_delay,
       };
 
       <SpaceOwnersBySpaceById<T>>::insert(space_id, new_space_owners);
@@ -210,78 +266,121 @@ decl_module! {
       Self::deposit_event(RawEvent::SpaceOwnersCreated(who, space_id));
     }
 
-    #[weight = T::DbWeight::get().reads_writes(5, 4) + 10_000]
-    pub fn propose_change(
+    /// Update owners, threshold or execution delay of a multi-owned space.
+    /// Can only be dispatched by the space account, i.e. as a confirmed change.
+    #[weight = T::DbWeight::get().reads_writes(1, 1 + (add_owners.len() + remove_owners.len()) as Weight) + 10_000]
+    pub fn update_space_owners(
       origin,
       space_id: SpaceId,
       add_owners: Vec<T::AccountId>,
       remove_owners: Vec<T::AccountId>,
       new_threshold: Option<u16>,
-      notes: Vec<u8>
+      new_execution_delay: Option<T::BlockNumber>
     ) {
       let who = ensure_signed(origin)?;
+      ensure!(who == Self::space_account_id(space_id), Error::<T>::NotASpaceAccount);
 
       let has_updates =
         !add_owners.is_empty() ||
         !remove_owners.is_empty() ||
-        new_threshold.is_some();
+        new_threshold.is_some() ||
+        new_execution_delay.is_some();
 
       ensure!(has_updates, Error::<T>::NoUpdatesProposed);
-      ensure!(notes.len() <= T::MaxChangeNotesLength::get() as usize, Error::<T>::ChangeNotesOversize);
 
-      let space_owners = Self::space_owners_by_space_id(space_id).ok_or(Error::<T>::SpaceOwnersNotFound)?;
-      ensure!(Self::pending_change_id_by_space_id(space_id).is_none(), Error::<T>::PendingChangeAlreadyExists);
-
-      let is_space_owner = space_owners.owners.iter().any(|owner| *owner == who.clone());
-      ensure!(is_space_owner, Error::<T>::NotASpaceOwner);
+      let mut space_owners = Self::space_owners_by_space_id(space_id).ok_or(Error::<T>::SpaceOwnersNotFound)?;
 
       let mut fields_updated : u16 = 0;
 
-      let result_owners = Self::transform_new_owners_to_vec(space_owners.owners.clone(), add_owners.clone(), remove_owners.clone());
+      let result_owners = Self::transform_new_owners_to_vec(space_owners.owners.clone(), add_owners, remove_owners);
       ensure!(!result_owners.is_empty(), Error::<T>::NoSpaceOwnersLeft);
+      ensure!(result_owners.len() >= T::MinSpaceOwners::get() as usize, Error::<T>::NotEnoughOwners);
+      ensure!(result_owners.len() <= T::MaxSpaceOwners::get() as usize, Error::<T>::TooManyOwners);
       if result_owners != space_owners.owners {
         fields_updated += 1;
       }
 
-      if let Some(threshold) = new_threshold {
-        if space_owners.threshold != threshold {
-          ensure!(threshold as usize <= result_owners.len(), Error::<T>::TooBigThreshold);
-          ensure!(threshold > 0, Error::<T>::ZeroThershold);
+      let threshold = new_threshold.unwrap_or(space_owners.threshold);
+      if threshold != space_owners.threshold {
+        ensure!(threshold > 0, Error::<T>::ZeroThershold);
+        fields_updated += 1;
+      }
+      ensure!(threshold as usize <= result_owners.len(), Error::<T>::TooBigThreshold);
+
+      if let Some(execution_delay) = new_execution_delay {
+        if execution_delay != space_owners.execution_delay {
+          ensure!(execution_delay <= T::BlocksToLive::get(), Error::<T>::TooLongExecutionDelay);
+          space_owners.execution_delay = execution_delay;
           fields_updated += 1;
         }
       }
 
+      ensure!(fields_updated > 0, Error::<T>::NoFieldsUpdatedOnProposal);
+
+      for account in result_owners.iter().filter(|account| !space_owners.owners.contains(*account)) {
+        <SpaceIdsOwnedByAccountId<T>>::mutate(account, |ids| ids.insert(space_id));
+      }
+      for account in space_owners.owners.iter().filter(|account| !result_owners.contains(*account)) {
+        <SpaceIdsOwnedByAccountId<T>>::mutate(account, |ids| ids.remove(&space_id));
+      }
+
+      space_owners.owners = result_owners;
+      space_owners.threshold = threshold;
+      <SpaceOwnersBySpaceById<T>>::insert(space_id, space_owners);
+
+      Self::deposit_event(RawEvent::SpaceOwnersUpdated(space_id));
+    }
+
+    /// Propose a call to be dispatched on behalf of the space account.
+    #[weight = T::DbWeight::get().reads_writes(3, 6) + 10_000 + call.get_dispatch_info().weight]
+    pub fn propose_change(
+      origin,
+      space_id: SpaceId,
+      call: Box<<T as Trait>::Call>,
+      notes: Vec<u8>
+    ) {
+      let who = ensure_signed(origin)?;
+
+      ensure!(notes.len() <= T::MaxChangeNotesLength::get() as usize, Error::<T>::ChangeNotesOversize);
+
+      let space_owners = Self::space_owners_by_space_id(space_id).ok_or(Error::<T>::SpaceOwnersNotFound)?;
+
+      let is_space_owner = space_owners.owners.iter().any(|owner| *owner == who.clone());
+      ensure!(is_space_owner, Error::<T>::NotASpaceOwner);
+
       let change_id = Self::next_change_id();
-      let mut new_change = Change {
+      let new_change = Change {
         created: WhoAndWhen::<T>::new(who.clone()),
         id: change_id,
         space_id,
-        add_owners: add_owners,
-        remove_owners: remove_owners,
-        new_threshold: new_threshold,
+        call,
         notes,
-        confirmed_by: Vec::new(),
-        expires_at: <system::Module<T>>::block_number() + T::BlocksToLive::get()
+        confirmed_by: vec![who.clone()],
+        expires_at: <system::Module<T>>::block_number() + T::BlocksToLive::get(),
+        executable_at: None,
       };
 
-      if fields_updated > 0 {
-        new_change.confirmed_by.push(who.clone());
-        <ChangeById<T>>::insert(change_id, new_change);
-        PendingChangeIdBySpaceId::insert(space_id, change_id);
-        PendingChangeIds::mutate(|set| set.insert(change_id));
-        NextChangeId::mutate(|n| { *n += 1; });
+      PendingChangeIdsBySpaceId::mutate(space_id, |ids| ids.insert(change_id));
+      PendingChangeIds::mutate(|set| set.insert(change_id));
+      NextChangeId::mutate(|n| { *n += 1; });
 
-        Self::deposit_event(RawEvent::ChangeProposed(who, space_id, change_id));
+      Self::deposit_event(RawEvent::ChangeProposed(who, space_id, change_id));
+
+      if new_change.confirmed_by.len() >= space_owners.threshold as usize {
+        Self::approve_change(space_owners, new_change);
       } else {
-        return Err(Error::<T>::NoFieldsUpdatedOnProposal.into());
+        <ChangeById<T>>::insert(change_id, new_change);
       }
     }
 
-    #[weight = T::DbWeight::get().reads_writes(3, 1) + 10_000]
+    /// Confirm a proposed change. `max_weight` should cover the weight of the proposed call
+    /// in case this confirmation is the last one required and the space has no execution delay.
+    #[weight = T::DbWeight::get().reads_writes(3, 5) + 10_000 + *max_weight]
     pub fn confirm_change(
       origin,
       space_id: SpaceId,
-      change_id: ChangeId
+      change_id: ChangeId,
+      max_weight: Weight
     ) {
       let who = ensure_signed(origin)?;
 
@@ -290,26 +389,57 @@ decl_module! {
       let is_space_owner = space_owners.owners.iter().any(|owner| *owner == who.clone());
       ensure!(is_space_owner, Error::<T>::NotASpaceOwner);
 
-      let mut change = Self::change_by_id(change_id).ok_or(Error::<T>::ChangeNotFound)?;
-
-      let pending_change_id = Self::pending_change_id_by_space_id(space_id).ok_or(Error::<T>::PendingChangeDoesNotExist)?;
-      ensure!(pending_change_id == change_id, Error::<T>::ChangeNotRelatedToSpace);
+      let mut change = Self::require_pending_change(space_id, change_id)?;
+      ensure!(change.executable_at.is_none(), Error::<T>::ChangeAlreadyApproved);
 
       // Check whether sender confirmed change or not
       ensure!(!change.confirmed_by.iter().any(|account| *account == who.clone()), Error::<T>::ChangeAlreadyConfirmed);
 
+      // Confirmations of accounts that are no longer space owners do not count.
+      change.confirmed_by.retain(|account| space_owners.owners.contains(account));
       change.confirmed_by.push(who.clone());
 
-      if change.confirmed_by.len() == space_owners.threshold as usize {
-        Self::update_space_owners(who.clone(), space_owners, change)?;
+      let is_approved = change.confirmed_by.len() >= space_owners.threshold as usize;
+      if is_approved && space_owners.execution_delay.is_zero() {
+        ensure!(change.call.get_dispatch_info().weight <= max_weight, Error::<T>::MaxWeightTooLow);
+      }
+
+      Self::deposit_event(RawEvent::ChangeConfirmed(who, space_id, change_id));
+
+      if is_approved {
+        Self::approve_change(space_owners, change);
       } else {
         <ChangeById<T>>::insert(change_id, change);
       }
+    }
 
-      Self::deposit_event(RawEvent::ChangeConfirmed(who, space_id, change_id));
+    /// Execute an approved change once its execution delay has passed.
+    #[weight = T::DbWeight::get().reads_writes(3, 5) + 10_000 + *max_weight]
+    pub fn execute_change(
+      origin,
+      space_id: SpaceId,
+      change_id: ChangeId,
+      max_weight: Weight
+    ) {
+      let who = ensure_signed(origin)?;
+
+      let space_owners = Self::space_owners_by_space_id(space_id).ok_or(Error::<T>::SpaceOwnersNotFound)?;
+
+      let is_space_owner = space_owners.owners.iter().any(|owner| *owner == who.clone());
+      ensure!(is_space_owner, Error::<T>::NotASpaceOwner);
+
+      let change = Self::require_pending_change(space_id, change_id)?;
+
+      let executable_at = change.executable_at.ok_or(Error::<T>::NotEnoughConfirms)?;
+      ensure!(<system::Module<T>>::block_number() >= executable_at, Error::<T>::ChangeIsTimelocked);
+      ensure!(change.call.get_dispatch_info().weight <= max_weight, Error::<T>::MaxWeightTooLow);
+
+      Self::do_execute_change(space_owners, change);
     }
 
-    #[weight = T::DbWeight::get().reads_writes(4, 3) + 10_000]
+    /// Cancel a pending change. It is possible until the change is executed,
+    /// so owners can still stop an approved change while it is timelocked.
+    #[weight = T::DbWeight::get().reads_writes(3, 3) + 10_000]
     pub fn cancel_change(
       origin,
       space_id: SpaceId,
@@ -322,17 +452,13 @@ decl_module! {
       let is_space_owner = space_owners.owners.iter().any(|owner| *owner == who.clone());
       ensure!(is_space_owner, Error::<T>::NotASpaceOwner);
 
-      let pending_change_id = Self::pending_change_id_by_space_id(space_id).ok_or(Error::<T>::PendingChangeDoesNotExist)?;
-      ensure!(pending_change_id == change_id, Error::<T>::ChangeNotRelatedToSpace);
-
-      let change = Self::change_by_id(change_id).ok_or(Error::<T>::ChangeNotFound)?;
+      let change = Self::require_pending_change(space_id, change_id)?;
       ensure!(change.created.account == who, Error::<T>::NotAChangeCreator);
 
       <ChangeById<T>>::remove(change_id);
-      PendingChangeIdBySpaceId::remove(space_id);
-      PendingChangeIds::mutate(|set| set.remove(&change_id));
+      Self::remove_pending_change(space_id, change_id);
 
-      Self::deposit_event(RawEvent::ProposalCanceled(who, space_id));
+      Self::deposit_event(RawEvent::ChangeCanceled(who, space_id, change_id));
     }
   }
 }
@@ -340,11 +466,32 @@ decl_module! {
 decl_event!(
   pub enum Event<T> where
     <T as system::Trait>::AccountId,
+    <T as system::Trait>::BlockNumber,
    {
     SpaceOwnersCreated(AccountId, SpaceId),
+    SpaceOwnersUpdated(SpaceId),
     ChangeProposed(AccountId, SpaceId, ChangeId),
-    ProposalCanceled(AccountId, SpaceId),
+    ChangeCanceled(AccountId, SpaceId, ChangeId),
     ChangeConfirmed(AccountId, SpaceId, ChangeId),
-    SpaceOwnersUpdated(AccountId, SpaceId, ChangeId),
+    /// A change got enough confirmations and can be executed since a given block.
+    ChangeApproved(SpaceId, ChangeId, BlockNumber),
+    /// A change was dispatched on behalf of the space account with a given result.
+    ChangeExecuted(SpaceId, ChangeId, DispatchResult),
   }
 );
+
+impl<T: Trait> SpaceCleaner for Module<T> {
+  fn clean_up_space(space_id: SpaceId, limit: u32) -> u32 {
+    if limit == 0 {
+      return 0;
+    }
+
+    match Self::space_owners_by_space_id(space_id) {
+      Some(space_owners) => {
+        Self::remove_space_owners(space_owners);
+        1
+      },
+      None => 0,
+    }
+  }
+}
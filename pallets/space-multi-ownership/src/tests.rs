@@ -2,15 +2,38 @@ use crate::*;
 
 use sp_core::H256;
 use sp_io::TestExternalities;
-use frame_support::{impl_outer_origin, assert_ok, assert_noop, parameter_types, weights::Weight, dispatch::DispatchResult};
+use frame_support::{
+  impl_outer_origin, impl_outer_dispatch, assert_ok, assert_noop, parameter_types,
+  weights::Weight,
+  dispatch::{DispatchError, DispatchResult},
+};
 use sp_runtime::{
   traits::{BlakeTwo256, IdentityLookup}, testing::Header, Perbill,
 };
 
+use df_traits::{PermissionChecker, SpaceFollowsProvider};
+use pallet_permissions::{
+  Module as Permissions, SpacePermission, SpacePermissionsContext,
+  default_permissions::DefaultSpacePermissions,
+};
+use pallet_spaces::{SpaceUpdate, Error as SpacesError};
+use pallet_space_ownership::Error as SpaceOwnershipError;
+use pallet_utils::{Content, User};
+
+use crate as space_multi_ownership;
+
 impl_outer_origin! {
   pub enum Origin for Test {}
 }
 
+impl_outer_dispatch! {
+  pub enum Call for Test where origin: Origin {
+    frame_system::System,
+    pallet_spaces::Spaces,
+    space_multi_ownership::MultiOwnership,
+  }
+}
+
 #[derive(Clone, Eq, PartialEq)]
 pub struct Test;
 
@@ -24,7 +47,7 @@ parameter_types! {
 impl system::Trait for Test {
   type BaseCallFilter = ();
   type Origin = Origin;
-  type Call = ();
+  type Call = Call;
   type Index = u64;
   type BlockNumber = u64;
   type Hash = H256;
@@ -86,16 +109,76 @@ impl pallet_utils::Trait for Test {
   type MaxHandleLen = MaxHandleLen;
 }
 
+impl pallet_permissions::Trait for Test {
+  type DefaultSpacePermissions = DefaultSpacePermissions;
+}
+
+parameter_types! {
+  pub const MaxItemsToCleanUpPerBlock: u16 = 100;
+}
+
+impl pallet_spaces::Trait for Test {
+  type Event = ();
+  type Currency = Balances;
+  type Roles = MultiOwnership;
+  type SpaceFollows = MultiOwnership;
+  type BeforeSpaceCreated = ();
+  type AfterSpaceUpdated = ();
+  type AfterSpaceOwnerChanged = SpaceOwnership;
+  type IsAccountBlocked = ();
+  type IsContentBlocked = ();
+  type HandleDeposit = ();
+  type SpaceCleaner = MultiOwnership;
+  type MaxItemsToCleanUpPerBlock = MaxItemsToCleanUpPerBlock;
+}
+
+// Only the default space permissions are checked here, there are no roles.
+impl<T: Trait> PermissionChecker for Module<T> {
+  type AccountId = AccountId;
+
+  fn ensure_user_has_space_permission(
+    _user: User<Self::AccountId>,
+    ctx: SpacePermissionsContext,
+    permission: SpacePermission,
+    error: DispatchError,
+  ) -> DispatchResult {
+    match Permissions::<Test>::has_user_a_space_permission(ctx, permission) {
+      Some(true) => Ok(()),
+      _ => Err(error),
+    }
+  }
+}
+
+impl<T: Trait> SpaceFollowsProvider for Module<T> {
+  type AccountId = AccountId;
+
+  fn is_space_follower(_account: Self::AccountId, _space_id: SpaceId) -> bool {
+    false
+  }
+}
+
+parameter_types! {
+  pub const PendingTransferLifetime: u64 = 10;
+}
+
+impl pallet_space_ownership::Trait for Test {
+  type Event = ();
+  type PendingTransferLifetime = PendingTransferLifetime;
+}
+
 parameter_types! {
 	pub const MinSpaceOwners: u16 = 1;
 	pub const MaxSpaceOwners: u16 = 1000;
 	pub const MaxChangeNotesLength: u16 = 1024;
 	pub const BlocksToLive: u64 = 302_400;
 	pub const DeleteExpiredChangesPeriod: u64 = 1800;
+	pub const MultiOwnershipModuleId: ModuleId = ModuleId(*b"df/spcmo");
 }
 
 impl Trait for Test {
   type Event = ();
+  type Call = Call;
+  type ModuleId = MultiOwnershipModuleId;
   type MinSpaceOwners = MinSpaceOwners;
   type MaxSpaceOwners = MaxSpaceOwners;
   type MaxChangeNotesLength = MaxChangeNotesLength;
@@ -104,6 +187,8 @@ impl Trait for Test {
 }
 
 type MultiOwnership = Module<Test>;
+type Spaces = pallet_spaces::Module<Test>;
+type SpaceOwnership = pallet_space_ownership::Module<Test>;
 type Balances = pallet_balances::Module<Test>;
 type System = system::Module<Test>;
 
@@ -111,14 +196,19 @@ pub struct ExtBuilder;
 
 impl ExtBuilder {
   
-  /// Default ext configuration with BlockNumber 1
+  /// Default ext configuration with BlockNumber 1,
+  /// SpaceId 1001 owned by ACCOUNT1 and SpaceId 1002 owned by ACCOUNT3
   pub fn build() -> TestExternalities {
     let storage = system::GenesisConfig::default()
         .build_storage::<Test>()
         .unwrap();
 
     let mut ext = TestExternalities::from(storage);
-    ext.execute_with(|| System::set_block_number(1));
+    ext.execute_with(|| {
+      System::set_block_number(1);
+      assert_ok!(_create_space(ACCOUNT1));
+      assert_ok!(_create_space(ACCOUNT3));
+    });
 
     ext
   }
@@ -241,18 +331,61 @@ impl ExtBuilder {
 }
 
 type AccountId = u64;
+type BlockNumber = u64;
 
 const ACCOUNT1: AccountId = 1;
 const ACCOUNT2: AccountId = 2;
 const ACCOUNT3: AccountId = 3;
 const ACCOUNT4: AccountId = 4;
 
+const SPACE1: SpaceId = 1001;
+const SPACE2: SpaceId = 1002;
+
+const MAX_WEIGHT: Weight = 1_000_000;
+
+fn _create_space(owner: AccountId) -> DispatchResult {
+  Spaces::create_space(Origin::signed(owner), None, None, Content::None, None)
+}
+
+fn space_update() -> SpaceUpdate {
+  SpaceUpdate {
+    parent_id: None,
+    handle: None,
+    content: None,
+    hidden: Some(true),
+    permissions: None,
+  }
+}
+
+fn update_space_call() -> Box<Call> {
+  Box::new(Call::Spaces(pallet_spaces::Call::update_space(SPACE1, self::space_update())))
+}
+
+fn update_space_owners_call(
+  space_id: SpaceId,
+  add_owners: Vec<AccountId>,
+  remove_owners: Vec<AccountId>,
+  new_threshold: Option<u16>,
+) -> Box<Call> {
+  Box::new(Call::MultiOwnership(crate::Call::update_space_owners(
+    space_id,
+    add_owners,
+    remove_owners,
+    new_threshold,
+    None,
+  )))
+}
+
+fn default_change_call() -> Box<Call> {
+  self::update_space_owners_call(SPACE1, vec![ACCOUNT3], vec![], Some(3))
+}
+
 fn change_note() -> Vec<u8> {
   b"Default change proposal".to_vec()
 }
 
 fn _create_default_space_owners() -> DispatchResult {
-  _create_space_owners(None, None, None, None)
+  _create_space_owners(None, None, None, None, None)
 }
 
 fn _create_space_owners(
@@ -260,62 +393,92 @@ fn _create_space_owners(
   space_id: Option<SpaceId>,
   owners: Option<Vec<AccountId>>,
   threshold: Option<u16>,
+  execution_delay: Option<BlockNumber>,
 ) -> DispatchResult {
   MultiOwnership::create_space_owners(
     origin.unwrap_or_else(|| Origin::signed(ACCOUNT1)),
-    space_id.unwrap_or(1),
+    space_id.unwrap_or(SPACE1),
     owners.unwrap_or_else(|| vec![ACCOUNT1, ACCOUNT2]),
     threshold.unwrap_or(2),
+    execution_delay.unwrap_or(0),
+  )
+}
+
+fn _update_space_owners(
+  origin: Option<Origin>,
+  add_owners: Vec<AccountId>,
+  remove_owners: Vec<AccountId>,
+  new_threshold: Option<u16>,
+  new_execution_delay: Option<BlockNumber>,
+) -> DispatchResult {
+  MultiOwnership::update_space_owners(
+    origin.unwrap_or_else(|| Origin::signed(MultiOwnership::space_account_id(SPACE1))),
+    SPACE1,
+    add_owners,
+    remove_owners,
+    new_threshold,
+    new_execution_delay,
   )
 }
 
 fn _propose_default_change() -> DispatchResult {
-  _propose_change(None, None, None, None, None, None)
+  _propose_change(None, None, None, None)
 }
 
 fn _propose_change_on_second_space() {
   assert_ok!(_propose_change(
-      Some(Origin::signed(ACCOUNT3)),
-      Some(2),
-      Some(vec![ACCOUNT1]),
-      Some(vec![]),
-      Some(Some(2)),
-      Some(self::change_note())
-    ));
+    Some(Origin::signed(ACCOUNT3)),
+    Some(SPACE2),
+    Some(self::update_space_owners_call(SPACE2, vec![ACCOUNT1], vec![], Some(2))),
+    None
+  ));
 }
 
-#[allow(clippy::option_option)]
 fn _propose_change(
   origin: Option<Origin>,
   space_id: Option<SpaceId>,
-  add_owners: Option<Vec<AccountId>>,
-  remove_owners: Option<Vec<AccountId>>,
-  new_threshold: Option<Option<u16>>,
+  call: Option<Box<Call>>,
   notes: Option<Vec<u8>>,
 ) -> DispatchResult {
   MultiOwnership::propose_change(
     origin.unwrap_or_else(|| Origin::signed(ACCOUNT1)),
-    space_id.unwrap_or(1),
-    add_owners.unwrap_or_else(|| vec![ACCOUNT3]),
-    remove_owners.unwrap_or_else(|| vec![]),
-    new_threshold.unwrap_or(Some(3)),
+    space_id.unwrap_or(SPACE1),
+    call.unwrap_or_else(self::default_change_call),
     notes.unwrap_or_else(self::change_note),
   )
 }
 
 fn _confirm_default_change() -> DispatchResult {
-  _confirm_change(None, None, None)
+  _confirm_change(None, None, None, None)
 }
 
 fn _confirm_change(
   origin: Option<Origin>,
   space_id: Option<SpaceId>,
   change_id: Option<ChangeId>,
+  max_weight: Option<Weight>,
 ) -> DispatchResult {
   MultiOwnership::confirm_change(
     origin.unwrap_or_else(|| Origin::signed(ACCOUNT2)),
-    space_id.unwrap_or(1),
+    space_id.unwrap_or(SPACE1),
     change_id.unwrap_or(1),
+    max_weight.unwrap_or(MAX_WEIGHT),
+  )
+}
+
+fn _execute_default_change() -> DispatchResult {
+  _execute_change(None, None)
+}
+
+fn _execute_change(
+  origin: Option<Origin>,
+  max_weight: Option<Weight>,
+) -> DispatchResult {
+  MultiOwnership::execute_change(
+    origin.unwrap_or_else(|| Origin::signed(ACCOUNT1)),
+    SPACE1,
+    1,
+    max_weight.unwrap_or(MAX_WEIGHT),
   )
 }
 
@@ -330,11 +493,17 @@ fn _cancel_change(
 ) -> DispatchResult {
   MultiOwnership::cancel_change(
     origin.unwrap_or_else(|| Origin::signed(ACCOUNT1)),
-    space_id.unwrap_or(1),
+    space_id.unwrap_or(SPACE1),
     change_id.unwrap_or(1),
   )
 }
 
+/// Space owners of SpaceId 1001 with a timelock of 10 blocks and a pending change to hide the space.
+fn _create_timelocked_space_owners_and_propose_update_space() {
+  assert_ok!(_create_space_owners(None, None, Some(vec![ACCOUNT1, ACCOUNT2, ACCOUNT3]), None, Some(10)));
+  assert_ok!(_propose_change(None, None, Some(self::update_space_call()), None));
+}
+
 #[test]
 fn create_space_owners_should_work() {
   ExtBuilder::build().execute_with(|| {
@@ -342,141 +511,205 @@ fn create_space_owners_should_work() {
 
     // Check storages
     let mut check: Vec<u64> = MultiOwnership::space_ids_owned_by_account_id(ACCOUNT1).iter().cloned().collect();
-    assert_eq!(check, vec![1]);
+    assert_eq!(check, vec![SPACE1]);
 
     check = MultiOwnership::space_ids_owned_by_account_id(ACCOUNT2).iter().cloned().collect();
-    assert_eq!(check, vec![1]);
+    assert_eq!(check, vec![SPACE1]);
 
     // Check whether data is stored correctly
-    let space_owners = MultiOwnership::space_owners_by_space_id(1).unwrap();
+    let space_owners = MultiOwnership::space_owners_by_space_id(SPACE1).unwrap();
     assert_eq!(space_owners.owners, vec![ACCOUNT1, ACCOUNT2]);
-    assert_eq!(space_owners.space_id, 1);
+    assert_eq!(space_owners.space_id, SPACE1);
     assert_eq!(space_owners.threshold, 2);
     assert_eq!(space_owners.changes_count, 0);
+    assert_eq!(space_owners.execution_delay, 0);
   });
 }
 
-// -------
-
 #[test]
-fn propose_change_should_work() {
+fn create_space_owners_should_transfer_space_to_space_account() {
   ExtBuilder::build().execute_with(|| {
     assert_ok!(_create_default_space_owners());
-    assert_ok!(_propose_default_change());
-
-    // Check storages
-    let set_to_vec: Vec<u64> = MultiOwnership::pending_change_ids().iter().cloned().collect();
-    assert_eq!(set_to_vec, vec![1]);
-    assert_eq!(MultiOwnership::pending_change_id_by_space_id(1), Some(1));
-    assert_eq!(MultiOwnership::next_change_id(), 2);
 
-    // Check whether data is stored correctly
-    let change = MultiOwnership::change_by_id(1).unwrap();
-    assert_eq!(change.add_owners, vec![ACCOUNT3]);
-    assert!(change.remove_owners.is_empty());
-    assert_eq!(change.new_threshold, Some(3));
-    assert_eq!(change.notes, self::change_note());
-    assert_eq!(change.confirmed_by, vec![ACCOUNT1]);
+    let space_account = MultiOwnership::space_account_id(SPACE1);
+    assert_eq!(Spaces::space_by_id(SPACE1).unwrap().owner, space_account);
+    assert!(Spaces::space_ids_by_owner(ACCOUNT1).is_empty());
+    assert_eq!(Spaces::space_ids_by_owner(space_account), vec![SPACE1]);
   });
 }
 
 #[test]
-fn propose_change_should_work_with_only_one_owner() {
+fn create_space_owners_should_drop_pending_ownership_transfer() {
   ExtBuilder::build().execute_with(|| {
+    assert_ok!(SpaceOwnership::transfer_space_ownership(Origin::signed(ACCOUNT1), SPACE1, ACCOUNT4, None));
     assert_ok!(_create_default_space_owners());
-    assert_ok!(_propose_change(
+
+    assert!(SpaceOwnership::pending_transfer_by_space_id(SPACE1).is_none());
+    assert_noop!(
+      SpaceOwnership::accept_pending_ownership(Origin::signed(ACCOUNT4), SPACE1, None),
+      SpaceOwnershipError::<Test>::NoPendingTransferOnSpace
+    );
+    assert_eq!(Spaces::space_by_id(SPACE1).unwrap().owner, MultiOwnership::space_account_id(SPACE1));
+  });
+}
+
+#[test]
+fn create_space_owners_should_fail_not_a_space_owner() {
+  ExtBuilder::build().execute_with(|| {
+    assert_noop!(_create_space_owners(
+      Some(Origin::signed(ACCOUNT2)),
+      None,
       None,
       None,
-      Some(vec![ACCOUNT3]),
-      Some(vec![ACCOUNT1, ACCOUNT2]),
-      Some(Some(1)),
-      None)
+      None
+    ), SpacesError::<Test>::NotASpaceOwner);
+  });
+}
+
+#[test]
+fn create_space_owners_should_fail_space_owners_already_exist() {
+  ExtBuilder::build().execute_with(|| {
+    assert_ok!(_create_default_space_owners());
+    assert_noop!(_create_default_space_owners(), Error::<Test>::SpaceOwnersAlreadyExist);
+  });
+}
+
+#[test]
+fn create_space_owners_should_fail_too_long_execution_delay() {
+  ExtBuilder::build().execute_with(|| {
+    assert_noop!(
+      _create_space_owners(None, None, None, None, Some(BlocksToLive::get() + 1)),
+      Error::<Test>::TooLongExecutionDelay
     );
+  });
+}
 
-    // Check storages
-    assert_eq!(MultiOwnership::pending_change_id_by_space_id(1), Some(1));
-    assert_eq!(MultiOwnership::next_change_id(), 2);
+#[test]
+fn update_space_should_fail_for_a_single_owner_of_multi_owned_space() {
+  ExtBuilder::build().execute_with(|| {
+    assert_ok!(_create_default_space_owners());
+    assert_noop!(
+      Spaces::update_space(Origin::signed(ACCOUNT1), SPACE1, self::space_update()),
+      SpacesError::<Test>::NoPermissionToUpdateSpace
+    );
+  });
+}
 
-    // Check whether data is stored correctly
-    let change = MultiOwnership::change_by_id(1).unwrap();
-    assert_eq!(change.add_owners, vec![ACCOUNT3]);
-    assert_eq!(change.remove_owners, vec![ACCOUNT1, ACCOUNT2]);
-    assert_eq!(change.new_threshold, Some(1));
-    assert_eq!(change.notes, self::change_note());
-    assert_eq!(change.confirmed_by, vec![ACCOUNT1]);
+// -------
+
+#[test]
+fn update_space_owners_should_fail_not_a_space_account() {
+  ExtBuilder::build().execute_with(|| {
+    assert_ok!(_create_default_space_owners());
+    assert_noop!(
+      _update_space_owners(Some(Origin::signed(ACCOUNT1)), vec![ACCOUNT3], vec![], None, None),
+      Error::<Test>::NotASpaceAccount
+    );
   });
 }
 
 #[test]
-fn propose_change_should_fail_zero_threshold() {
+fn update_space_owners_should_fail_no_updates_proposed() {
   ExtBuilder::build().execute_with(|| {
     assert_ok!(_create_default_space_owners());
-    assert_noop!(_propose_change(None, None, Some(vec![]), Some(vec![]), Some(Some(0)), None), Error::<Test>::ZeroThershold);
+    assert_noop!(_update_space_owners(None, vec![], vec![], None, None), Error::<Test>::NoUpdatesProposed);
   });
 }
 
 #[test]
-fn propose_change_should_fail_too_big_threshold() {
+fn update_space_owners_should_fail_zero_threshold() {
   ExtBuilder::build().execute_with(|| {
     assert_ok!(_create_default_space_owners());
-    assert_noop!(_propose_change(None, None, Some(vec![]), Some(vec![]), Some(Some(3)), None), Error::<Test>::TooBigThreshold);
+    assert_noop!(_update_space_owners(None, vec![], vec![], Some(0), None), Error::<Test>::ZeroThershold);
   });
 }
 
 #[test]
-fn propose_change_should_fail_no_owners_left() {
+fn update_space_owners_should_fail_too_big_threshold() {
   ExtBuilder::build().execute_with(|| {
     assert_ok!(_create_default_space_owners());
-    assert_noop!(_propose_change(
-      None,
-      None,
-      Some(vec![]),
-      Some(vec![ACCOUNT1, ACCOUNT2]),
-      Some(None),
-      None
-     ), Error::<Test>::NoSpaceOwnersLeft);
+    assert_noop!(_update_space_owners(None, vec![], vec![], Some(3), None), Error::<Test>::TooBigThreshold);
+    // The current threshold is too big for the owners left
+    assert_noop!(_update_space_owners(None, vec![], vec![ACCOUNT2], None, None), Error::<Test>::TooBigThreshold);
   });
 }
 
 #[test]
-fn propose_change_should_fail_proposal_already_exist() {
+fn update_space_owners_should_fail_no_owners_left() {
   ExtBuilder::build().execute_with(|| {
     assert_ok!(_create_default_space_owners());
-    assert_ok!(_propose_default_change());
-    assert_noop!(_propose_change(
-      Some(Origin::signed(ACCOUNT2)),
-      None, None, None, Some(None), None
-     ), Error::<Test>::PendingChangeAlreadyExists);
+    assert_noop!(
+      _update_space_owners(None, vec![], vec![ACCOUNT1, ACCOUNT2], None, None),
+      Error::<Test>::NoSpaceOwnersLeft
+    );
   });
 }
 
 #[test]
-fn propose_change_should_fail_no_updates_on_owners() {
+fn update_space_owners_should_fail_no_updates_on_owners() {
   ExtBuilder::build().execute_with(|| {
     assert_ok!(_create_default_space_owners());
-    assert_noop!(_propose_change(
-      None,
-      None,
-      Some(vec![]),
-      Some(vec![ACCOUNT3]),
-      Some(None),
-      None
-     ), Error::<Test>::NoFieldsUpdatedOnProposal);
+    assert_noop!(
+      _update_space_owners(None, vec![], vec![ACCOUNT3], None, None),
+      Error::<Test>::NoFieldsUpdatedOnProposal
+    );
   });
 }
 
 #[test]
-fn propose_change_should_fail_no_updates_on_threshold() {
+fn update_space_owners_should_fail_no_updates_on_threshold() {
   ExtBuilder::build().execute_with(|| {
     assert_ok!(_create_default_space_owners());
-    assert_noop!(_propose_change(
-      None,
-      None,
-      Some(vec![]),
-      Some(vec![]),
-      Some(Some(2)),
-      None
-     ), Error::<Test>::NoFieldsUpdatedOnProposal);
+    assert_noop!(
+      _update_space_owners(None, vec![], vec![], Some(2), None),
+      Error::<Test>::NoFieldsUpdatedOnProposal
+    );
+  });
+}
+
+#[test]
+fn update_space_owners_should_fail_too_long_execution_delay() {
+  ExtBuilder::build().execute_with(|| {
+    assert_ok!(_create_default_space_owners());
+    assert_noop!(
+      _update_space_owners(None, vec![], vec![], None, Some(BlocksToLive::get() + 1)),
+      Error::<Test>::TooLongExecutionDelay
+    );
+  });
+}
+
+// -------
+
+#[test]
+fn propose_change_should_work() {
+  ExtBuilder::build().execute_with(|| {
+    assert_ok!(_create_default_space_owners());
+    assert_ok!(_propose_default_change());
+
+    // Check storages
+    let set_to_vec: Vec<u64> = MultiOwnership::pending_change_ids().iter().cloned().collect();
+    assert_eq!(set_to_vec, vec![1]);
+    assert!(MultiOwnership::pending_change_ids_by_space_id(SPACE1).contains(&1));
+    assert_eq!(MultiOwnership::next_change_id(), 2);
+
+    // Check whether data is stored correctly
+    let change = MultiOwnership::change_by_id(1).unwrap();
+    assert_eq!(change.call, self::default_change_call());
+    assert_eq!(change.notes, self::change_note());
+    assert_eq!(change.confirmed_by, vec![ACCOUNT1]);
+    assert_eq!(change.executable_at, None);
+  });
+}
+
+#[test]
+fn propose_change_should_dispatch_call_if_threshold_is_one() {
+  ExtBuilder::build().execute_with(|| {
+    assert_ok!(_create_space_owners(None, None, None, Some(1), None));
+    assert_ok!(_propose_change(None, None, Some(self::update_space_call()), None));
+
+    assert!(Spaces::space_by_id(SPACE1).unwrap().hidden);
+    assert!(MultiOwnership::pending_change_ids().is_empty());
+    assert_eq!(MultiOwnership::executed_change_ids_by_space_id(SPACE1), vec![1]);
   });
 }
 
@@ -484,14 +717,10 @@ fn propose_change_should_fail_no_updates_on_threshold() {
 fn propose_change_should_fail_not_a_space_owner() {
   ExtBuilder::build().execute_with(|| {
     assert_ok!(_create_default_space_owners());
-    assert_noop!(_propose_change(
-      Some(Origin::signed(ACCOUNT3)),
-      None,
-      Some(vec![]),
-      Some(vec![]),
-      Some(Some(2)),
-      None
-     ), Error::<Test>::NotASpaceOwner);
+    assert_noop!(
+      _propose_change(Some(Origin::signed(ACCOUNT3)), None, None, None),
+      Error::<Test>::NotASpaceOwner
+    );
   });
 }
 
@@ -505,18 +734,20 @@ fn confirm_change_should_work_owner_added() {
     assert_ok!(_confirm_default_change());
 
     // Check storages
-    assert_eq!(MultiOwnership::pending_change_id_by_space_id(1), None);
-    assert_eq!(MultiOwnership::executed_change_ids_by_space_id(1), vec![1]);
+    assert!(MultiOwnership::pending_change_ids_by_space_id(SPACE1).is_empty());
+    assert_eq!(MultiOwnership::executed_change_ids_by_space_id(SPACE1), vec![1]);
     assert_eq!(MultiOwnership::next_change_id(), 2);
+    assert!(MultiOwnership::space_ids_owned_by_account_id(ACCOUNT3).contains(&SPACE1));
 
     // Check whether data is stored correctly
     let change = MultiOwnership::change_by_id(1).unwrap();
     assert_eq!(change.confirmed_by, vec![ACCOUNT1, ACCOUNT2]);
 
     // Check whether updates applied
-    let space_owners = MultiOwnership::space_owners_by_space_id(1).unwrap();
+    let space_owners = MultiOwnership::space_owners_by_space_id(SPACE1).unwrap();
     assert_eq!(space_owners.owners, vec![ACCOUNT1, ACCOUNT2, ACCOUNT3]);
     assert_eq!(space_owners.threshold, 3);
+    assert_eq!(space_owners.changes_count, 1);
   });
 }
 
@@ -527,29 +758,63 @@ fn confirm_change_should_work_owner_removed() {
     assert_ok!(_propose_change(
       None,
       None,
-      Some(vec![]),
-      Some(vec![ACCOUNT2]),
-      Some(Some(1)),
+      Some(self::update_space_owners_call(SPACE1, vec![], vec![ACCOUNT2], Some(1))),
       None
     ));
     assert_ok!(_confirm_default_change());
 
     // Check storages
-    assert_eq!(MultiOwnership::pending_change_id_by_space_id(1), None);
-    assert_eq!(MultiOwnership::executed_change_ids_by_space_id(1), vec![1]);
-    assert_eq!(MultiOwnership::next_change_id(), 2);
+    assert!(MultiOwnership::pending_change_ids_by_space_id(SPACE1).is_empty());
+    assert_eq!(MultiOwnership::executed_change_ids_by_space_id(SPACE1), vec![1]);
+    assert!(MultiOwnership::space_ids_owned_by_account_id(ACCOUNT2).is_empty());
 
     // Check whether data is stored correctly
     let change = MultiOwnership::change_by_id(1).unwrap();
     assert_eq!(change.confirmed_by, vec![ACCOUNT1, ACCOUNT2]);
 
     // Check whether updates applied
-    let space_owners = MultiOwnership::space_owners_by_space_id(1).unwrap();
+    let space_owners = MultiOwnership::space_owners_by_space_id(SPACE1).unwrap();
     assert_eq!(space_owners.owners, vec![ACCOUNT1]);
     assert_eq!(space_owners.threshold, 1);
   });
 }
 
+#[test]
+fn confirm_change_should_dispatch_call_on_behalf_of_space_account() {
+  ExtBuilder::build().execute_with(|| {
+    assert_ok!(_create_default_space_owners());
+    assert_ok!(_propose_change(None, None, Some(self::update_space_call()), None));
+    assert_ok!(_confirm_default_change());
+
+    // Check whether the call is dispatched
+    let space = Spaces::space_by_id(SPACE1).unwrap();
+    assert!(space.hidden);
+    assert_eq!(space.updated.unwrap().account, MultiOwnership::space_account_id(SPACE1));
+
+    // Check storages
+    assert!(MultiOwnership::pending_change_ids().is_empty());
+    assert_eq!(MultiOwnership::executed_change_ids_by_space_id(SPACE1), vec![1]);
+  });
+}
+
+#[test]
+fn confirm_change_should_not_count_confirmations_of_removed_owners() {
+  ExtBuilder::build().execute_with(|| {
+    assert_ok!(_create_space_owners(None, None, Some(vec![ACCOUNT1, ACCOUNT2, ACCOUNT3]), None, None));
+    assert_ok!(_propose_change(Some(Origin::signed(ACCOUNT2)), None, Some(self::update_space_call()), None));
+    assert_ok!(_update_space_owners(None, vec![], vec![ACCOUNT2], None, None));
+
+    // The confirmation of ACCOUNT2 is dropped, so the change is not approved yet
+    assert_ok!(_confirm_change(Some(Origin::signed(ACCOUNT1)), None, None, None));
+    assert!(!Spaces::space_by_id(SPACE1).unwrap().hidden);
+    assert_eq!(MultiOwnership::change_by_id(1).unwrap().confirmed_by, vec![ACCOUNT1]);
+
+    assert_ok!(_confirm_change(Some(Origin::signed(ACCOUNT3)), None, None, None));
+    assert!(Spaces::space_by_id(SPACE1).unwrap().hidden);
+    assert_eq!(MultiOwnership::executed_change_ids_by_space_id(SPACE1), vec![1]);
+  });
+}
+
 #[test]
 fn confirm_change_should_fail_not_related_to_space_owners() {
   ExtBuilder::build().execute_with(|| {
@@ -557,17 +822,19 @@ fn confirm_change_should_fail_not_related_to_space_owners() {
     assert_ok!(_propose_default_change());
     assert_ok!(_create_space_owners(
       Some(Origin::signed(ACCOUNT3)),
+      Some(SPACE2),
+      Some(vec![ACCOUNT3, ACCOUNT4]),
       Some(2),
-      Some(vec![ACCOUNT3]),
-      Some(1)
+      None
     ));
 
     _propose_change_on_second_space();
 
     assert_noop!(_confirm_change(
       None,
-      Some(1),
-      Some(2)
+      Some(SPACE1),
+      Some(2),
+      None
     ), Error::<Test>::ChangeNotRelatedToSpace);
   });
 }
@@ -577,9 +844,10 @@ fn confirm_change_should_fail_already_confirmed() {
   ExtBuilder::build().execute_with(|| {
     assert_ok!(_create_space_owners(
       Some(Origin::signed(ACCOUNT1)),
-      Some(1),
+      Some(SPACE1),
       Some(vec![ACCOUNT1, ACCOUNT2, ACCOUNT4]),
-      Some(3)
+      Some(3),
+      None
     ));
     assert_ok!(_propose_default_change());
     assert_ok!(_confirm_default_change());
@@ -588,6 +856,20 @@ fn confirm_change_should_fail_already_confirmed() {
   });
 }
 
+#[test]
+fn confirm_change_should_fail_already_executed() {
+  ExtBuilder::build().execute_with(|| {
+    assert_ok!(_create_default_space_owners());
+    assert_ok!(_propose_default_change());
+    assert_ok!(_confirm_default_change());
+
+    assert_noop!(
+      _confirm_change(Some(Origin::signed(ACCOUNT3)), None, None, None),
+      Error::<Test>::ChangeAlreadyExecuted
+    );
+  });
+}
+
 #[test]
 fn confirm_change_should_fail_not_a_space_owner() {
   ExtBuilder::build().execute_with(|| {
@@ -596,11 +878,97 @@ fn confirm_change_should_fail_not_a_space_owner() {
     assert_noop!(_confirm_change(
       Some(Origin::signed(ACCOUNT3)),
       None,
+      None,
       None
      ), Error::<Test>::NotASpaceOwner);
   });
 }
 
+#[test]
+fn confirm_change_should_fail_max_weight_too_low() {
+  ExtBuilder::build().execute_with(|| {
+    assert_ok!(_create_default_space_owners());
+    assert_ok!(_propose_change(None, None, Some(self::update_space_call()), None));
+    assert_noop!(_confirm_change(None, None, None, Some(0)), Error::<Test>::MaxWeightTooLow);
+  });
+}
+
+// -------
+
+#[test]
+fn confirm_change_should_start_timelock_if_space_has_execution_delay() {
+  ExtBuilder::build().execute_with(|| {
+    _create_timelocked_space_owners_and_propose_update_space();
+    assert_ok!(_confirm_default_change());
+
+    // The call is not dispatched until the execution delay passes
+    assert!(!Spaces::space_by_id(SPACE1).unwrap().hidden);
+    assert!(MultiOwnership::pending_change_ids().contains(&1));
+
+    let change = MultiOwnership::change_by_id(1).unwrap();
+    assert_eq!(change.executable_at, Some(11));
+    assert_eq!(change.expires_at, 11 + BlocksToLive::get());
+  });
+}
+
+#[test]
+fn confirm_change_should_fail_already_approved() {
+  ExtBuilder::build().execute_with(|| {
+    _create_timelocked_space_owners_and_propose_update_space();
+    assert_ok!(_confirm_default_change());
+
+    assert_noop!(
+      _confirm_change(Some(Origin::signed(ACCOUNT3)), None, None, None),
+      Error::<Test>::ChangeAlreadyApproved
+    );
+  });
+}
+
+#[test]
+fn execute_change_should_work_after_execution_delay() {
+  ExtBuilder::build().execute_with(|| {
+    _create_timelocked_space_owners_and_propose_update_space();
+    assert_ok!(_confirm_default_change());
+
+    System::set_block_number(11);
+    assert_ok!(_execute_default_change());
+
+    assert!(Spaces::space_by_id(SPACE1).unwrap().hidden);
+    assert!(MultiOwnership::pending_change_ids().is_empty());
+    assert_eq!(MultiOwnership::executed_change_ids_by_space_id(SPACE1), vec![1]);
+  });
+}
+
+#[test]
+fn execute_change_should_fail_change_is_timelocked() {
+  ExtBuilder::build().execute_with(|| {
+    _create_timelocked_space_owners_and_propose_update_space();
+    assert_ok!(_confirm_default_change());
+
+    System::set_block_number(10);
+    assert_noop!(_execute_default_change(), Error::<Test>::ChangeIsTimelocked);
+  });
+}
+
+#[test]
+fn execute_change_should_fail_not_enough_confirms() {
+  ExtBuilder::build().execute_with(|| {
+    _create_timelocked_space_owners_and_propose_update_space();
+    assert_noop!(_execute_default_change(), Error::<Test>::NotEnoughConfirms);
+  });
+}
+
+#[test]
+fn execute_change_should_fail_max_weight_too_low() {
+  ExtBuilder::build().execute_with(|| {
+    _create_timelocked_space_owners_and_propose_update_space();
+    assert_ok!(_confirm_default_change());
+
+    System::set_block_number(11);
+    assert_noop!(_execute_change(None, Some(0)), Error::<Test>::MaxWeightTooLow);
+  });
+}
+
 // -------
 
 #[test]
@@ -613,12 +981,24 @@ fn cancel_proposal_should_work() {
     // Check storages
     let set_to_vec: Vec<u64> = MultiOwnership::pending_change_ids().iter().cloned().collect();
     assert!(set_to_vec.is_empty());
-    assert_eq!(MultiOwnership::pending_change_id_by_space_id(1), None);
+    assert!(MultiOwnership::pending_change_ids_by_space_id(SPACE1).is_empty());
     assert_eq!(MultiOwnership::next_change_id(), 2);
     assert!(MultiOwnership::change_by_id(1).is_none());
   });
 }
 
+#[test]
+fn cancel_proposal_should_work_while_timelocked() {
+  ExtBuilder::build().execute_with(|| {
+    _create_timelocked_space_owners_and_propose_update_space();
+    assert_ok!(_confirm_default_change());
+    assert_ok!(_cancel_default_proposal());
+
+    System::set_block_number(11);
+    assert_noop!(_execute_default_change(), Error::<Test>::ChangeNotFound);
+  });
+}
+
 #[test]
 fn cancel_proposal_should_fail_not_related_to_space_owners() {
   ExtBuilder::build().execute_with(|| {
@@ -626,16 +1006,17 @@ fn cancel_proposal_should_fail_not_related_to_space_owners() {
     assert_ok!(_propose_default_change());
     assert_ok!(_create_space_owners(
       Some(Origin::signed(ACCOUNT3)),
+      Some(SPACE2),
+      Some(vec![ACCOUNT3, ACCOUNT4]),
       Some(2),
-      Some(vec![ACCOUNT3]),
-      Some(1)
+      None
     ));
 
     _propose_change_on_second_space();
 
     assert_noop!(_cancel_change(
       None,
-      Some(1),
+      Some(SPACE1),
       Some(2)
     ), Error::<Test>::ChangeNotRelatedToSpace);
   });
@@ -666,3 +1047,19 @@ fn cancel_proposal_should_fail_not_a_space_owner() {
      ), Error::<Test>::NotASpaceOwner);
   });
 }
+
+// -------
+
+#[test]
+fn delete_expired_changes_should_remove_expired_changes() {
+  ExtBuilder::build().execute_with(|| {
+    assert_ok!(_create_default_space_owners());
+    assert_ok!(_propose_default_change());
+
+    MultiOwnership::delete_expired_changes(DeleteExpiredChangesPeriod::get() * 169);
+
+    assert!(MultiOwnership::change_by_id(1).is_none());
+    assert!(MultiOwnership::pending_change_ids().is_empty());
+    assert!(MultiOwnership::pending_change_ids_by_space_id(SPACE1).is_empty());
+  });
+}
@@ -1,17 +1,35 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use codec::{Decode, Encode};
 use frame_support::{
     decl_error, decl_event, decl_module, decl_storage,
     ensure,
     dispatch::DispatchResult,
-    traits::Get
+    storage::{with_transaction, TransactionOutcome, migration::StorageIterator},
+    traits::{Get, Currency, ExistenceRequirement},
+    weights::Weight,
 };
+use sp_runtime::{RuntimeDebug, traits::Zero};
 use sp_std::prelude::*;
 use frame_system::{self as system, ensure_signed};
 
-use df_traits::moderation::IsAccountBlocked;
-use pallet_spaces::{Module as Spaces, SpaceById, SpaceIdsByOwner};
-use pallet_utils::{Error as UtilsError, SpaceId, remove_from_vec};
+use df_traits::{SpaceCleaner, moderation::IsAccountBlocked};
+use pallet_spaces::{Module as Spaces, Space, AfterSpaceOwnerChanged};
+use pallet_utils::{Error as UtilsError, SpaceId, WhoAndWhen, remove_from_vec};
+
+type BalanceOf<T> =
+    <<T as pallet_utils::Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+/// An offer of the current space owner to transfer a space to another account.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+pub struct PendingOwnershipTransfer<T: Trait> {
+    pub created: WhoAndWhen<T>,
+    pub transfer_to: T::AccountId,
+    /// The amount of tokens that `transfer_to` pays to the current owner when accepting this transfer.
+    pub price: Option<BalanceOf<T>>,
+    /// The transfer is removed at the end of this block if it was not accepted.
+    pub expires_at: T::BlockNumber,
+}
 
 /// The pallet's configuration trait.
 pub trait Trait: system::Trait
@@ -20,6 +38,9 @@ pub trait Trait: system::Trait
 {
     /// The overarching event type.
     type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+    /// How many blocks a pending ownership transfer can wait to be accepted.
+    type PendingTransferLifetime: Get<Self::BlockNumber>;
 }
 
 decl_error! {
@@ -34,14 +55,23 @@ decl_error! {
     NotAllowedToAcceptOwnershipTransfer,
     /// Account is not allowed to reject ownership transfer.
     NotAllowedToRejectOwnershipTransfer,
+    /// Price of a space can not be zero.
+    ZeroPriceProvided,
+    /// Ownership transfer was offered by an account that no longer owns this space.
+    TransferOfferedByPreviousOwner,
+    /// Price of a space is higher than the max price that an account agreed to pay.
+    PriceIsHigherThanExpected,
   }
 }
 
 // This pallet's storage items.
 decl_storage! {
     trait Store for Module<T: Trait> as SpaceOwnershipModule {
-        pub PendingSpaceOwner get(fn pending_space_owner):
-            map hasher(twox_64_concat) SpaceId => Option<T::AccountId>;
+        pub PendingTransferBySpaceId get(fn pending_transfer_by_space_id):
+            map hasher(twox_64_concat) SpaceId => Option<PendingOwnershipTransfer<T>>;
+
+        PendingTransfersByExpireBlock get(fn pending_transfers_by_expire_block):
+            map hasher(twox_64_concat) T::BlockNumber => Vec<SpaceId>;
     }
 }
 
@@ -52,6 +82,8 @@ decl_event!(
         SpaceOwnershipTransferCreated(/* current owner */ AccountId, SpaceId, /* new owner */ AccountId),
         SpaceOwnershipTransferAccepted(AccountId, SpaceId),
         SpaceOwnershipTransferRejected(AccountId, SpaceId),
+        SpaceOwnershipTransferCanceled(AccountId, SpaceId),
+        SpaceOwnershipTransferExpired(SpaceId),
     }
 );
 
@@ -62,11 +94,37 @@ decl_module! {
     // Initializing errors
     type Error = Error<T>;
 
+    /// How many blocks a pending ownership transfer can wait to be accepted.
+    const PendingTransferLifetime: T::BlockNumber = T::PendingTransferLifetime::get();
+
     // Initializing events
     fn deposit_event() = default;
 
-    #[weight = 10_000 + T::DbWeight::get().reads_writes(1, 1)]
-    pub fn transfer_space_ownership(origin, space_id: SpaceId, transfer_to: T::AccountId) -> DispatchResult {
+    fn on_runtime_upgrade() -> Weight {
+      Self::migrate_pending_space_owners()
+    }
+
+    fn on_finalize(block_number: T::BlockNumber) {
+      for space_id in PendingTransfersByExpireBlock::<T>::take(block_number) {
+        let is_expired = Self::pending_transfer_by_space_id(space_id)
+          .map_or(false, |transfer| transfer.expires_at == block_number);
+
+        if is_expired {
+          <PendingTransferBySpaceId<T>>::remove(space_id);
+          Self::deposit_event(RawEvent::SpaceOwnershipTransferExpired(space_id));
+        }
+      }
+    }
+
+    /// Offer a space to another account, optionally for a `price` that it pays on accepting.
+    /// A new offer replaces the pending one.
+    #[weight = 10_000 + T::DbWeight::get().reads_writes(3, 3)]
+    pub fn transfer_space_ownership(
+      origin,
+      space_id: SpaceId,
+      transfer_to: T::AccountId,
+      price: Option<BalanceOf<T>>
+    ) -> DispatchResult {
       let who = ensure_signed(origin)?;
 
       let space = Spaces::<T>::require_space(space_id)?;
@@ -74,37 +132,47 @@ decl_module! {
 
       ensure!(who != transfer_to, Error::<T>::CannotTranferToCurrentOwner);
       ensure!(T::IsAccountBlocked::is_allowed_account(transfer_to.clone(), space_id), UtilsError::<T>::AccountIsBlocked);
+      ensure!(price.map_or(true, |price| !price.is_zero()), Error::<T>::ZeroPriceProvided);
 
-      <PendingSpaceOwner<T>>::insert(space_id, transfer_to.clone());
+      if let Some(old_transfer) = Self::pending_transfer_by_space_id(space_id) {
+        Self::remove_pending_transfer(space_id, old_transfer.expires_at);
+      }
+
+      let expires_at = <system::Module<T>>::block_number() + T::PendingTransferLifetime::get();
+      let transfer = PendingOwnershipTransfer {
+        created: WhoAndWhen::<T>::new(who.clone()),
+        transfer_to: transfer_to.clone(),
+        price,
+        expires_at,
+      };
+
+      <PendingTransferBySpaceId<T>>::insert(space_id, transfer);
+      PendingTransfersByExpireBlock::<T>::mutate(expires_at, |space_ids| space_ids.push(space_id));
 
       Self::deposit_event(RawEvent::SpaceOwnershipTransferCreated(who, space_id, transfer_to));
       Ok(())
     }
 
-    #[weight = 10_000 + T::DbWeight::get().reads_writes(2, 2)]
-    pub fn accept_pending_ownership(origin, space_id: SpaceId) -> DispatchResult {
+    /// Accept a pending ownership transfer, paying at most `max_price` for it.
+    /// `None` means that only a free transfer is accepted,
+    /// so the price can not be raised by a new offer right before this call.
+    #[weight = 10_000 + T::DbWeight::get().reads_writes(6, 7)]
+    pub fn accept_pending_ownership(origin, space_id: SpaceId, max_price: Option<BalanceOf<T>>) -> DispatchResult {
       let new_owner = ensure_signed(origin)?;
 
-      let mut space = Spaces::require_space(space_id)?;
+      let space = Spaces::require_space(space_id)?;
       ensure!(!space.is_owner(&new_owner), Error::<T>::AlreadyASpaceOwner);
 
-      let transfer_to = Self::pending_space_owner(space_id).ok_or(Error::<T>::NoPendingTransferOnSpace)?;
-      ensure!(new_owner == transfer_to, Error::<T>::NotAllowedToAcceptOwnershipTransfer);
+      let transfer = Self::pending_transfer_by_space_id(space_id).ok_or(Error::<T>::NoPendingTransferOnSpace)?;
+      ensure!(new_owner == transfer.transfer_to, Error::<T>::NotAllowedToAcceptOwnershipTransfer);
+      ensure!(transfer.created.account == space.owner, Error::<T>::TransferOfferedByPreviousOwner);
 
-      // Here we know that the origin is eligible to become a new owner of this space.
-      <PendingSpaceOwner<T>>::remove(space_id);
-
-      Spaces::maybe_transfer_handle_deposit_to_new_space_owner(&space, &new_owner)?;
-
-      let old_owner = space.owner;
-      space.owner = new_owner.clone();
-      <SpaceById<T>>::insert(space_id, space);
-
-      // Remove space id from the list of spaces by old owner
-      <SpaceIdsByOwner<T>>::mutate(old_owner.clone(), |space_ids| remove_from_vec(space_ids, space_id));
+      let price = transfer.price.unwrap_or_else(Zero::zero);
+      ensure!(price <= max_price.unwrap_or_else(Zero::zero), Error::<T>::PriceIsHigherThanExpected);
 
-      // Add space id to the list of spaces by new owner
-      <SpaceIdsByOwner<T>>::mutate(new_owner.clone(), |ids| ids.push(space_id));
+      // Here we know that the origin is eligible to become a new owner of this space.
+      Self::pay_and_take_over_space(space, &new_owner, transfer.price)?;
+      Self::remove_pending_transfer(space_id, transfer.expires_at);
 
       // TODO add a new owner as a space follower? See T::BeforeSpaceCreated::before_space_created(new_owner.clone(), space)?;
 
@@ -117,13 +185,139 @@ decl_module! {
       let who = ensure_signed(origin)?;
 
       let space = Spaces::<T>::require_space(space_id)?;
-      let transfer_to = Self::pending_space_owner(space_id).ok_or(Error::<T>::NoPendingTransferOnSpace)?;
-      ensure!(who == transfer_to || who == space.owner, Error::<T>::NotAllowedToRejectOwnershipTransfer);
+      let transfer = Self::pending_transfer_by_space_id(space_id).ok_or(Error::<T>::NoPendingTransferOnSpace)?;
+      ensure!(who == transfer.transfer_to || who == space.owner, Error::<T>::NotAllowedToRejectOwnershipTransfer);
 
-      <PendingSpaceOwner<T>>::remove(space_id);
+      Self::remove_pending_transfer(space_id, transfer.expires_at);
 
       Self::deposit_event(RawEvent::SpaceOwnershipTransferRejected(who, space_id));
       Ok(())
     }
+
+    /// Cancel a pending ownership transfer on behalf of the current space owner.
+    #[weight = 10_000 + T::DbWeight::get().reads_writes(2, 2)]
+    pub fn cancel_pending_ownership(origin, space_id: SpaceId) -> DispatchResult {
+      let who = ensure_signed(origin)?;
+
+      let space = Spaces::<T>::require_space(space_id)?;
+      space.ensure_space_owner(who.clone())?;
+
+      let transfer = Self::pending_transfer_by_space_id(space_id).ok_or(Error::<T>::NoPendingTransferOnSpace)?;
+      Self::remove_pending_transfer(space_id, transfer.expires_at);
+
+      Self::deposit_event(RawEvent::SpaceOwnershipTransferCanceled(who, space_id));
+      Ok(())
+    }
   }
 }
+
+impl<T: Trait> Module<T> {
+    /// Pay a price (if any) to the current space owner, then hand a space with its handle deposit
+    /// over to a new owner. Storage is reverted if any step fails, so no one pays for a space they did not get.
+    fn pay_and_take_over_space(
+        space: Space<T>,
+        new_owner: &T::AccountId,
+        price: Option<BalanceOf<T>>,
+    ) -> DispatchResult {
+        with_transaction(|| {
+            let res = Self::pay_for_space(&space, new_owner, price)
+                .and_then(|_| Spaces::transfer_space_to_new_owner(space, new_owner));
+
+            if res.is_ok() {
+                TransactionOutcome::Commit(res)
+            } else {
+                TransactionOutcome::Rollback(res)
+            }
+        })
+    }
+
+    fn pay_for_space(space: &Space<T>, buyer: &T::AccountId, price: Option<BalanceOf<T>>) -> DispatchResult {
+        if let Some(price) = price {
+            <T as pallet_utils::Trait>::Currency::transfer(
+                buyer,
+                &space.owner,
+                price,
+                ExistenceRequirement::KeepAlive
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Convert offers stored in the old `PendingSpaceOwner` map (SpaceId => AccountId)
+    /// into free transfers that expire in `PendingTransferLifetime` blocks from now.
+    /// The old map is drained, so running this again does nothing.
+    fn migrate_pending_space_owners() -> Weight {
+        let expires_at = <system::Module<T>>::block_number() + T::PendingTransferLifetime::get();
+        let mut old_transfers: u64 = 0;
+        let mut migrated: u64 = 0;
+
+        let old_pending_owners = StorageIterator::<T::AccountId>::new(b"SpaceOwnershipModule", b"PendingSpaceOwner");
+        for (key, transfer_to) in old_pending_owners.drain() {
+            old_transfers += 1;
+
+            // Keys were hashed with twox_64_concat: an 8-byte hash followed by an encoded space id.
+            let maybe_space = key.get(8..)
+                .and_then(|mut encoded_id| SpaceId::decode(&mut encoded_id).ok())
+                .and_then(Spaces::<T>::space_by_id);
+
+            if let Some(space) = maybe_space {
+                if space.owner == transfer_to {
+                    continue;
+                }
+
+                let transfer = PendingOwnershipTransfer {
+                    created: WhoAndWhen::<T>::new(space.owner),
+                    transfer_to,
+                    price: None,
+                    expires_at,
+                };
+
+                <PendingTransferBySpaceId<T>>::insert(space.id, transfer);
+                PendingTransfersByExpireBlock::<T>::mutate(expires_at, |space_ids| space_ids.push(space.id));
+                migrated += 1;
+            }
+        }
+
+        T::DbWeight::get().reads_writes(1 + old_transfers * 2, old_transfers + migrated * 2)
+    }
+
+    fn remove_pending_transfer(space_id: SpaceId, expires_at: T::BlockNumber) {
+        <PendingTransferBySpaceId<T>>::remove(space_id);
+
+        PendingTransfersByExpireBlock::<T>::mutate_exists(expires_at, |space_ids_opt| {
+            let is_empty = space_ids_opt.as_mut().map_or(false, |space_ids| {
+                remove_from_vec(space_ids, space_id);
+                space_ids.is_empty()
+            });
+
+            if is_empty {
+                *space_ids_opt = None;
+            }
+        });
+    }
+}
+
+impl<T: Trait> AfterSpaceOwnerChanged<T> for Module<T> {
+    fn after_space_owner_changed(space: &Space<T>, _old_owner: &T::AccountId) {
+        // An offer made by the previous owner must not be accepted after the space changed hands.
+        if let Some(transfer) = Self::pending_transfer_by_space_id(space.id) {
+            Self::remove_pending_transfer(space.id, transfer.expires_at);
+        }
+    }
+}
+
+impl<T: Trait> SpaceCleaner for Module<T> {
+    fn clean_up_space(space_id: SpaceId, limit: u32) -> u32 {
+        if limit == 0 {
+            return 0;
+        }
+
+        match Self::pending_transfer_by_space_id(space_id) {
+            Some(transfer) => {
+                Self::remove_pending_transfer(space_id, transfer.expires_at);
+                1
+            },
+            None => 0,
+        }
+    }
+}
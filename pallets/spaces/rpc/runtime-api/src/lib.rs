@@ -0,0 +1,24 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::too_many_arguments)]
+#![allow(clippy::unnecessary_mut_passed)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+use pallet_spaces::rpc::FlatSpace;
+use pallet_utils::SpaceId;
+
+sp_api::decl_runtime_apis! {
+    pub trait SpacesApi<AccountId, BlockNumber> where
+        AccountId: Codec,
+        BlockNumber: Codec
+    {
+        fn get_spaces(start_id: u64, limit: u64) -> Vec<FlatSpace<AccountId, BlockNumber>>;
+
+        fn get_spaces_by_ids(space_ids: Vec<SpaceId>) -> Vec<FlatSpace<AccountId, BlockNumber>>;
+
+        fn get_space_by_handle(handle: Vec<u8>) -> Option<FlatSpace<AccountId, BlockNumber>>;
+
+        fn get_space_ids_by_owner(owner: AccountId) -> Vec<SpaceId>;
+    }
+}
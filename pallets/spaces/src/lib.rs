@@ -4,18 +4,22 @@ use codec::{Decode, Encode};
 use frame_support::{
     decl_error, decl_event, decl_module, decl_storage, ensure,
     dispatch::{DispatchError, DispatchResult},
+    storage::IterableStorageMap,
     traits::{Get, Currency, ExistenceRequirement, ReservableCurrency},
+    weights::Weight,
 };
 use sp_runtime::RuntimeDebug;
 use sp_std::prelude::*;
 use frame_system::{self as system, ensure_signed};
 
 use df_traits::{
-    SpaceForRoles, SpaceForRolesProvider, PermissionChecker, SpaceFollowsProvider,
+    SpaceForRoles, SpaceForRolesProvider, PermissionChecker, SpaceFollowsProvider, SpaceCleaner,
     moderation::{IsAccountBlocked, IsContentBlocked},
 };
 use pallet_permissions::{Module as Permissions, SpacePermission, SpacePermissions, SpacePermissionsContext};
-use pallet_utils::{Module as Utils, Error as UtilsError, SpaceId, WhoAndWhen, Content};
+use pallet_utils::{Module as Utils, Error as UtilsError, SpaceId, WhoAndWhen, Content, remove_from_vec};
+
+pub mod rpc;
 
 #[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
 pub struct Space<T: Trait> {
@@ -72,11 +76,19 @@ pub trait Trait: system::Trait
 
     type AfterSpaceUpdated: AfterSpaceUpdated<Self>;
 
+    type AfterSpaceOwnerChanged: AfterSpaceOwnerChanged<Self>;
+
     type IsAccountBlocked: IsAccountBlocked<Self::AccountId>;
 
     type IsContentBlocked: IsContentBlocked;
 
     type HandleDeposit: Get<BalanceOf<Self>>;
+
+    /// Removes posts, roles, follows, etc. of deleted spaces.
+    type SpaceCleaner: SpaceCleaner;
+
+    /// Max number of items of deleted spaces to remove per block.
+    type MaxItemsToCleanUpPerBlock: Get<u16>;
 }
 
 decl_error! {
@@ -95,6 +107,10 @@ decl_error! {
     NoPermissionToCreateSubspaces,
     /// Space is at root level, no parent_id specified
     SpaceIsAtRoot,
+    /// User has no permission to delete their own subspaces in this space.
+    NoPermissionToDeleteOwnSubspaces,
+    /// User is not a subspace owner and has no permission to delete subspaces in this space.
+    NoPermissionToDeleteAnySubspace,
   }
 }
 
@@ -121,6 +137,13 @@ decl_storage! {
 
         pub SpaceIdsByOwner get(fn space_ids_by_owner):
             map hasher(twox_64_concat) T::AccountId => Vec<SpaceId>;
+
+        /// Ids of subspaces of a space (key).
+        pub SubspaceIdsByParentId get(fn subspace_ids_by_parent_id):
+            map hasher(twox_64_concat) SpaceId => Vec<SpaceId>;
+
+        /// Ids of deleted spaces which related data is not fully removed yet.
+        pub SpaceIdsToCleanUp get(fn space_ids_to_clean_up): Vec<SpaceId>;
     }
     add_extra_genesis {
       config(endowed_account): T::AccountId;
@@ -134,6 +157,8 @@ decl_event!(
         SpaceCreated(AccountId, SpaceId),
         SpaceUpdated(AccountId, SpaceId),
         SpaceDeleted(AccountId, SpaceId),
+        /// All data related to the deleted space was removed.
+        SpaceCleanedUp(SpaceId),
     }
 );
 
@@ -143,12 +168,22 @@ decl_module! {
 
     const HandleDeposit: BalanceOf<T> = T::HandleDeposit::get();
 
+    const MaxItemsToCleanUpPerBlock: u16 = T::MaxItemsToCleanUpPerBlock::get();
+
     // Initializing errors
     type Error = Error<T>;
 
     // Initializing events
     fn deposit_event() = default;
 
+    fn on_runtime_upgrade() -> Weight {
+      Self::backfill_subspace_ids_by_parent_id()
+    }
+
+    fn on_initialize(_block_number: T::BlockNumber) -> Weight {
+      Self::clean_up_deleted_spaces()
+    }
+
     #[weight = 500_000 + T::DbWeight::get().reads_writes(4, 4)]
     pub fn create_space(
       origin,
@@ -191,6 +226,9 @@ decl_module! {
 
       <SpaceById<T>>::insert(space_id, new_space);
       <SpaceIdsByOwner<T>>::mutate(owner.clone(), |ids| ids.push(space_id));
+      if let Some(parent_id) = parent_id_opt {
+        SubspaceIdsByParentId::mutate(parent_id, |ids| ids.push(space_id));
+      }
       NextSpaceId::mutate(|n| { *n += 1; });
 
       Self::deposit_event(RawEvent::SpaceCreated(owner, space_id));
@@ -293,6 +331,10 @@ decl_module! {
       if is_update_applied {
         space.updated = Some(WhoAndWhen::<T>::new(owner.clone()));
 
+        if let Some(old_parent_id_opt) = old_data.parent_id {
+          Self::move_in_subspace_index(space_id, old_parent_id_opt, space.parent_id);
+        }
+
         <SpaceById<T>>::insert(space_id, space.clone());
         T::AfterSpaceUpdated::after_space_updated(owner.clone(), &space, old_data);
 
@@ -300,6 +342,31 @@ decl_module! {
       }
       Ok(())
     }
+
+    /// Delete a space and release its handle together with the handle deposit.
+    /// Posts, roles, follows and other data of this space are removed later
+    /// in `on_initialize`, at most `MaxItemsToCleanUpPerBlock` items per block.
+    /// Subspaces of this space are moved to the root level at the same time.
+    #[weight = 500_000 + T::DbWeight::get().reads_writes(4, 4)]
+    pub fn delete_space(origin, space_id: SpaceId) -> DispatchResult {
+      let who = ensure_signed(origin)?;
+
+      let space = Self::require_space(space_id)?;
+
+      Self::ensure_account_can_delete_space(&who, &space)?;
+
+      if let Some(handle) = space.handle.clone() {
+        Self::unreserve_handle(&space, handle)?;
+      }
+
+      <SpaceById<T>>::remove(space_id);
+      <SpaceIdsByOwner<T>>::mutate(space.owner, |ids| remove_from_vec(ids, space_id));
+      Self::move_in_subspace_index(space_id, space.parent_id, None);
+      SpaceIdsToCleanUp::mutate(|ids| ids.push(space_id));
+
+      Self::deposit_event(RawEvent::SpaceDeleted(who, space_id));
+      Ok(())
+    }
   }
 }
 
@@ -430,8 +497,125 @@ impl<T: Trait> Module<T> {
         )
     }
 
+    /// A subspace can be deleted by its owner or by anyone who is allowed to delete
+    /// subspaces in the parent space. A root space can be deleted only by its owner.
+    pub fn ensure_account_can_delete_space(account: &T::AccountId, space: &Space<T>) -> DispatchResult {
+        let is_owner = space.is_owner(account);
+
+        match space.parent_id.and_then(Self::space_by_id) {
+            Some(parent_space) => {
+                let (permission, error) =
+                    if is_owner {
+                        (SpacePermission::DeleteOwnSubspaces, Error::<T>::NoPermissionToDeleteOwnSubspaces)
+                    } else {
+                        (SpacePermission::DeleteAnySubspace, Error::<T>::NoPermissionToDeleteAnySubspace)
+                    };
+
+                Self::ensure_account_has_space_permission(
+                    account.clone(),
+                    &parent_space,
+                    permission,
+                    error.into()
+                )
+            },
+            None => space.ensure_space_owner(account.clone()),
+        }
+    }
+
+    /// Remove data of the deleted spaces in the order they were deleted,
+    /// but no more than `MaxItemsToCleanUpPerBlock` items at once.
+    fn clean_up_deleted_spaces() -> Weight {
+        let mut space_ids = Self::space_ids_to_clean_up();
+        if space_ids.is_empty() {
+            return T::DbWeight::get().reads(1);
+        }
+
+        let max_items = T::MaxItemsToCleanUpPerBlock::get() as u32;
+        let mut items_left = max_items;
+
+        while !space_ids.is_empty() && items_left > 0 {
+            let space_id = space_ids[0];
+            let moved = Self::move_subspaces_to_root(space_id, items_left);
+            let removed = moved + T::SpaceCleaner::clean_up_space(space_id, items_left - moved);
+
+            if removed < items_left {
+                space_ids.remove(0);
+                Self::deposit_event(RawEvent::SpaceCleanedUp(space_id));
+            }
+            items_left = items_left.saturating_sub(removed);
+        }
+
+        SpaceIdsToCleanUp::put(space_ids);
+
+        // Removal of one item takes a few reads and writes in other pallets.
+        let items_removed = (max_items - items_left) as Weight;
+        T::DbWeight::get().reads_writes(1 + items_removed * 3, 1 + items_removed * 3)
+    }
+
+    /// Index subspaces created before `SubspaceIdsByParentId` was introduced.
+    /// Subspaces that are already indexed are skipped, so running this again changes nothing.
+    fn backfill_subspace_ids_by_parent_id() -> Weight {
+        let mut spaces: u64 = 0;
+        let mut indexed: u64 = 0;
+
+        for (space_id, space) in <SpaceById<T>>::iter() {
+            spaces += 1;
+
+            if let Some(parent_id) = space.parent_id {
+                let mut subspace_ids = Self::subspace_ids_by_parent_id(parent_id);
+                if !subspace_ids.contains(&space_id) {
+                    subspace_ids.push(space_id);
+                    SubspaceIdsByParentId::insert(parent_id, subspace_ids);
+                    indexed += 1;
+                }
+            }
+        }
+
+        T::DbWeight::get().reads_writes(spaces * 2, indexed)
+    }
+
+    /// Move at most `limit` subspaces of the deleted space to the root level.
+    /// Returns how many subspaces were moved.
+    fn move_subspaces_to_root(space_id: SpaceId, limit: u32) -> u32 {
+        let mut moved = 0u32;
+        let mut subspace_ids = Self::subspace_ids_by_parent_id(space_id);
+
+        while moved < limit {
+            let subspace_id = match subspace_ids.pop() {
+                Some(subspace_id) => subspace_id,
+                None => break,
+            };
+
+            <SpaceById<T>>::mutate(subspace_id, |space_opt| {
+                if let Some(space) = space_opt {
+                    space.parent_id = None;
+                }
+            });
+            moved += 1;
+        }
+
+        if subspace_ids.is_empty() {
+            SubspaceIdsByParentId::remove(space_id);
+        } else {
+            SubspaceIdsByParentId::insert(space_id, subspace_ids);
+        }
+
+        moved
+    }
+
+    /// Move a subspace from the list of subspaces of its old parent to the list of the new one.
+    fn move_in_subspace_index(space_id: SpaceId, old_parent_id: Option<SpaceId>, new_parent_id: Option<SpaceId>) {
+        if let Some(parent_id) = old_parent_id {
+            SubspaceIdsByParentId::mutate(parent_id, |ids| remove_from_vec(ids, space_id));
+        }
+        if let Some(parent_id) = new_parent_id {
+            SubspaceIdsByParentId::mutate(parent_id, |ids| ids.push(space_id));
+        }
+    }
+
     pub fn try_move_space_to_root(space_id: SpaceId) -> DispatchResult {
         let mut space = Self::require_space(space_id)?;
+        Self::move_in_subspace_index(space_id, space.parent_id, None);
         space.parent_id = None;
 
         SpaceById::<T>::insert(space_id, space);
@@ -491,6 +675,27 @@ impl<T: Trait> Module<T> {
         Ok(())
     }
 
+    /// Hand a space over to a new owner: move a handle deposit (if any),
+    /// then update the space owner and the lists of spaces by the old and the new owner.
+    pub fn transfer_space_to_new_owner(mut space: Space<T>, new_owner: &T::AccountId) -> DispatchResult {
+        Self::maybe_transfer_handle_deposit_to_new_space_owner(&space, new_owner)?;
+
+        let space_id = space.id;
+        let old_owner = space.owner.clone();
+        space.owner = new_owner.clone();
+        <SpaceById<T>>::insert(space_id, &space);
+
+        // Remove space id from the list of spaces by old owner
+        <SpaceIdsByOwner<T>>::mutate(&old_owner, |space_ids| remove_from_vec(space_ids, space_id));
+
+        // Add space id to the list of spaces by new owner
+        <SpaceIdsByOwner<T>>::mutate(new_owner, |ids| ids.push(space_id));
+
+        T::AfterSpaceOwnerChanged::after_space_owner_changed(&space, &old_owner);
+
+        Ok(())
+    }
+
     fn reserve_handle(
         space: &Space<T>,
         handle: Vec<u8>
@@ -575,3 +780,9 @@ impl<T: Trait> BeforeSpaceCreated<T> for () {
 pub trait AfterSpaceUpdated<T: Trait> {
     fn after_space_updated(sender: T::AccountId, space: &Space<T>, old_data: SpaceUpdate);
 }
+
+/// Called after a space was handed over to a new owner, e.g. to drop what was offered by the old owner.
+#[impl_trait_for_tuples::impl_for_tuples(10)]
+pub trait AfterSpaceOwnerChanged<T: Trait> {
+    fn after_space_owner_changed(space: &Space<T>, old_owner: &T::AccountId);
+}
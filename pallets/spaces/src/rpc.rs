@@ -0,0 +1,116 @@
+use codec::{Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_runtime::RuntimeDebug;
+use sp_std::prelude::*;
+
+use pallet_permissions::{Module as Permissions, SpacePermissions};
+use pallet_utils::{SpaceId, rpc::{FlatContent, FlatWhoAndWhen}};
+#[cfg(feature = "std")]
+use pallet_utils::rpc::{serialize_option_as_string, deserialize_option_from_string};
+
+use crate::{Module, Space, SpaceById, SpaceIdByHandle, Trait};
+
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct FlatSpace<AccountId, BlockNumber> {
+    pub id: SpaceId,
+
+    #[cfg_attr(feature = "std", serde(flatten))]
+    pub who_and_when: FlatWhoAndWhen<AccountId, BlockNumber>,
+
+    pub owner_id: AccountId,
+
+    #[cfg_attr(feature = "std", serde(skip_serializing_if = "Option::is_none"))]
+    pub parent_id: Option<SpaceId>,
+
+    #[cfg_attr(feature = "std", serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_as_string",
+        deserialize_with = "deserialize_option_from_string",
+        default
+    ))]
+    pub handle: Option<Vec<u8>>,
+
+    #[cfg_attr(feature = "std", serde(flatten))]
+    pub content: FlatContent,
+
+    pub hidden: bool,
+
+    pub posts_count: u32,
+    pub hidden_posts_count: u32,
+    pub visible_posts_count: u32,
+    pub followers_count: u32,
+
+    pub score: i32,
+
+    /// Effective permissions of every level, i.e. space overrides merged with the defaults.
+    pub permissions: SpacePermissions,
+}
+
+impl<T: Trait> From<Space<T>> for FlatSpace<T::AccountId, T::BlockNumber> {
+    fn from(from: Space<T>) -> Self {
+        let Space {
+            id, created, updated, owner,
+            parent_id, handle, content, hidden, posts_count,
+            hidden_posts_count, followers_count, score, permissions, ..
+        } = from;
+
+        Self {
+            id,
+            who_and_when: (created, updated).into(),
+            owner_id: owner,
+            parent_id,
+            handle,
+            content: content.into(),
+            hidden,
+            posts_count,
+            hidden_posts_count,
+            visible_posts_count: posts_count.saturating_sub(hidden_posts_count),
+            followers_count,
+            score,
+            permissions: Permissions::<T>::resolve_space_perms(permissions),
+        }
+    }
+}
+
+impl<T: Trait> Module<T> {
+
+    /// Get up to `limit` spaces starting from `start_id`, skipping the ids that have no space.
+    pub fn get_spaces(start_id: u64, limit: u64) -> Vec<FlatSpace<T::AccountId, T::BlockNumber>> {
+        let mut spaces = Vec::new();
+
+        let last_space_id = Self::next_space_id().saturating_sub(1);
+        let mut space_id = start_id;
+
+        while spaces.len() < limit as usize && space_id <= last_space_id {
+            if let Some(space) = Self::space_by_id(space_id) {
+                spaces.push(space.into());
+            }
+            space_id = space_id.saturating_add(1);
+        }
+
+        spaces
+    }
+
+    pub fn get_spaces_by_ids(space_ids: Vec<SpaceId>) -> Vec<FlatSpace<T::AccountId, T::BlockNumber>> {
+        space_ids.iter()
+            .filter_map(|id| SpaceById::<T>::get(id))
+            .map(|space| space.into())
+            .collect()
+    }
+
+    /// Find a space by its handle. The handle is lowercased before the lookup.
+    pub fn get_space_by_handle(handle: Vec<u8>) -> Option<FlatSpace<T::AccountId, T::BlockNumber>> {
+        let handle_in_lowercase = pallet_utils::Module::<T>::lowercase_handle(handle);
+
+        SpaceIdByHandle::get(handle_in_lowercase)
+            .and_then(|space_id| Self::space_by_id(space_id))
+            .map(|space| space.into())
+    }
+
+    pub fn get_space_ids_by_owner(owner: T::AccountId) -> Vec<SpaceId> {
+        Self::space_ids_by_owner(owner)
+    }
+}
@@ -1,14 +1,20 @@
 use crate::*;
 
-use sp_runtime::traits::Saturating;
-use pallet_sudo::Module as Sudo;
+use sp_runtime::{
+    Perbill,
+    helpers_128bit::multiply_by_rational,
+    traits::{Bounded, Saturating, SaturatedConversion},
+};
 use frame_support::{
     dispatch::DispatchError,
     traits::schedule::DispatchTime,
 };
 
+use df_traits::{SpaceCleaner, subscriptions::SubscriptionPlansProvider};
 use pallet_permissions::SpacePermission;
+use pallet_roles::Module as Roles;
 use pallet_spaces::Space;
+use pallet_utils::User;
 
 impl<T: Trait> Module<T> {
     pub fn require_plan(plan_id: SubscriptionPlanId) -> Result<SubscriptionPlan<T>, DispatchError> {
@@ -28,6 +34,28 @@ impl<T: Trait> Module<T> {
         )
     }
 
+    pub fn ensure_valid_period(period: &SubscriptionPeriod<T::BlockNumber>) -> DispatchResult {
+        if let SubscriptionPeriod::Custom(period_in_blocks) = period {
+            ensure!(*period_in_blocks >= T::MinCustomPeriodInBlocks::get(), Error::<T>::CustomPeriodTooShort);
+            ensure!(*period_in_blocks <= T::MaxCustomPeriodInBlocks::get(), Error::<T>::CustomPeriodTooLong);
+        }
+        Ok(())
+    }
+
+    pub fn ensure_role_in_space(role_id: RoleId, space_id: SpaceId) -> DispatchResult {
+        let role = Roles::<T>::require_role(role_id)?;
+        ensure!(role.space_id == space_id, Error::<T>::RoleNotFoundInSpace);
+        Ok(())
+    }
+
+    pub fn ensure_valid_trial_period(trial_period: T::BlockNumber) -> DispatchResult {
+        ensure!(
+            !trial_period.is_zero() && trial_period <= T::MaxCustomPeriodInBlocks::get(),
+            Error::<T>::InvalidTrialPeriod
+        );
+        Ok(())
+    }
+
     pub fn get_period_in_blocks(period: SubscriptionPeriod<T::BlockNumber>) -> T::BlockNumber {
         match period {
             SubscriptionPeriod::Daily => T::DailyPeriodInBlocks::get(),
@@ -39,36 +67,132 @@ impl<T: Trait> Module<T> {
         }
     }
 
+    /// Schedule the next payment of the subscription at the end of the current period.
     pub(crate) fn schedule_recurring_subscription_payment(
-        subscription_id: SubscriptionId,
+        subscription: &mut Subscription<T>,
         period: SubscriptionPeriod<T::BlockNumber>
     ) -> DispatchResult {
-        let period_in_blocks = Self::get_period_in_blocks(period);
-        let task_name = (SUBSCRIPTIONS_ID, subscription_id).encode();
-        let when = <system::Module<T>>::block_number().saturating_add(period_in_blocks);
+        Self::schedule_subscription_payment_in(subscription, Self::get_period_in_blocks(period))
+    }
+
+    /// Schedule the next payment of the subscription in `delay` blocks from now.
+    ///
+    /// Every payment is a separate one-off task: the next one is scheduled only after
+    /// the current one has been processed, so a failed payment can be retried earlier.
+    pub(crate) fn schedule_subscription_payment_in(
+        subscription: &mut Subscription<T>,
+        delay: T::BlockNumber
+    ) -> DispatchResult {
+        let when = <system::Module<T>>::block_number().saturating_add(delay);
 
         T::Scheduler::schedule_named(
-            task_name,
+            Self::payment_task_name(subscription.id, when),
             DispatchTime::At(when),
-            Some((period_in_blocks, 12)),
+            None,
             1,
-            frame_system::RawOrigin::Signed(Sudo::<T>::key()).into(),
-            Call::process_subscription_payment(subscription_id).into()
+            frame_system::RawOrigin::Root.into(),
+            Call::process_subscription_payment(subscription.id).into()
         ).map_err(|_| Error::<T>::CannotScheduleReccurentPayment)?;
+
+        subscription.next_payment_at = when;
         Ok(())
     }
 
-    pub(crate) fn cancel_recurring_subscription_payment(subscription_id: SubscriptionId) {
-        let _ = T::Scheduler::cancel_named((SUBSCRIPTIONS_ID, subscription_id).encode())
+    pub(crate) fn cancel_recurring_subscription_payment(subscription: &Subscription<T>) {
+        let task_name = Self::payment_task_name(subscription.id, subscription.next_payment_at);
+        let _ = T::Scheduler::cancel_named(task_name)
             .map_err(|_| Error::<T>::RecurringPaymentMissing);
         // todo: emmit event with status
     }
 
+    fn payment_task_name(subscription_id: SubscriptionId, when: T::BlockNumber) -> Vec<u8> {
+        (SUBSCRIPTIONS_ID, subscription_id, when).encode()
+    }
+
+    /// The value of the part of the current period that is not used yet.
+    /// It's zero during a trial and while a payment is overdue.
+    pub fn unused_value_of_period(subscription: &Subscription<T>) -> BalanceOf<T> {
+        if subscription.failed_payments > 0 {
+            return Zero::zero();
+        }
+
+        let now = <system::Module<T>>::block_number();
+        let blocks_left = subscription.next_payment_at.saturating_sub(now);
+        let period_len = subscription.next_payment_at.saturating_sub(subscription.period_started_at);
+
+        Perbill::from_rational_approximation(blocks_left, period_len) * subscription.paid_for_period
+    }
+
+    /// The price of the rest of the current period at the rate of the `plan`.
+    /// It's zero during a trial, as the trial continues after switching plans.
+    ///
+    /// The rest of the period can be longer than the period of the `plan`
+    /// (e.g. when switching from a yearly plan to a monthly one), so it's not capped by `plan.price`.
+    pub fn price_of_rest_of_period(subscription: &Subscription<T>, plan: &SubscriptionPlan<T>) -> BalanceOf<T> {
+        if subscription.paid_for_period.is_zero() {
+            return Zero::zero();
+        }
+
+        let now = <system::Module<T>>::block_number();
+        let blocks_left = subscription.next_payment_at.saturating_sub(now);
+        let plan_period_len = Self::get_period_in_blocks(plan.period.clone());
+
+        if plan_period_len.is_zero() {
+            return Zero::zero();
+        }
+
+        multiply_by_rational(
+            plan.price.saturated_into::<u128>(),
+            blocks_left.saturated_into::<u128>(),
+            plan_period_len.saturated_into::<u128>(),
+        )
+        .map(|price| price.saturated_into())
+        // The result doesn't fit into u128, so it can't be paid anyway.
+        .unwrap_or_else(|_| Bounded::max_value())
+    }
+
+    /// Grant the plan role (if any) to the subscriber.
+    pub(crate) fn grant_plan_role(subscriber: &T::AccountId, plan: &SubscriptionPlan<T>) {
+        if let Some(role) = plan.role_id.and_then(Roles::<T>::role_by_id) {
+            role.grant_to_users(vec![User::Account(subscriber.clone())]);
+        }
+    }
+
+    /// Revoke the plan role (if any) from the subscriber, unless the same role is granted
+    /// by another paid subscription of this subscriber.
+    pub(crate) fn revoke_plan_role(
+        subscriber: &T::AccountId,
+        plan: &SubscriptionPlan<T>,
+        subscription_id: SubscriptionId
+    ) {
+        let role_id = match plan.role_id {
+            Some(role_id) => role_id,
+            None => return,
+        };
+
+        let is_granted_by_other_subscription = Self::subscription_ids_by_patron(subscriber).into_iter()
+            .filter(|id| *id != subscription_id)
+            .filter_map(Self::subscription_by_id)
+            .filter(|subscription| subscription.is_active && subscription.failed_payments == 0)
+            .filter_map(|subscription| Self::plan_by_id(subscription.plan_id))
+            .any(|other_plan| other_plan.role_id == Some(role_id));
+
+        if is_granted_by_other_subscription {
+            return;
+        }
+
+        if let Some(role) = Roles::<T>::role_by_id(role_id) {
+            role.revoke_from_users(vec![User::Account(subscriber.clone())]);
+        }
+    }
+
     pub(crate) fn do_unsubscribe(who: T::AccountId, subscription: &mut Subscription<T>) -> DispatchResult {
-        let space_id = Self::require_plan(subscription.plan_id)?.space_id;
+        let plan = Self::require_plan(subscription.plan_id)?;
+        let space_id = plan.space_id;
         let subscription_id = subscription.id;
 
-        Self::cancel_recurring_subscription_payment(subscription_id);
+        Self::cancel_recurring_subscription_payment(subscription);
+        Self::revoke_plan_role(&who, &plan, subscription_id);
         subscription.is_active = false;
 
         SubscriptionById::<T>::insert(subscription_id, subscription);
@@ -97,7 +221,9 @@ impl<T: Trait> SubscriptionPlan<T> {
         wallet: Option<T::AccountId>,
         price: BalanceOf<T>,
         period: SubscriptionPeriod<T::BlockNumber>,
-        content: Content
+        content: Content,
+        role_id: Option<RoleId>,
+        trial_period: Option<T::BlockNumber>
     ) -> Self {
         Self {
             id,
@@ -109,6 +235,8 @@ impl<T: Trait> SubscriptionPlan<T> {
             wallet,
             price,
             period,
+            role_id,
+            trial_period,
         }
     }
 
@@ -135,6 +263,11 @@ impl<T: Trait> Subscription<T> {
             is_active: true,
             wallet,
             plan_id,
+            next_payment_at: Zero::zero(),
+            failed_payments: 0,
+            period_started_at: <system::Module<T>>::block_number(),
+            paid_for_period: Zero::zero(),
+            credit: Zero::zero(),
         }
     }
 
@@ -142,4 +275,83 @@ impl<T: Trait> Subscription<T> {
         ensure!(&self.created.account == who, Error::<T>::NotSubscriber);
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+impl<T: Trait> SubscriptionPlansProvider<T::AccountId> for Module<T> {
+    fn is_active_plan_in_space(plan_id: SubscriptionPlanId, space_id: SpaceId) -> bool {
+        Self::plan_by_id(plan_id).map_or(false, |plan| plan.is_active && plan.space_id == space_id)
+    }
+
+    fn is_active_subscriber(account: &T::AccountId, plan_id: SubscriptionPlanId) -> bool {
+        Self::subscription_ids_by_patron(account).into_iter()
+            .filter_map(Self::subscription_by_id)
+            // A subscriber with an overdue payment loses access, as well as the plan role.
+            .any(|subscription| subscription.is_active
+                && subscription.failed_payments == 0
+                && subscription.plan_id == plan_id)
+    }
+}
+
+impl<T: Trait> SpaceCleaner for Module<T> {
+    fn clean_up_space(space_id: SpaceId, limit: u32) -> u32 {
+        let mut removed = 0u32;
+        let mut subscription_ids = Self::subscription_ids_by_space(space_id);
+
+        // Subscribers should not be charged anymore for a deleted space.
+        while removed < limit {
+            let subscription_id = match subscription_ids.pop() {
+                Some(subscription_id) => subscription_id,
+                None => break,
+            };
+
+            if let Some(mut subscription) = Self::subscription_by_id(subscription_id) {
+                Self::cancel_recurring_subscription_payment(&subscription);
+                subscription.is_active = false;
+
+                SubscriptionIdsByPatron::<T>::mutate(
+                    &subscription.created.account,
+                    |ids| remove_from_vec(ids, subscription_id)
+                );
+                SubscriptionById::<T>::insert(subscription_id, subscription);
+            }
+
+            removed += 1;
+        }
+
+        let mut plan_ids = Self::plan_ids_by_space(space_id);
+
+        while removed < limit {
+            let plan_id = match plan_ids.pop() {
+                Some(plan_id) => plan_id,
+                None => break,
+            };
+
+            if let Some(mut plan) = Self::plan_by_id(plan_id) {
+                plan.is_active = false;
+                PlanById::<T>::insert(plan_id, plan);
+            }
+
+            removed += 1;
+        }
+
+        let is_space_cleaned_up = subscription_ids.is_empty() && plan_ids.is_empty();
+
+        if subscription_ids.is_empty() {
+            SubscriptionIdsBySpace::remove(space_id);
+        } else {
+            SubscriptionIdsBySpace::insert(space_id, subscription_ids);
+        }
+
+        if plan_ids.is_empty() {
+            PlanIdsBySpace::remove(space_id);
+        } else {
+            PlanIdsBySpace::insert(space_id, plan_ids);
+        }
+
+        if is_space_cleaned_up {
+            RecipientWallet::<T>::remove(space_id);
+        }
+
+        removed
+    }
+}
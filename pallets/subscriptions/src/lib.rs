@@ -8,15 +8,32 @@
 //! This pallet provides a way for creators to create a list of subscription plans (aka levels, tiers)
 //! and specify a different price and time period per each plan. There are several pre-built
 //! subscription periods: `Daily`, `Weekly`, `Monthly`, `Quarterly` and `Yearly`.
+//! A plan can also have a `Custom` period in blocks, bounded by `MinCustomPeriodInBlocks`
+//! and `MaxCustomPeriodInBlocks`.
+//!
+//! A plan can reference a role from the same space (see `pallet_roles`). Such a role is granted
+//! to every subscriber of the plan, while their subscription is paid, and revoked once
+//! the subscription is cancelled, deactivated or its payment fails.
+//!
+//! A plan can offer a free trial: the first payment of a new subscriber is charged only when
+//! the trial ends. Each account can use a trial of a plan once.
+//!
+//! A subscriber can switch to another plan in the same space. The unused part of the current
+//! period is credited, the remaining part of the period is charged at the price of the new plan,
+//! and only the difference is paid. If the credit is bigger, it's kept on the subscription and
+//! used to pay for the next periods. A space owner can refund the unused part of the current
+//! period together with the credit to a subscriber, that also cancels the subscription.
 //!
 //! This pallet uses Substrate's Schedule pallet to schedule recurring transfers from supporters'
-//! (patrons') wallets to creators' wallets.
+//! (patrons') wallets to creators' wallets. If a recurring payment fails, the subscription
+//! stays active for a grace period: the payment is retried every `PaymentRetryIntervalInBlocks`
+//! up to `MaxPaymentRetries` times, and only then the subscription is deactivated.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use codec::{Encode, Decode};
 use sp_std::prelude::*;
-use sp_runtime::RuntimeDebug;
+use sp_runtime::{RuntimeDebug, traits::Zero};
 
 use frame_support::{
 	decl_module, decl_storage, decl_event, decl_error, ensure,
@@ -28,14 +45,15 @@ use frame_support::{
 };
 use frame_system::{self as system, ensure_signed, ensure_root};
 
+use pallet_roles::{Module as Roles, RoleId};
 use pallet_spaces::Module as Spaces;
 use pallet_utils::{Module as Utils, SpaceId, Content, WhoAndWhen, remove_from_vec};
 
-/*#[cfg(test)]
+#[cfg(test)]
 mod mock;
 
 #[cfg(test)]
-mod tests;*/
+mod tests;
 
 pub mod functions;
 
@@ -51,7 +69,8 @@ pub enum SubscriptionPeriod<BlockNumber> {
 	Monthly,
 	Quarterly,
 	Yearly,
-	Custom(BlockNumber), // Currently not supported
+	/// A custom period in blocks.
+	Custom(BlockNumber),
 }
 
 #[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
@@ -68,6 +87,12 @@ pub struct SubscriptionPlan<T: Trait> {
 	pub wallet: Option<T::AccountId>,
 	pub price: BalanceOf<T>,
 	pub period: SubscriptionPeriod<T::BlockNumber>,
+
+	/// A role in the same space that is granted to active subscribers of this plan.
+	pub role_id: Option<RoleId>,
+
+	/// How many blocks after subscribing the first payment is charged.
+	pub trial_period: Option<T::BlockNumber>,
 }
 
 #[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
@@ -80,6 +105,18 @@ pub struct Subscription<T: Trait> {
 
 	pub wallet: Option<T::AccountId>,
 	pub plan_id: SubscriptionPlanId,
+
+	/// A block at which the next payment (or a retry of a failed one) is scheduled.
+	pub next_payment_at: T::BlockNumber,
+	/// How many times in a row the recurring payment has failed.
+	pub failed_payments: u16,
+
+	/// A block at which the current (paid or trial) period started.
+	pub period_started_at: T::BlockNumber,
+	/// How much the current period is worth. It is zero during a trial.
+	pub paid_for_period: BalanceOf<T>,
+	/// The unused value of previous plans, that is spent on the next payments.
+	pub credit: BalanceOf<T>,
 }
 
 type BalanceOf<T> = <<T as pallet_utils::Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
@@ -89,14 +126,17 @@ pub trait Trait:
 	system::Trait
 	+ pallet_utils::Trait
 	+ pallet_spaces::Trait
-	+ pallet_sudo::Trait
+	+ pallet_roles::Trait
 {
 	/// The overarching event type.
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
 
 	type Subscription: Dispatchable<Origin=<Self as system::Trait>::Origin> + From<Call<Self>>;
 
-	type Scheduler: ScheduleNamed<Self::BlockNumber, Self::Subscription, Self::Origin>;
+	/// The caller origin the Scheduler dispatches recurring payments with.
+	type PalletsOrigin: From<system::RawOrigin<Self::AccountId>>;
+
+	type Scheduler: ScheduleNamed<Self::BlockNumber, Self::Subscription, Self::PalletsOrigin>;
 
 	type DailyPeriodInBlocks: Get<Self::BlockNumber>;
 
@@ -107,6 +147,18 @@ pub trait Trait:
 	type QuarterlyPeriodInBlocks: Get<Self::BlockNumber>;
 
 	type YearlyPeriodInBlocks: Get<Self::BlockNumber>;
+
+	/// The shortest `Custom` period a plan can have.
+	type MinCustomPeriodInBlocks: Get<Self::BlockNumber>;
+
+	/// The longest `Custom` period a plan can have.
+	type MaxCustomPeriodInBlocks: Get<Self::BlockNumber>;
+
+	/// How many blocks to wait before retrying a failed payment.
+	type PaymentRetryIntervalInBlocks: Get<Self::BlockNumber>;
+
+	/// How many times to retry a failed payment before the subscription is deactivated.
+	type MaxPaymentRetries: Get<u16>;
 }
 
 decl_storage! {
@@ -134,6 +186,12 @@ decl_storage! {
 		pub SubscriptionIdsBySpace get(fn subscription_ids_by_space):
 			map hasher(twox_64_concat) SpaceId => Vec<SubscriptionId>;
 
+		/// Whether an account (key 1) has already used a trial of a plan (key 2).
+		pub TrialUsed get(fn is_trial_used): double_map
+			hasher(blake2_128_concat) T::AccountId,
+			hasher(twox_64_concat) SubscriptionPlanId
+			=> bool;
+
 		// Wallets
 
 		/// A recipient's wallet that receives transfers sent from their subscribers.
@@ -149,10 +207,22 @@ decl_storage! {
 // The pallet's events
 decl_event!(
 	pub enum Event<T> where
-		AccountId = <T as system::Trait>::AccountId
+		AccountId = <T as system::Trait>::AccountId,
+		Balance = BalanceOf<T>
 	{
 		SubscriptionPlanCreated(AccountId, SubscriptionPlanId),
-		// todo: complete event list for this pallet once dispatches are implemented
+		SubscriptionPlanUpdated(AccountId, SubscriptionPlanId),
+		SubscriptionPlanDeleted(AccountId, SubscriptionPlanId),
+		Subscribed(AccountId, SubscriptionId),
+		Unsubscribed(AccountId, SubscriptionId),
+		SubscriptionPaid(AccountId, SubscriptionId),
+		/// A recurring payment failed and will be retried.
+		SubscriptionPaymentFailed(AccountId, SubscriptionId),
+		/// All retries of a failed payment failed, so the subscription was deactivated.
+		SubscriptionDeactivated(AccountId, SubscriptionId),
+		SubscriptionPlanSwitched(AccountId, SubscriptionId, SubscriptionPlanId),
+		/// A space owner refunded an amount to the subscriber and cancelled the subscription.
+		SubscriptionRefunded(AccountId, SubscriptionId, Balance),
 	}
 );
 
@@ -160,6 +230,12 @@ decl_error! {
 	pub enum Error for Module<T: Trait> {
 		AlreadySubscribed,
 		CannotScheduleReccurentPayment,
+		/// Cannot switch to a plan from another space.
+		CannotSwitchToPlanInAnotherSpace,
+		CustomPeriodTooLong,
+		CustomPeriodTooShort,
+		/// Trial period should be longer than zero and not longer than `MaxCustomPeriodInBlocks`.
+		InvalidTrialPeriod,
 		NoPermissionToUpdateSubscriptionPlan,
 		NotSubscriber,
 		NothingToUpdate,
@@ -167,8 +243,12 @@ decl_error! {
 		PriceLowerExistencialDeposit,
 		RecipientNotFound,
 		RecurringPaymentMissing,
+		/// Subscription plan can only grant a role from the same space.
+		RoleNotFoundInSpace,
 		SubscriptionIsNotActive,
 		SubscriptionNotFound,
+		/// Cannot switch a plan while the payment for the current period is overdue.
+		SubscriptionPaymentIsOverdue,
 		SubscriptionPlanNotFound,
 	}
 }
@@ -182,6 +262,10 @@ decl_module! {
 		const MonthlyPeriodInBlocks: T::BlockNumber = T::MonthlyPeriodInBlocks::get();
 		const QuarterlyPeriodInBlocks: T::BlockNumber = T::QuarterlyPeriodInBlocks::get();
 		const YearlyPeriodInBlocks: T::BlockNumber = T::YearlyPeriodInBlocks::get();
+		const MinCustomPeriodInBlocks: T::BlockNumber = T::MinCustomPeriodInBlocks::get();
+		const MaxCustomPeriodInBlocks: T::BlockNumber = T::MaxCustomPeriodInBlocks::get();
+		const PaymentRetryIntervalInBlocks: T::BlockNumber = T::PaymentRetryIntervalInBlocks::get();
+		const MaxPaymentRetries: u16 = T::MaxPaymentRetries::get();
 
 		// Initializing errors
 		type Error = Error<T>;
@@ -193,14 +277,18 @@ decl_module! {
 		/// It's possible to specify a price and time period (in blocks) for the plan.
 		/// Content could be an IPFS CID that points to an off-chain data such as
 		/// plan's title, description and cover image.
-		#[weight = T::DbWeight::get().reads_writes(3, 3) + 25_000]
+		/// If `role_id` is provided, this role will be granted to the plan subscribers.
+		/// If `trial_period` is provided, new subscribers are charged only after it ends.
+		#[weight = T::DbWeight::get().reads_writes(4, 3) + 25_000]
 		pub fn create_plan(
 			origin,
 			space_id: SpaceId,
 			custom_wallet: Option<T::AccountId>,
 			price: BalanceOf<T>,
 			period: SubscriptionPeriod<T::BlockNumber>,
-			content: Content
+			content: Content,
+			role_id: Option<RoleId>,
+			trial_period: Option<T::BlockNumber>
 		) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
 
@@ -211,24 +299,39 @@ decl_module! {
 				Error::<T>::PriceLowerExistencialDeposit
 			);
 
+			Self::ensure_valid_period(&period)?;
+
+			if let Some(trial_period) = trial_period {
+				Self::ensure_valid_trial_period(trial_period)?;
+			}
+
 			let space = Spaces::<T>::require_space(space_id)?;
 			Self::ensure_subscriptions_manager(sender.clone(), &space)?;
 
+			if let Some(role_id) = role_id {
+				// Subscribers get this role, so attaching it to a plan is the same as granting it.
+				Roles::<T>::ensure_role_manager(sender.clone(), space_id)?;
+				Self::ensure_role_in_space(role_id, space_id)?;
+			}
+
 			let plan_id = Self::next_plan_id();
 			let subscription_plan = SubscriptionPlan::<T>::new(
 				plan_id,
-				sender,
+				sender.clone(),
 				space_id,
 				custom_wallet,
 				price,
 				period,
-				content
+				content,
+				role_id,
+				trial_period
 			);
 
 			PlanById::<T>::insert(plan_id, subscription_plan);
 			PlanIdsBySpace::mutate(space_id, |ids| ids.push(plan_id));
 			NextPlanId::mutate(|x| { *x += 1 });
 
+			Self::deposit_event(RawEvent::SubscriptionPlanCreated(sender, plan_id));
 			Ok(())
 		}
 
@@ -244,9 +347,10 @@ decl_module! {
 
 			ensure!(new_wallet != plan.wallet, Error::<T>::NothingToUpdate);
 			plan.wallet = new_wallet;
-			plan.updated = Some(WhoAndWhen::<T>::new(sender));
+			plan.updated = Some(WhoAndWhen::<T>::new(sender.clone()));
 			PlanById::<T>::insert(plan_id, plan);
 
+			Self::deposit_event(RawEvent::SubscriptionPlanUpdated(sender, plan_id));
 			Ok(())
 		}
 
@@ -259,17 +363,15 @@ decl_module! {
 			ensure!(plan.is_active, Error::<T>::PlanIsNotActive);
 
 			let space = Spaces::<T>::require_space(plan.space_id)?;
-			Self::ensure_subscriptions_manager(sender, &space)?;
+			Self::ensure_subscriptions_manager(sender.clone(), &space)?;
 
-			let space_subscriptions = SubscriptionIdsBySpace::take(plan.space_id);
-			let plan_subscriptions = space_subscriptions.iter()
-				.filter(|id| Self::filter_subscriptions_by_plan(**id, plan_id));
+			let plan_subscriptions = Self::subscription_ids_by_space(plan.space_id).into_iter()
+				.filter(|id| Self::filter_subscriptions_by_plan(*id, plan_id));
 
 			for id in plan_subscriptions {
-				if let Ok(mut subscription) = Self::require_subscription(*id) {
-					Self::cancel_recurring_subscription_payment(*id);
-					subscription.is_active = false;
-					SubscriptionById::<T>::insert(id, subscription);
+				if let Ok(mut subscription) = Self::require_subscription(id) {
+					let subscriber = subscription.created.account.clone();
+					Self::do_unsubscribe(subscriber, &mut subscription)?;
 				}
 			}
 
@@ -277,6 +379,7 @@ decl_module! {
 			PlanById::<T>::insert(plan_id, plan.clone());
 			PlanIdsBySpace::mutate(plan.space_id, |ids| remove_from_vec(ids, plan_id));
 
+			Self::deposit_event(RawEvent::SubscriptionPlanDeleted(sender, plan_id));
 			Ok(())
 		}
 
@@ -306,7 +409,10 @@ decl_module! {
 
 		/// Subscribe to a selected subscription plan and optionally specify a wallet
 		/// that will be used for recurring payments fro this subscription.
-		#[weight = T::DbWeight::get().reads_writes(5, 1) + 50_000]
+		/// If the plan has a role, it is granted to the subscriber.
+		/// If the plan has a trial that was not used by this account yet,
+		/// the first payment is charged only when the trial ends.
+		#[weight = T::DbWeight::get().reads_writes(8, 4) + 50_000]
 		pub fn subscribe(
 			origin,
 			plan_id: SubscriptionPlanId,
@@ -317,43 +423,50 @@ decl_module! {
 			let plan = Self::require_plan(plan_id)?;
 			ensure!(plan.is_active, Error::<T>::PlanIsNotActive);
 
-			let subscriptions = Self::subscription_ids_by_patron(&sender);
-			let is_already_subscribed = subscriptions.iter().any(|subscription_id| {
-				if let Ok(subscription) = Self::require_subscription(*subscription_id) {
-					return subscription.plan_id == plan_id;
-				}
-				false
-			});
-			ensure!(is_already_subscribed, Error::<T>::AlreadySubscribed);
+			let is_already_subscribed = Self::subscription_ids_by_patron(&sender).into_iter()
+				.any(|id| Self::filter_subscriptions_by_plan(id, plan_id));
+			ensure!(!is_already_subscribed, Error::<T>::AlreadySubscribed);
+
+			let recipient = plan.try_get_recipient().ok_or(Error::<T>::RecipientNotFound)?;
 
 			let subscription_id = Self::next_subscription_id();
-			let subscription = Subscription::<T>::new(
+			let mut subscription = Subscription::<T>::new(
 				subscription_id,
 				sender.clone(),
 				custom_wallet,
 				plan_id
 			);
 
-			Self::schedule_recurring_subscription_payment(subscription_id, plan.period.clone())?;
-
-			let recipient = plan.try_get_recipient();
-			ensure!(recipient.is_some(), Error::<T>::RecipientNotFound);
+			let trial_period = plan.trial_period.filter(|_| !Self::is_trial_used(&sender, plan_id));
+
+			if let Some(trial_period) = trial_period {
+				Self::schedule_subscription_payment_in(&mut subscription, trial_period)?;
+				TrialUsed::<T>::insert(&sender, plan_id, true);
+			} else {
+				Self::schedule_recurring_subscription_payment(&mut subscription, plan.period.clone())?;
+
+				// todo: maybe implement function `transfer_or_reserve`?
+				<T as pallet_utils::Trait>::Currency::transfer(
+					&sender,
+					&recipient,
+					plan.price,
+					ExistenceRequirement::KeepAlive
+				).map_err(|err| {
+					Self::cancel_recurring_subscription_payment(&subscription);
+					err
+				})?;
+
+				subscription.paid_for_period = plan.price;
+			}
 
-			// todo: maybe implement function `transfer_or_reserve`?
-			<T as pallet_utils::Trait>::Currency::transfer(
-				&sender,
-				&recipient.unwrap(),
-				plan.price,
-				ExistenceRequirement::KeepAlive
-			).map_err(|err| {
-				Self::cancel_recurring_subscription_payment(subscription_id);
-				err
-			})?;
+			Self::grant_plan_role(&sender, &plan);
 
 			SubscriptionById::<T>::insert(subscription_id, subscription);
-			SubscriptionIdsByPatron::<T>::mutate(sender, |ids| ids.push(subscription_id));
+			SubscriptionIdsByPatron::<T>::mutate(sender.clone(), |ids| ids.push(subscription_id));
 			SubscriptionIdsBySpace::mutate(plan.space_id, |ids| ids.push(subscription_id));
+			NextSubscriptionId::mutate(|x| { *x += 1 });
 
+			Self::deposit_event(RawEvent::Subscribed(sender, subscription_id));
 			Ok(())
 		}
 
@@ -388,8 +501,100 @@ decl_module! {
 			ensure!(subscription.is_active, Error::<T>::SubscriptionIsNotActive);
 
 			// todo: add scheduled task to make subscription inactive at the end
-			Self::do_unsubscribe(sender, &mut subscription)?;
+			Self::do_unsubscribe(sender.clone(), &mut subscription)?;
 
+			Self::deposit_event(RawEvent::Unsubscribed(sender, subscription_id));
+			Ok(())
+		}
+
+		/// Switch a subscription to another plan in the same space.
+		///
+		/// The unused part of the current period is credited and the rest of the period
+		/// is charged at the price of the new plan, so only the difference is paid now.
+		/// If the credit is bigger than the charge, the rest of it is spent on the next payments.
+		/// The date of the next payment stays the same, and it is charged for the new plan.
+		#[weight = T::DbWeight::get().reads_writes(9, 6) + 50_000]
+		pub fn switch_subscription_plan(
+			origin,
+			subscription_id: SubscriptionId,
+			new_plan_id: SubscriptionPlanId
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			let mut subscription = Self::require_subscription(subscription_id)?;
+			subscription.ensure_subscriber(&sender)?;
+
+			ensure!(subscription.is_active, Error::<T>::SubscriptionIsNotActive);
+			ensure!(subscription.failed_payments == 0, Error::<T>::SubscriptionPaymentIsOverdue);
+
+			let old_plan = Self::require_plan(subscription.plan_id)?;
+			let new_plan = Self::require_plan(new_plan_id)?;
+			ensure!(new_plan.is_active, Error::<T>::PlanIsNotActive);
+			ensure!(new_plan.space_id == old_plan.space_id, Error::<T>::CannotSwitchToPlanInAnotherSpace);
+
+			let is_already_subscribed = Self::subscription_ids_by_patron(&sender).into_iter()
+				.any(|id| Self::filter_subscriptions_by_plan(id, new_plan_id));
+			ensure!(!is_already_subscribed, Error::<T>::AlreadySubscribed);
+
+			let total_credit = subscription.credit.saturating_add(Self::unused_value_of_period(&subscription));
+			let charge = Self::price_of_rest_of_period(&subscription, &new_plan);
+			let amount_to_pay = charge.saturating_sub(total_credit);
+
+			if !amount_to_pay.is_zero() {
+				let recipient = new_plan.try_get_recipient().ok_or(Error::<T>::RecipientNotFound)?;
+				<T as pallet_utils::Trait>::Currency::transfer(
+					&sender,
+					&recipient,
+					amount_to_pay,
+					ExistenceRequirement::KeepAlive
+				)?;
+			}
+
+			subscription.credit = total_credit.saturating_sub(charge);
+			subscription.plan_id = new_plan_id;
+			subscription.period_started_at = <system::Module<T>>::block_number();
+			subscription.paid_for_period = charge;
+			subscription.updated = Some(WhoAndWhen::<T>::new(sender.clone()));
+
+			Self::revoke_plan_role(&sender, &old_plan, subscription_id);
+			Self::grant_plan_role(&sender, &new_plan);
+
+			SubscriptionById::<T>::insert(subscription_id, subscription);
+
+			Self::deposit_event(RawEvent::SubscriptionPlanSwitched(sender, subscription_id, new_plan_id));
+			Ok(())
+		}
+
+		/// Refund the unused part of the current period and the credit left after switching plans
+		/// to a subscriber and cancel the subscription. The refund is paid by the space owner,
+		/// who is the only one that can call this dispatch.
+		#[weight = T::DbWeight::get().reads_writes(7, 5) + 50_000]
+		pub fn refund_subscription(origin, subscription_id: SubscriptionId) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			let mut subscription = Self::require_subscription(subscription_id)?;
+			ensure!(subscription.is_active, Error::<T>::SubscriptionIsNotActive);
+
+			let plan = Self::require_plan(subscription.plan_id)?;
+			let space = Spaces::<T>::require_space(plan.space_id)?;
+			space.ensure_space_owner(sender.clone())?;
+
+			let subscriber = subscription.created.account.clone();
+			let refund = subscription.credit.saturating_add(Self::unused_value_of_period(&subscription));
+
+			if !refund.is_zero() {
+				<T as pallet_utils::Trait>::Currency::transfer(
+					&sender,
+					&subscriber,
+					refund,
+					ExistenceRequirement::KeepAlive
+				)?;
+			}
+
+			subscription.credit = Zero::zero();
+			Self::do_unsubscribe(subscriber, &mut subscription)?;
+
+			Self::deposit_event(RawEvent::SubscriptionRefunded(sender, subscription_id, refund));
 			Ok(())
 		}
 
@@ -418,24 +623,56 @@ decl_module! {
 			Ok(())
 		}
 
-		#[weight = T::DbWeight::get().reads_writes(4, 1) + 25_000]
+		/// Charge a subscriber for the next period of their subscription.
+		/// Called by the Scheduler at the end of every subscription period.
+		///
+		/// If the payment fails, it's retried every `PaymentRetryIntervalInBlocks`
+		/// and the plan role is revoked from the subscriber until the payment succeeds.
+		/// The subscription is deactivated, once `MaxPaymentRetries` retries have failed.
+		#[weight = T::DbWeight::get().reads_writes(8, 7) + 50_000]
 		pub fn process_subscription_payment(origin, subscription_id: SubscriptionId) -> DispatchResult {
 			ensure_root(origin)?;
 
-			// todo: remove recurring payment if something in this block goes wrong
 			let mut subscription = Self::require_subscription(subscription_id)?;
-			let plan = Self::require_plan(subscription.plan_id)?;
-			let recipient = plan.try_get_recipient();
-			ensure!(recipient.is_some(), Error::<T>::RecipientNotFound);
+			ensure!(subscription.is_active, Error::<T>::SubscriptionIsNotActive);
 
-			subscription.is_active = <T as pallet_utils::Trait>::Currency::transfer(
-				&subscription.created.account,
-				&recipient.unwrap(),
-				plan.price,
-				ExistenceRequirement::KeepAlive
-			).is_err();
+			let plan = Self::require_plan(subscription.plan_id)?;
+			let subscriber = subscription.created.account.clone();
+
+			// A credit left after switching plans is spent first.
+			let amount_to_pay = plan.price.saturating_sub(subscription.credit);
+
+			let is_paid = amount_to_pay.is_zero() || plan.try_get_recipient().map_or(false, |recipient| {
+				<T as pallet_utils::Trait>::Currency::transfer(
+					&subscriber,
+					&recipient,
+					amount_to_pay,
+					ExistenceRequirement::KeepAlive
+				).is_ok()
+			});
 
-			SubscriptionById::<T>::insert(subscription_id, subscription);
+			if is_paid {
+				subscription.credit = subscription.credit.saturating_sub(plan.price);
+				subscription.failed_payments = 0;
+				subscription.period_started_at = <system::Module<T>>::block_number();
+				subscription.paid_for_period = plan.price;
+				Self::schedule_recurring_subscription_payment(&mut subscription, plan.period.clone())?;
+				Self::grant_plan_role(&subscriber, &plan);
+				SubscriptionById::<T>::insert(subscription_id, subscription);
+
+				Self::deposit_event(RawEvent::SubscriptionPaid(subscriber, subscription_id));
+			} else if subscription.failed_payments < T::MaxPaymentRetries::get() {
+				subscription.failed_payments = subscription.failed_payments.saturating_add(1);
+				Self::schedule_subscription_payment_in(&mut subscription, T::PaymentRetryIntervalInBlocks::get())?;
+				Self::revoke_plan_role(&subscriber, &plan, subscription_id);
+				SubscriptionById::<T>::insert(subscription_id, subscription);
+
+				Self::deposit_event(RawEvent::SubscriptionPaymentFailed(subscriber, subscription_id));
+			} else {
+				Self::do_unsubscribe(subscriber.clone(), &mut subscription)?;
+
+				Self::deposit_event(RawEvent::SubscriptionDeactivated(subscriber, subscription_id));
+			}
 
 			Ok(())
 		}
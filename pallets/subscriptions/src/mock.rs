@@ -1,32 +1,51 @@
 // Creating mock runtime here
 
-use crate::{Module, Trait};
+use crate::{Module, Trait, SubscriptionPeriod, SubscriptionPlanId, SubscriptionId};
 use sp_core::H256;
-use frame_support::{impl_outer_origin, parameter_types, weights::Weight};
+use sp_io::TestExternalities;
 use sp_runtime::{
 	traits::{BlakeTwo256, IdentityLookup}, testing::Header, Perbill,
 };
-use frame_system as system;
+use frame_support::{
+	impl_outer_origin, impl_outer_dispatch, parameter_types, assert_ok,
+	weights::Weight,
+	dispatch::DispatchResult,
+	traits::OnInitialize,
+};
+use frame_system::{self as system, EnsureRoot};
+
+use pallet_permissions::{SpacePermission, default_permissions::DefaultSpacePermissions};
+use pallet_roles::RoleId;
+use pallet_spaces::RESERVED_SPACE_COUNT;
+use pallet_utils::{Content, SpaceId, User};
+
+use crate as subscriptions;
 
 impl_outer_origin! {
 	pub enum Origin for Test {}
 }
 
-// For testing the pallet, we construct most of a mock runtime. This means
-// first constructing a configuration type (`Test`) which `impl`s each of the
-// configuration traits of pallets we want to use.
+impl_outer_dispatch! {
+	pub enum Call for Test where origin: Origin {
+		frame_system::System,
+		subscriptions::Subscriptions,
+	}
+}
+
 #[derive(Clone, Eq, PartialEq)]
 pub struct Test;
+
 parameter_types! {
 	pub const BlockHashCount: u64 = 250;
 	pub const MaximumBlockWeight: Weight = 1024;
 	pub const MaximumBlockLength: u32 = 2 * 1024;
 	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
 }
+
 impl system::Trait for Test {
 	type BaseCallFilter = ();
 	type Origin = Origin;
-	type Call = ();
+	type Call = Call;
 	type Index = u64;
 	type BlockNumber = u64;
 	type Hash = H256;
@@ -45,18 +64,317 @@ impl system::Trait for Test {
 	type AvailableBlockRatio = AvailableBlockRatio;
 	type Version = ();
 	type PalletInfo = ();
-	type AccountData = ();
+	type AccountData = pallet_balances::AccountData<u64>;
 	type OnNewAccount = ();
 	type OnKilledAccount = ();
 	type SystemWeightInfo = ();
 }
+
+parameter_types! {
+	pub const MinimumPeriod: u64 = 5;
+}
+
+impl pallet_timestamp::Trait for Test {
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = MinimumPeriod;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+
+impl pallet_balances::Trait for Test {
+	type Balance = u64;
+	type DustRemoval = ();
+	type Event = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+	type MaxLocks = ();
+}
+
+parameter_types! {
+	pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) * MaximumBlockWeight::get();
+	pub const MaxScheduledPerBlock: u32 = 50;
+}
+
+impl pallet_scheduler::Trait for Test {
+	type Event = ();
+	type Origin = Origin;
+	type PalletsOrigin = OriginCaller;
+	type Call = Call;
+	type MaximumWeight = MaximumSchedulerWeight;
+	type ScheduleOrigin = EnsureRoot<AccountId>;
+	type MaxScheduledPerBlock = MaxScheduledPerBlock;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const MinHandleLen: u32 = 5;
+	pub const MaxHandleLen: u32 = 50;
+}
+
+impl pallet_utils::Trait for Test {
+	type Event = ();
+	type Currency = Balances;
+	type MinHandleLen = MinHandleLen;
+	type MaxHandleLen = MaxHandleLen;
+}
+
+impl pallet_permissions::Trait for Test {
+	type DefaultSpacePermissions = DefaultSpacePermissions;
+}
+
+parameter_types! {
+	pub const MaxItemsToCleanUpPerBlock: u16 = 100;
+}
+
+impl pallet_spaces::Trait for Test {
+	type Event = ();
+	type Currency = Balances;
+	type Roles = Roles;
+	type SpaceFollows = SpaceFollows;
+	type BeforeSpaceCreated = SpaceFollows;
+	type AfterSpaceUpdated = ();
+	type AfterSpaceOwnerChanged = ();
+	type IsAccountBlocked = ();
+	type IsContentBlocked = ();
+	type HandleDeposit = ();
+	type SpaceCleaner = ();
+	type MaxItemsToCleanUpPerBlock = MaxItemsToCleanUpPerBlock;
+}
+
+impl pallet_space_follows::Trait for Test {
+	type Event = ();
+	type BeforeSpaceFollowed = ();
+	type BeforeSpaceUnfollowed = ();
+}
+
+impl pallet_profiles::Trait for Test {
+	type Event = ();
+	type AfterProfileUpdated = ();
+}
+
+parameter_types! {
+	pub const MaxUsersToProcessPerDeleteRole: u16 = 40;
+}
+
+impl pallet_roles::Trait for Test {
+	type Event = ();
+	type MaxUsersToProcessPerDeleteRole = MaxUsersToProcessPerDeleteRole;
+	type Spaces = Spaces;
+	type SpaceFollows = SpaceFollows;
+	type IsAccountBlocked = ();
+	type IsContentBlocked = ();
+}
+
+parameter_types! {
+	pub const DailyPeriodInBlocks: BlockNumber = 100;
+	pub const WeeklyPeriodInBlocks: BlockNumber = 700;
+	pub const MonthlyPeriodInBlocks: BlockNumber = 3_000;
+	pub const QuarterlyPeriodInBlocks: BlockNumber = 9_000;
+	pub const YearlyPeriodInBlocks: BlockNumber = 36_500;
+	pub const MinCustomPeriodInBlocks: BlockNumber = 10;
+	pub const MaxCustomPeriodInBlocks: BlockNumber = 36_500;
+	pub const PaymentRetryIntervalInBlocks: BlockNumber = 2;
+	pub const MaxPaymentRetries: u16 = 3;
+}
+
 impl Trait for Test {
 	type Event = ();
+	type Subscription = Call;
+	type PalletsOrigin = OriginCaller;
+	type Scheduler = Scheduler;
+	type DailyPeriodInBlocks = DailyPeriodInBlocks;
+	type WeeklyPeriodInBlocks = WeeklyPeriodInBlocks;
+	type MonthlyPeriodInBlocks = MonthlyPeriodInBlocks;
+	type QuarterlyPeriodInBlocks = QuarterlyPeriodInBlocks;
+	type YearlyPeriodInBlocks = YearlyPeriodInBlocks;
+	type MinCustomPeriodInBlocks = MinCustomPeriodInBlocks;
+	type MaxCustomPeriodInBlocks = MaxCustomPeriodInBlocks;
+	type PaymentRetryIntervalInBlocks = PaymentRetryIntervalInBlocks;
+	type MaxPaymentRetries = MaxPaymentRetries;
+}
+
+pub(crate) type System = system::Module<Test>;
+pub(crate) type Balances = pallet_balances::Module<Test>;
+pub(crate) type Scheduler = pallet_scheduler::Module<Test>;
+pub(crate) type Spaces = pallet_spaces::Module<Test>;
+pub(crate) type SpaceFollows = pallet_space_follows::Module<Test>;
+pub(crate) type Roles = pallet_roles::Module<Test>;
+pub(crate) type Subscriptions = Module<Test>;
+
+pub(crate) type AccountId = u64;
+pub(crate) type BlockNumber = u64;
+pub(crate) type Balance = u64;
+
+pub struct ExtBuilder;
+
+impl ExtBuilder {
+	fn build_with_balances() -> TestExternalities {
+		let mut storage = system::GenesisConfig::default()
+			.build_storage::<Test>()
+			.unwrap();
+
+		let _ = pallet_balances::GenesisConfig::<Test> {
+			balances: vec![
+				(ACCOUNT_SPACE_OWNER, INITIAL_BALANCE),
+				(ACCOUNT_SUBSCRIBER, INITIAL_BALANCE),
+			],
+		}.assimilate_storage(&mut storage);
+
+		let mut ext = TestExternalities::from(storage);
+		ext.execute_with(|| System::set_block_number(1));
+
+		ext
+	}
+
+	pub fn build_with_plan() -> TestExternalities {
+		let mut ext = Self::build_with_balances();
+		ext.execute_with(|| {
+			assert_ok!(Spaces::create_space(
+				Origin::signed(ACCOUNT_SPACE_OWNER),
+				None,
+				None,
+				Content::None,
+				None
+			));
+			assert_ok!(Roles::create_role(
+				Origin::signed(ACCOUNT_SPACE_OWNER),
+				SPACE1,
+				None,
+				Content::None,
+				vec![SpacePermission::CreatePosts]
+			));
+			assert_ok!(_create_default_plan());
+		});
+
+		ext
+	}
+
+	pub fn build_with_subscription() -> TestExternalities {
+		let mut ext = Self::build_with_plan();
+		ext.execute_with(|| {
+			assert_ok!(_subscribe_to_default_plan());
+		});
+
+		ext
+	}
+}
+
+pub(crate) const ACCOUNT_SPACE_OWNER: AccountId = 1;
+pub(crate) const ACCOUNT_SUBSCRIBER: AccountId = 2;
+pub(crate) const ACCOUNT_NOT_SUBSCRIBER: AccountId = 3;
+
+pub(crate) const INITIAL_BALANCE: Balance = 100;
+pub(crate) const PLAN_PRICE: Balance = 20;
+pub(crate) const CUSTOM_PERIOD: BlockNumber = 10;
+pub(crate) const TRIAL_PERIOD: BlockNumber = 5;
+
+pub(crate) const SPACE1: SpaceId = RESERVED_SPACE_COUNT + 1;
+pub(crate) const PLAN1: SubscriptionPlanId = 1;
+pub(crate) const PLAN2: SubscriptionPlanId = 2;
+pub(crate) const SUBSCRIPTION1: SubscriptionId = 1;
+pub(crate) const SUBSCRIPTION2: SubscriptionId = 2;
+pub(crate) const ROLE1: RoleId = 1;
+
+pub(crate) fn _create_default_plan() -> DispatchResult {
+	_create_plan(None, None)
+}
+
+pub(crate) fn _create_plan(
+	origin: Option<Origin>,
+	period: Option<SubscriptionPeriod<BlockNumber>>,
+) -> DispatchResult {
+	_create_plan_with_role(origin, period, Some(ROLE1))
+}
+
+pub(crate) fn _create_plan_with_role(
+	origin: Option<Origin>,
+	period: Option<SubscriptionPeriod<BlockNumber>>,
+	role_id: Option<RoleId>,
+) -> DispatchResult {
+	_create_custom_plan(origin, None, period, role_id, None)
+}
+
+/// Create a plan in `SPACE1` with a trial.
+pub(crate) fn _create_plan_with_trial(trial_period: BlockNumber) -> DispatchResult {
+	_create_custom_plan(None, None, None, None, Some(trial_period))
+}
+
+/// Create a plan in `SPACE1` with a custom price.
+pub(crate) fn _create_plan_with_price(price: Balance) -> DispatchResult {
+	_create_custom_plan(None, Some(price), None, None, None)
+}
+
+pub(crate) fn _create_custom_plan(
+	origin: Option<Origin>,
+	price: Option<Balance>,
+	period: Option<SubscriptionPeriod<BlockNumber>>,
+	role_id: Option<RoleId>,
+	trial_period: Option<BlockNumber>,
+) -> DispatchResult {
+	Subscriptions::create_plan(
+		origin.unwrap_or_else(|| Origin::signed(ACCOUNT_SPACE_OWNER)),
+		SPACE1,
+		None,
+		price.unwrap_or(PLAN_PRICE),
+		period.unwrap_or(SubscriptionPeriod::Custom(CUSTOM_PERIOD)),
+		Content::None,
+		role_id,
+		trial_period,
+	)
+}
+
+pub(crate) fn _delete_default_plan() -> DispatchResult {
+	Subscriptions::delete_plan(Origin::signed(ACCOUNT_SPACE_OWNER), PLAN1)
+}
+
+pub(crate) fn _subscribe_to_default_plan() -> DispatchResult {
+	_subscribe(None, None)
+}
+
+pub(crate) fn _subscribe(origin: Option<Origin>, plan_id: Option<SubscriptionPlanId>) -> DispatchResult {
+	Subscriptions::subscribe(
+		origin.unwrap_or_else(|| Origin::signed(ACCOUNT_SUBSCRIBER)),
+		plan_id.unwrap_or(PLAN1),
+		None,
+	)
+}
+
+pub(crate) fn _switch_default_subscription_plan(new_plan_id: SubscriptionPlanId) -> DispatchResult {
+	Subscriptions::switch_subscription_plan(Origin::signed(ACCOUNT_SUBSCRIBER), SUBSCRIPTION1, new_plan_id)
+}
+
+pub(crate) fn _refund_default_subscription(origin: Option<Origin>) -> DispatchResult {
+	Subscriptions::refund_subscription(
+		origin.unwrap_or_else(|| Origin::signed(ACCOUNT_SPACE_OWNER)),
+		SUBSCRIPTION1,
+	)
+}
+
+pub(crate) fn _unsubscribe(origin: Option<Origin>) -> DispatchResult {
+	Subscriptions::unsubscribe(
+		origin.unwrap_or_else(|| Origin::signed(ACCOUNT_SUBSCRIBER)),
+		SUBSCRIPTION1,
+	)
+}
+
+/// Move to the block `n`, executing all payments scheduled on the way.
+pub(crate) fn run_to_block(n: BlockNumber) {
+	while System::block_number() < n {
+		let next_block = System::block_number() + 1;
+		System::set_block_number(next_block);
+		Scheduler::on_initialize(next_block);
+	}
+}
+
+pub(crate) fn subscriber_has_role() -> bool {
+	Roles::role_ids_by_user_in_space(User::Account(ACCOUNT_SUBSCRIBER), SPACE1).contains(&ROLE1)
 }
-pub type TemplateModule = Module<Test>;
 
-// This function basically just builds a genesis storage key/value store according to
-// our desired mockup.
-pub fn new_test_ext() -> sp_io::TestExternalities {
-	system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+pub(crate) fn next_payment_at() -> BlockNumber {
+	Subscriptions::subscription_by_id(SUBSCRIPTION1).unwrap().next_payment_at
 }
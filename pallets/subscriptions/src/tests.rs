@@ -1,26 +1,506 @@
-// Tests to be written here
+use crate::{Error, mock::*, SubscriptionPeriod};
+use df_traits::{SpaceCleaner, subscriptions::SubscriptionPlansProvider};
+use frame_support::{assert_ok, assert_noop, traits::Currency};
+use pallet_permissions::SpacePermission;
+use pallet_utils::{Content, User};
+use sp_runtime::DispatchError::BadOrigin;
 
-use crate::{Error, mock::*};
-use frame_support::{assert_ok, assert_noop};
+// Create plan
+// ----------------------------------------------------------------------------
 
 #[test]
-fn it_works_for_default_value() {
-	new_test_ext().execute_with(|| {
-		// Just a dummy test for the dummy function `do_something`
-		// calling the `do_something` function with a value 42
-		assert_ok!(TemplateModule::do_something(Origin::signed(1), 42));
-		// asserting that the stored value is equal to what we stored
-		assert_eq!(TemplateModule::something(), Some(42));
+fn create_plan_should_work_with_custom_period() {
+	ExtBuilder::build_with_plan().execute_with(|| {
+		let plan = Subscriptions::plan_by_id(PLAN1).unwrap();
+		assert_eq!(plan.space_id, SPACE1);
+		assert_eq!(plan.price, PLAN_PRICE);
+		assert_eq!(plan.period, SubscriptionPeriod::Custom(CUSTOM_PERIOD));
+		assert_eq!(plan.role_id, Some(ROLE1));
+
+		assert_eq!(Subscriptions::plan_ids_by_space(SPACE1), vec![PLAN1]);
+	});
+}
+
+#[test]
+fn create_plan_should_fail_when_custom_period_is_too_short() {
+	ExtBuilder::build_with_plan().execute_with(|| {
+		let period = SubscriptionPeriod::Custom(MinCustomPeriodInBlocks::get() - 1);
+		assert_noop!(_create_plan(None, Some(period)), Error::<Test>::CustomPeriodTooShort);
+	});
+}
+
+#[test]
+fn create_plan_should_fail_when_custom_period_is_too_long() {
+	ExtBuilder::build_with_plan().execute_with(|| {
+		let period = SubscriptionPeriod::Custom(MaxCustomPeriodInBlocks::get() + 1);
+		assert_noop!(_create_plan(None, Some(period)), Error::<Test>::CustomPeriodTooLong);
+	});
+}
+
+#[test]
+fn create_plan_should_fail_when_role_not_found() {
+	ExtBuilder::build_with_plan().execute_with(|| {
+		assert_noop!(
+			_create_plan_with_role(None, None, Some(ROLE1 + 1)),
+			pallet_roles::Error::<Test>::RoleNotFound
+		);
+	});
+}
+
+#[test]
+fn create_plan_should_fail_when_role_is_from_another_space() {
+	ExtBuilder::build_with_plan().execute_with(|| {
+		assert_ok!(Spaces::create_space(Origin::signed(ACCOUNT_SPACE_OWNER), None, None, Content::None, None));
+		assert_ok!(Roles::create_role(
+			Origin::signed(ACCOUNT_SPACE_OWNER),
+			SPACE1 + 1,
+			None,
+			Content::None,
+			vec![SpacePermission::CreatePosts]
+		)); // ROLE1 + 1 in another space
+
+		assert_noop!(
+			_create_plan_with_role(None, None, Some(ROLE1 + 1)),
+			Error::<Test>::RoleNotFoundInSpace
+		);
+	});
+}
+
+#[test]
+fn create_plan_with_role_should_fail_when_account_cannot_manage_roles() {
+	ExtBuilder::build_with_plan().execute_with(|| {
+		assert_ok!(Roles::create_role(
+			Origin::signed(ACCOUNT_SPACE_OWNER),
+			SPACE1,
+			None,
+			Content::None,
+			vec![SpacePermission::UpdateSpaceSettings]
+		)); // ROLE1 + 1
+		assert_ok!(Roles::grant_role(
+			Origin::signed(ACCOUNT_SPACE_OWNER),
+			ROLE1 + 1,
+			vec![User::Account(ACCOUNT_NOT_SUBSCRIBER)]
+		));
+
+		assert_noop!(
+			_create_plan_with_role(Some(Origin::signed(ACCOUNT_NOT_SUBSCRIBER)), None, Some(ROLE1)),
+			pallet_roles::Error::<Test>::NoPermissionToManageRoles
+		);
+		assert_ok!(_create_plan_with_role(Some(Origin::signed(ACCOUNT_NOT_SUBSCRIBER)), None, None));
+	});
+}
+
+// Subscribe
+// ----------------------------------------------------------------------------
+
+#[test]
+fn subscribe_should_work() {
+	ExtBuilder::build_with_subscription().execute_with(|| {
+		let subscription = Subscriptions::subscription_by_id(SUBSCRIPTION1).unwrap();
+		assert!(subscription.is_active);
+		assert_eq!(subscription.plan_id, PLAN1);
+		assert_eq!(subscription.next_payment_at, 1 + CUSTOM_PERIOD);
+
+		assert_eq!(Subscriptions::next_subscription_id(), SUBSCRIPTION1 + 1);
+		assert_eq!(Subscriptions::subscription_ids_by_patron(ACCOUNT_SUBSCRIBER), vec![SUBSCRIPTION1]);
+		assert_eq!(Subscriptions::subscription_ids_by_space(SPACE1), vec![SUBSCRIPTION1]);
+
+		assert_eq!(Balances::free_balance(ACCOUNT_SUBSCRIBER), INITIAL_BALANCE - PLAN_PRICE);
+		assert_eq!(Balances::free_balance(ACCOUNT_SPACE_OWNER), INITIAL_BALANCE + PLAN_PRICE);
+
+		assert!(subscriber_has_role());
+		assert_eq!(Roles::users_by_role_id(ROLE1), vec![User::Account(ACCOUNT_SUBSCRIBER)]);
+	});
+}
+
+#[test]
+fn subscribe_should_fail_when_already_subscribed() {
+	ExtBuilder::build_with_subscription().execute_with(|| {
+		assert_noop!(_subscribe_to_default_plan(), Error::<Test>::AlreadySubscribed);
+	});
+}
+
+#[test]
+fn subscribe_should_fail_when_plan_is_not_active() {
+	ExtBuilder::build_with_plan().execute_with(|| {
+		assert_ok!(_delete_default_plan());
+		assert_noop!(_subscribe_to_default_plan(), Error::<Test>::PlanIsNotActive);
+	});
+}
+
+// Recurring payments
+// ----------------------------------------------------------------------------
+
+#[test]
+fn recurring_payment_should_be_charged_every_period() {
+	ExtBuilder::build_with_subscription().execute_with(|| {
+		run_to_block(CUSTOM_PERIOD);
+		assert_eq!(Balances::free_balance(ACCOUNT_SUBSCRIBER), INITIAL_BALANCE - PLAN_PRICE);
+
+		run_to_block(1 + CUSTOM_PERIOD);
+		assert_eq!(Balances::free_balance(ACCOUNT_SUBSCRIBER), INITIAL_BALANCE - 2 * PLAN_PRICE);
+		assert_eq!(next_payment_at(), 1 + 2 * CUSTOM_PERIOD);
+
+		run_to_block(1 + 2 * CUSTOM_PERIOD);
+		assert_eq!(Balances::free_balance(ACCOUNT_SUBSCRIBER), INITIAL_BALANCE - 3 * PLAN_PRICE);
+		assert_eq!(Balances::free_balance(ACCOUNT_SPACE_OWNER), INITIAL_BALANCE + 3 * PLAN_PRICE);
+	});
+}
+
+#[test]
+fn recurring_payment_should_stop_after_unsubscribe() {
+	ExtBuilder::build_with_subscription().execute_with(|| {
+		assert_ok!(_unsubscribe(None));
+
+		run_to_block(1 + 2 * CUSTOM_PERIOD);
+
+		let subscription = Subscriptions::subscription_by_id(SUBSCRIPTION1).unwrap();
+		assert!(!subscription.is_active);
+		assert_eq!(Balances::free_balance(ACCOUNT_SUBSCRIBER), INITIAL_BALANCE - PLAN_PRICE);
+		assert!(Subscriptions::subscription_ids_by_patron(ACCOUNT_SUBSCRIBER).is_empty());
+		assert!(!subscriber_has_role());
+	});
+}
+
+#[test]
+fn recurring_payment_should_stop_after_plan_deleted() {
+	ExtBuilder::build_with_subscription().execute_with(|| {
+		assert_ok!(_delete_default_plan());
+
+		run_to_block(1 + 2 * CUSTOM_PERIOD);
+
+		let subscription = Subscriptions::subscription_by_id(SUBSCRIPTION1).unwrap();
+		assert!(!subscription.is_active);
+		assert_eq!(Balances::free_balance(ACCOUNT_SUBSCRIBER), INITIAL_BALANCE - PLAN_PRICE);
+		assert!(!subscriber_has_role());
+	});
+}
+
+#[test]
+fn recurring_payment_should_stop_after_space_cleaned_up() {
+	ExtBuilder::build_with_subscription().execute_with(|| {
+		assert_eq!(Subscriptions::clean_up_space(SPACE1, 10), 2);
+
+		run_to_block(1 + 2 * CUSTOM_PERIOD);
+
+		let subscription = Subscriptions::subscription_by_id(SUBSCRIPTION1).unwrap();
+		assert!(!subscription.is_active);
+		assert_eq!(Balances::free_balance(ACCOUNT_SUBSCRIBER), INITIAL_BALANCE - PLAN_PRICE);
+		assert!(Subscriptions::subscription_ids_by_patron(ACCOUNT_SUBSCRIBER).is_empty());
+		assert!(Subscriptions::subscription_ids_by_space(SPACE1).is_empty());
+		assert!(Subscriptions::plan_ids_by_space(SPACE1).is_empty());
+		assert!(!Subscriptions::is_active_plan_in_space(PLAN1, SPACE1));
+	});
+}
+
+#[test]
+fn failed_payment_should_be_retried_during_grace_period() {
+	ExtBuilder::build_with_subscription().execute_with(|| {
+		// Leave the subscriber with less than a plan price.
+		let _ = Balances::make_free_balance_be(&ACCOUNT_SUBSCRIBER, PLAN_PRICE - 1);
+
+		run_to_block(1 + CUSTOM_PERIOD);
+
+		let subscription = Subscriptions::subscription_by_id(SUBSCRIPTION1).unwrap();
+		assert!(subscription.is_active);
+		assert_eq!(subscription.failed_payments, 1);
+		assert_eq!(subscription.next_payment_at, 1 + CUSTOM_PERIOD + PaymentRetryIntervalInBlocks::get());
+		assert!(!subscriber_has_role());
+
+		// The subscriber tops up their balance before the next retry.
+		let _ = Balances::make_free_balance_be(&ACCOUNT_SUBSCRIBER, INITIAL_BALANCE);
+		run_to_block(next_payment_at());
+
+		let subscription = Subscriptions::subscription_by_id(SUBSCRIPTION1).unwrap();
+		assert!(subscription.is_active);
+		assert_eq!(subscription.failed_payments, 0);
+		assert_eq!(Balances::free_balance(ACCOUNT_SUBSCRIBER), INITIAL_BALANCE - PLAN_PRICE);
+		assert!(subscriber_has_role());
+		assert_eq!(
+			subscription.next_payment_at,
+			1 + CUSTOM_PERIOD + PaymentRetryIntervalInBlocks::get() + CUSTOM_PERIOD
+		);
+	});
+}
+
+#[test]
+fn subscription_should_be_deactivated_when_all_retries_failed() {
+	ExtBuilder::build_with_subscription().execute_with(|| {
+		let _ = Balances::make_free_balance_be(&ACCOUNT_SUBSCRIBER, PLAN_PRICE - 1);
+
+		let grace_period = PaymentRetryIntervalInBlocks::get() * MaxPaymentRetries::get() as BlockNumber;
+		run_to_block(1 + CUSTOM_PERIOD + grace_period);
+
+		let subscription = Subscriptions::subscription_by_id(SUBSCRIPTION1).unwrap();
+		assert!(!subscription.is_active);
+		assert_eq!(subscription.failed_payments, MaxPaymentRetries::get());
+		assert!(Subscriptions::subscription_ids_by_patron(ACCOUNT_SUBSCRIBER).is_empty());
+		assert!(Subscriptions::subscription_ids_by_space(SPACE1).is_empty());
+		assert!(!subscriber_has_role());
+
+		// No more payments should be charged.
+		let _ = Balances::make_free_balance_be(&ACCOUNT_SUBSCRIBER, INITIAL_BALANCE);
+		run_to_block(1 + 3 * CUSTOM_PERIOD + grace_period);
+		assert_eq!(Balances::free_balance(ACCOUNT_SUBSCRIBER), INITIAL_BALANCE);
+	});
+}
+
+#[test]
+fn process_subscription_payment_should_fail_when_origin_is_not_root() {
+	ExtBuilder::build_with_subscription().execute_with(|| {
+		assert_noop!(
+			Subscriptions::process_subscription_payment(Origin::signed(ACCOUNT_NOT_SUBSCRIBER), SUBSCRIPTION1),
+			BadOrigin
+		);
+	});
+}
+
+// Trial
+// ----------------------------------------------------------------------------
+
+#[test]
+fn subscribe_should_not_charge_during_trial() {
+	ExtBuilder::build_with_plan().execute_with(|| {
+		assert_ok!(_create_plan_with_trial(TRIAL_PERIOD));
+		assert_ok!(_subscribe(None, Some(PLAN2)));
+
+		let subscription = Subscriptions::subscription_by_id(SUBSCRIPTION1).unwrap();
+		assert!(subscription.is_active);
+		assert_eq!(subscription.next_payment_at, 1 + TRIAL_PERIOD);
+		assert_eq!(Balances::free_balance(ACCOUNT_SUBSCRIBER), INITIAL_BALANCE);
+		assert!(Subscriptions::is_trial_used(ACCOUNT_SUBSCRIBER, PLAN2));
+
+		// The first payment is charged when the trial ends.
+		run_to_block(1 + TRIAL_PERIOD);
+		assert_eq!(Balances::free_balance(ACCOUNT_SUBSCRIBER), INITIAL_BALANCE - PLAN_PRICE);
+		assert_eq!(next_payment_at(), 1 + TRIAL_PERIOD + CUSTOM_PERIOD);
+	});
+}
+
+#[test]
+fn subscribe_should_charge_immediately_when_trial_was_already_used() {
+	ExtBuilder::build_with_plan().execute_with(|| {
+		assert_ok!(_create_plan_with_trial(TRIAL_PERIOD));
+		assert_ok!(_subscribe(None, Some(PLAN2)));
+		assert_ok!(_unsubscribe(None));
+
+		assert_ok!(_subscribe(None, Some(PLAN2)));
+		assert_eq!(Balances::free_balance(ACCOUNT_SUBSCRIBER), INITIAL_BALANCE - PLAN_PRICE);
+		assert_eq!(
+			Subscriptions::subscription_by_id(SUBSCRIPTION2).unwrap().next_payment_at,
+			1 + CUSTOM_PERIOD
+		);
+	});
+}
+
+#[test]
+fn create_plan_should_fail_when_trial_period_is_zero() {
+	ExtBuilder::build_with_plan().execute_with(|| {
+		assert_noop!(_create_plan_with_trial(0), Error::<Test>::InvalidTrialPeriod);
+	});
+}
+
+// Switch plan
+// ----------------------------------------------------------------------------
+
+#[test]
+fn switch_subscription_plan_should_charge_price_difference() {
+	ExtBuilder::build_with_subscription().execute_with(|| {
+		assert_ok!(_create_plan_with_price(2 * PLAN_PRICE));
+
+		// Switch in the middle of the period.
+		run_to_block(1 + CUSTOM_PERIOD / 2);
+		assert_ok!(_switch_default_subscription_plan(PLAN2));
+
+		// Half of the new price minus half of the old price.
+		let paid = PLAN_PRICE + (2 * PLAN_PRICE - PLAN_PRICE) / 2;
+		assert_eq!(Balances::free_balance(ACCOUNT_SUBSCRIBER), INITIAL_BALANCE - paid);
+
+		let subscription = Subscriptions::subscription_by_id(SUBSCRIPTION1).unwrap();
+		assert_eq!(subscription.plan_id, PLAN2);
+		assert_eq!(subscription.next_payment_at, 1 + CUSTOM_PERIOD);
+		assert_eq!(subscription.credit, 0);
+
+		// The next payment is charged for the new plan.
+		run_to_block(1 + CUSTOM_PERIOD);
+		assert_eq!(Balances::free_balance(ACCOUNT_SUBSCRIBER), INITIAL_BALANCE - paid - 2 * PLAN_PRICE);
+	});
+}
+
+#[test]
+fn switch_subscription_plan_should_credit_price_difference() {
+	ExtBuilder::build_with_subscription().execute_with(|| {
+		assert_ok!(_create_plan_with_price(PLAN_PRICE / 2));
+
+		run_to_block(1 + CUSTOM_PERIOD / 2);
+		assert_ok!(_switch_default_subscription_plan(PLAN2));
+
+		// Nothing is charged, the difference is kept as a credit.
+		let credit = PLAN_PRICE / 2 - PLAN_PRICE / 4;
+		assert_eq!(Balances::free_balance(ACCOUNT_SUBSCRIBER), INITIAL_BALANCE - PLAN_PRICE);
+		assert_eq!(Subscriptions::subscription_by_id(SUBSCRIPTION1).unwrap().credit, credit);
+
+		// The credit is spent on the next payment.
+		run_to_block(1 + CUSTOM_PERIOD);
+		assert_eq!(
+			Balances::free_balance(ACCOUNT_SUBSCRIBER),
+			INITIAL_BALANCE - PLAN_PRICE - (PLAN_PRICE / 2 - credit)
+		);
+		assert_eq!(Subscriptions::subscription_by_id(SUBSCRIPTION1).unwrap().credit, 0);
+	});
+}
+
+#[test]
+fn switch_subscription_plan_should_charge_rest_of_longer_period_at_shorter_plan_rate() {
+	ExtBuilder::build_with_plan().execute_with(|| {
+		let long_period = 4 * CUSTOM_PERIOD;
+		let long_plan_price = 2 * PLAN_PRICE;
+		assert_ok!(_create_custom_plan(
+			None,
+			Some(long_plan_price),
+			Some(SubscriptionPeriod::Custom(long_period)),
+			None,
+			None
+		));
+		assert_ok!(_subscribe(None, Some(PLAN2)));
+
+		// Switch to the shorter plan when 3/4 of the long period are left.
+		run_to_block(1 + CUSTOM_PERIOD);
+		assert_ok!(_switch_default_subscription_plan(PLAN1));
+
+		// The rest of the period is 3 periods of the short plan, and 3/4 of the long plan are credited.
+		let charge = 3 * PLAN_PRICE;
+		let credit = long_plan_price * 3 / 4;
+		let paid = long_plan_price + (charge - credit);
+		assert_eq!(Balances::free_balance(ACCOUNT_SUBSCRIBER), INITIAL_BALANCE - paid);
+
+		let subscription = Subscriptions::subscription_by_id(SUBSCRIPTION1).unwrap();
+		assert_eq!(subscription.plan_id, PLAN1);
+		assert_eq!(subscription.next_payment_at, 1 + long_period);
+		assert_eq!(subscription.paid_for_period, charge);
+		assert_eq!(subscription.credit, 0);
+
+		// The next payment is charged for the short plan at the end of the long period.
+		run_to_block(long_period);
+		assert_eq!(Balances::free_balance(ACCOUNT_SUBSCRIBER), INITIAL_BALANCE - paid);
+		run_to_block(1 + long_period);
+		assert_eq!(Balances::free_balance(ACCOUNT_SUBSCRIBER), INITIAL_BALANCE - paid - PLAN_PRICE);
+	});
+}
+
+#[test]
+fn switch_subscription_plan_should_fail_when_plan_is_in_another_space() {
+	ExtBuilder::build_with_subscription().execute_with(|| {
+		assert_ok!(Spaces::create_space(Origin::signed(ACCOUNT_SPACE_OWNER), None, None, Content::None, None));
+		assert_ok!(Subscriptions::create_plan(
+			Origin::signed(ACCOUNT_SPACE_OWNER),
+			SPACE1 + 1,
+			None,
+			PLAN_PRICE,
+			SubscriptionPeriod::Custom(CUSTOM_PERIOD),
+			Content::None,
+			None,
+			None
+		));
+
+		assert_noop!(
+			_switch_default_subscription_plan(PLAN2),
+			Error::<Test>::CannotSwitchToPlanInAnotherSpace
+		);
+	});
+}
+
+#[test]
+fn switch_subscription_plan_should_fail_when_switching_to_same_plan() {
+	ExtBuilder::build_with_subscription().execute_with(|| {
+		assert_noop!(_switch_default_subscription_plan(PLAN1), Error::<Test>::AlreadySubscribed);
+	});
+}
+
+// Refund
+// ----------------------------------------------------------------------------
+
+#[test]
+fn refund_subscription_should_refund_unused_part_of_period() {
+	ExtBuilder::build_with_subscription().execute_with(|| {
+		run_to_block(1 + CUSTOM_PERIOD / 2);
+		assert_ok!(_refund_default_subscription(None));
+
+		let refund = PLAN_PRICE / 2;
+		assert_eq!(Balances::free_balance(ACCOUNT_SUBSCRIBER), INITIAL_BALANCE - PLAN_PRICE + refund);
+		assert_eq!(Balances::free_balance(ACCOUNT_SPACE_OWNER), INITIAL_BALANCE + PLAN_PRICE - refund);
+
+		let subscription = Subscriptions::subscription_by_id(SUBSCRIPTION1).unwrap();
+		assert!(!subscription.is_active);
+		assert!(!subscriber_has_role());
+
+		// No more payments should be charged.
+		run_to_block(1 + 2 * CUSTOM_PERIOD);
+		assert_eq!(Balances::free_balance(ACCOUNT_SUBSCRIBER), INITIAL_BALANCE - PLAN_PRICE + refund);
+	});
+}
+
+#[test]
+fn refund_subscription_should_refund_credit() {
+	ExtBuilder::build_with_subscription().execute_with(|| {
+		assert_ok!(_create_plan_with_price(PLAN_PRICE / 2));
+
+		run_to_block(1 + CUSTOM_PERIOD / 2);
+		assert_ok!(_switch_default_subscription_plan(PLAN2));
+		let credit = Subscriptions::subscription_by_id(SUBSCRIPTION1).unwrap().credit;
+		assert!(credit > 0);
+
+		assert_ok!(_refund_default_subscription(None));
+
+		// The rest of the period of the new plan plus the credit left from the old one.
+		let refund = PLAN_PRICE / 4 + credit;
+		assert_eq!(Balances::free_balance(ACCOUNT_SUBSCRIBER), INITIAL_BALANCE - PLAN_PRICE + refund);
+		assert_eq!(Subscriptions::subscription_by_id(SUBSCRIPTION1).unwrap().credit, 0);
 	});
 }
 
 #[test]
-fn correct_error_for_none_value() {
-	new_test_ext().execute_with(|| {
-		// Ensure the correct error is thrown on None value
+fn refund_subscription_should_fail_when_not_space_owner() {
+	ExtBuilder::build_with_subscription().execute_with(|| {
 		assert_noop!(
-			TemplateModule::cause_error(Origin::signed(1)),
-			Error::<Test>::NoneValue
+			_refund_default_subscription(Some(Origin::signed(ACCOUNT_NOT_SUBSCRIBER))),
+			pallet_spaces::Error::<Test>::NotASpaceOwner
 		);
 	});
 }
+
+// Subscription plans provider
+// ----------------------------------------------------------------------------
+
+#[test]
+fn is_active_plan_in_space_should_work() {
+	ExtBuilder::build_with_plan().execute_with(|| {
+		assert!(Subscriptions::is_active_plan_in_space(PLAN1, SPACE1));
+		assert!(!Subscriptions::is_active_plan_in_space(PLAN1, SPACE1 + 1));
+
+		assert_ok!(_delete_default_plan());
+		assert!(!Subscriptions::is_active_plan_in_space(PLAN1, SPACE1));
+	});
+}
+
+#[test]
+fn is_active_subscriber_should_work() {
+	ExtBuilder::build_with_subscription().execute_with(|| {
+		assert!(Subscriptions::is_active_subscriber(&ACCOUNT_SUBSCRIBER, PLAN1));
+		assert!(!Subscriptions::is_active_subscriber(&ACCOUNT_NOT_SUBSCRIBER, PLAN1));
+
+		assert_ok!(_unsubscribe(None));
+		assert!(!Subscriptions::is_active_subscriber(&ACCOUNT_SUBSCRIBER, PLAN1));
+	});
+}
+
+#[test]
+fn is_active_subscriber_should_be_false_while_payment_is_overdue() {
+	ExtBuilder::build_with_subscription().execute_with(|| {
+		let _ = Balances::make_free_balance_be(&ACCOUNT_SUBSCRIBER, PLAN_PRICE - 1);
+		run_to_block(1 + CUSTOM_PERIOD);
+
+		assert!(Subscriptions::subscription_by_id(SUBSCRIPTION1).unwrap().is_active);
+		assert!(!Subscriptions::is_active_subscriber(&ACCOUNT_SUBSCRIBER, PLAN1));
+	});
+}
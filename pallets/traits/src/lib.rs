@@ -10,6 +10,7 @@ use pallet_permissions::{
 use pallet_utils::{SpaceId, User};
 
 pub mod moderation;
+pub mod subscriptions;
 
 /// Minimal set of fields from Space struct that are required by roles pallet.
 pub struct SpaceForRoles<AccountId> {
@@ -29,6 +30,25 @@ pub trait SpaceFollowsProvider {
   fn is_space_follower(account: Self::AccountId, space_id: SpaceId) -> bool;
 }
 
+/// Removes the data that a pallet keeps about a space after this space was deleted.
+pub trait SpaceCleaner {
+  /// Remove at most `limit` items related to a deleted space.
+  /// Returns how many items were removed. If it's less than `limit`, there is nothing left to remove.
+  fn clean_up_space(space_id: SpaceId, limit: u32) -> u32;
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(10)]
+impl SpaceCleaner for Tuple {
+  fn clean_up_space(space_id: SpaceId, limit: u32) -> u32 {
+    let mut removed = 0u32;
+    // Every next cleaner gets only what is left from the limit.
+    for_tuples!( #(
+      removed = removed.saturating_add(Tuple::clean_up_space(space_id, limit.saturating_sub(removed)));
+    )* );
+    removed
+  }
+}
+
 pub trait PermissionChecker {
   type AccountId;
 
@@ -0,0 +1,21 @@
+use pallet_utils::SpaceId;
+
+pub type SubscriptionPlanId = u64;
+
+pub trait SubscriptionPlansProvider<AccountId> {
+    /// Whether there is an active subscription plan with this id in the space.
+    fn is_active_plan_in_space(plan_id: SubscriptionPlanId, space_id: SpaceId) -> bool;
+
+    /// Whether the account has an active subscription to the plan.
+    fn is_active_subscriber(account: &AccountId, plan_id: SubscriptionPlanId) -> bool;
+}
+
+impl<AccountId> SubscriptionPlansProvider<AccountId> for () {
+    fn is_active_plan_in_space(_plan_id: SubscriptionPlanId, _space_id: SpaceId) -> bool {
+        false
+    }
+
+    fn is_active_subscriber(_account: &AccountId, _plan_id: SubscriptionPlanId) -> bool {
+        false
+    }
+}
@@ -0,0 +1,10 @@
+use jsonrpc_core::{Error, ErrorCode};
+
+/// Map a runtime API error to a JSON RPC error.
+pub fn map_rpc_error(err: impl std::fmt::Debug) -> Error {
+    Error {
+        code: ErrorCode::ServerError(1),
+        message: "An RPC error occurred".into(),
+        data: Some(format!("{:?}", err).into()),
+    }
+}
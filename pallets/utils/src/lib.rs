@@ -1,6 +1,8 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use codec::{Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
 use frame_support::{
     decl_error, decl_module, decl_storage, decl_event,
     dispatch::{DispatchError, DispatchResult}, ensure,
@@ -19,6 +21,7 @@ use frame_system::{self as system};
 #[cfg(test)]
 mod mock;
 pub mod mock_functions;
+pub mod rpc;
 
 #[cfg(test)]
 mod tests;
@@ -43,6 +46,8 @@ impl<T: Trait> WhoAndWhen<T> {
 }
 
 #[derive(Encode, Decode, Ord, PartialOrd, Clone, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
 pub enum User<AccountId> {
     Account(AccountId),
     Space(SpaceId),
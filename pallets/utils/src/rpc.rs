@@ -0,0 +1,106 @@
+use codec::{Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize, Serializer, Deserializer};
+use sp_runtime::{RuntimeDebug, SaturatedConversion};
+use sp_std::prelude::*;
+
+use crate::{Content, Trait, WhoAndWhen};
+
+/// Serialize a vector of bytes as a UTF-8 string, so it's readable in JSON.
+#[cfg(feature = "std")]
+pub fn serialize_as_string<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&String::from_utf8_lossy(bytes))
+}
+
+/// Deserialize a UTF-8 string into a vector of bytes.
+#[cfg(feature = "std")]
+pub fn deserialize_from_string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    let s = String::deserialize(deserializer)?;
+    Ok(s.into_bytes())
+}
+
+/// Serialize an optional vector of bytes as an optional UTF-8 string.
+#[cfg(feature = "std")]
+pub fn serialize_option_as_string<S: Serializer>(bytes: &Option<Vec<u8>>, serializer: S) -> Result<S::Ok, S::Error> {
+    match bytes {
+        Some(bytes) => serializer.serialize_some(&String::from_utf8_lossy(bytes)),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Deserialize an optional UTF-8 string into an optional vector of bytes.
+#[cfg(feature = "std")]
+pub fn deserialize_option_from_string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Vec<u8>>, D::Error> {
+    let s = Option::<String>::deserialize(deserializer)?;
+    Ok(s.map(|s| s.into_bytes()))
+}
+
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct FlatWhoAndWhen<AccountId, BlockNumber> {
+    pub created_by: AccountId,
+    pub created_at_block: BlockNumber,
+    pub created_at_time: u64,
+
+    #[cfg_attr(feature = "std", serde(skip_serializing_if = "Option::is_none"))]
+    pub updated_by: Option<AccountId>,
+    #[cfg_attr(feature = "std", serde(skip_serializing_if = "Option::is_none"))]
+    pub updated_at_block: Option<BlockNumber>,
+    #[cfg_attr(feature = "std", serde(skip_serializing_if = "Option::is_none"))]
+    pub updated_at_time: Option<u64>,
+}
+
+impl<T: Trait> From<(WhoAndWhen<T>, Option<WhoAndWhen<T>>)> for FlatWhoAndWhen<T::AccountId, T::BlockNumber> {
+    fn from(created_and_updated: (WhoAndWhen<T>, Option<WhoAndWhen<T>>)) -> Self {
+        let (created, updated) = created_and_updated;
+        Self {
+            created_by: created.account,
+            created_at_block: created.block,
+            created_at_time: created.time.saturated_into::<u64>(),
+
+            updated_by: updated.clone().map(|v| v.account),
+            updated_at_block: updated.clone().map(|v| v.block),
+            updated_at_time: updated.map(|v| v.time.saturated_into::<u64>()),
+        }
+    }
+}
+
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(tag = "contentType", content = "contentId"))]
+pub enum FlatContent {
+    None,
+    Raw(
+        #[cfg_attr(feature = "std", serde(
+            serialize_with = "serialize_as_string",
+            deserialize_with = "deserialize_from_string"
+        ))]
+        Vec<u8>
+    ),
+    IPFS(
+        #[cfg_attr(feature = "std", serde(
+            serialize_with = "serialize_as_string",
+            deserialize_with = "deserialize_from_string"
+        ))]
+        Vec<u8>
+    ),
+    Hyper(
+        #[cfg_attr(feature = "std", serde(
+            serialize_with = "serialize_as_string",
+            deserialize_with = "deserialize_from_string"
+        ))]
+        Vec<u8>
+    ),
+}
+
+impl From<Content> for FlatContent {
+    fn from(content: Content) -> Self {
+        match content {
+            Content::None => Self::None,
+            Content::Raw(raw) => Self::Raw(raw),
+            Content::IPFS(cid) => Self::IPFS(cid),
+            Content::Hyper(id) => Self::Hyper(id),
+        }
+    }
+}
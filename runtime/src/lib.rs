@@ -11,8 +11,8 @@ include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 use sp_std::prelude::*;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
-    ApplyExtrinsicResult, generic, create_runtime_str, impl_opaque_keys, MultiSignature,
-    transaction_validity::{TransactionValidity, TransactionSource},
+    ApplyExtrinsicResult, generic, create_runtime_str, impl_opaque_keys, MultiSignature, ModuleId,
+    transaction_validity::{TransactionLongevity, TransactionPriority, TransactionSource, TransactionValidity},
 };
 use sp_runtime::traits::{
     BlakeTwo256, Block as BlockT, Verify, IdentifyAccount, NumberFor, Saturating,
@@ -41,6 +41,12 @@ pub use frame_support::{
 };
 use frame_system::EnsureRoot;
 
+use pallet_donations::{DonationRecipient, rpc::{FlatSuperComment, FlatSupporter}};
+use pallet_posts::{PostId, rpc::{FlatPost, FlatReplyTree}};
+use pallet_roles::rpc::FlatSpacePermissions;
+use pallet_spaces::rpc::FlatSpace;
+use pallet_utils::{SpaceId, User};
+
 pub mod constants;
 use constants::{currency::*, time::*};
 
@@ -102,10 +108,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_name: create_runtime_str!("subsocial"),
 	impl_name: create_runtime_str!("dappforce-subsocial"),
 	authoring_version: 0,
-	spec_version: 10,
+	spec_version: 11,
 	impl_version: 0,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 2,
+	transaction_version: 3,
 };
 
 /// The version information used to identify this runtime when compiled natively.
@@ -322,7 +328,9 @@ impl pallet_posts::Trait for Runtime {
 	type MaxCommentDepth = MaxCommentDepth;
 	type PostScores = Scores;
 	type AfterPostUpdated = PostHistory;
-	type IsPostBlocked = ()/*Moderation*/;
+	type IsPostBlocked = Moderation;
+	type SubscriptionPlans = Subscriptions;
+	type PostCleaner = Reactions;
 }
 
 parameter_types! {}
@@ -364,8 +372,8 @@ impl pallet_roles::Trait for Runtime {
 	type MaxUsersToProcessPerDeleteRole = MaxUsersToProcessPerDeleteRole;
 	type Spaces = Spaces;
 	type SpaceFollows = SpaceFollows;
-	type IsAccountBlocked = ()/*Moderation*/;
-	type IsContentBlocked = ()/*Moderation*/;
+	type IsAccountBlocked = Moderation;
+	type IsContentBlocked = Moderation;
 }
 
 parameter_types! {
@@ -408,12 +416,18 @@ impl pallet_space_follows::Trait for Runtime {
 
 parameter_types! {}
 
+parameter_types! {
+	pub const PendingOwnershipTransferLifetime: BlockNumber = DAYS * 7;
+}
+
 impl pallet_space_ownership::Trait for Runtime {
 	type Event = Event;
+	type PendingTransferLifetime = PendingOwnershipTransferLifetime;
 }
 
 parameter_types! {
 	pub HandleDeposit: Balance = 50 * CENTS;
+	pub const MaxItemsToCleanUpPerBlock: u16 = 100;
 }
 
 impl pallet_spaces::Trait for Runtime {
@@ -423,9 +437,14 @@ impl pallet_spaces::Trait for Runtime {
 	type SpaceFollows = SpaceFollows;
 	type BeforeSpaceCreated = SpaceFollows;
 	type AfterSpaceUpdated = SpaceHistory;
-	type IsAccountBlocked = ()/*Moderation*/;
-	type IsContentBlocked = ()/*Moderation*/;
+	type AfterSpaceOwnerChanged = SpaceOwnership;
+	type IsAccountBlocked = Moderation;
+	type IsContentBlocked = Moderation;
 	type HandleDeposit = HandleDeposit;
+	type SpaceCleaner = (
+		Posts, Roles, SpaceFollows, SpaceOwnership, SpaceMultiOwnership, Moderation, Subscriptions, Donations,
+	);
+	type MaxItemsToCleanUpPerBlock = MaxItemsToCleanUpPerBlock;
 }
 
 parameter_types! {}
@@ -444,7 +463,6 @@ impl Filter<Call> for BaseFilter {
     }
 }
 
-/*
 parameter_types! {
 	pub const MaxSessionKeysPerAccount: u16 = 10;
 	pub const BaseSessionKeyBond: Balance = 1 * DOLLARS;
@@ -464,25 +482,64 @@ impl Filter<Call> for SessionKeysProxyFilter {
 	}
 }
 
+pub struct SessionKeysCallSpaceResolver;
+impl pallet_session_keys::CallSpaceResolver<Call> for SessionKeysCallSpaceResolver {
+	fn call_space(c: &Call) -> Option<SpaceId> {
+		let space_of_post = |post_id: &PostId| Posts::post_by_id(post_id)
+			.and_then(|post| post.try_get_space_id());
+
+		match c {
+			Call::SpaceFollows(pallet_space_follows::Call::follow_space(space_id)) => Some(*space_id),
+			Call::SpaceFollows(pallet_space_follows::Call::unfollow_space(space_id)) => Some(*space_id),
+			Call::Posts(pallet_posts::Call::create_post(space_id_opt, extension, _)) => match extension {
+				pallet_posts::PostExtension::Comment(comment) => space_of_post(&comment.root_post_id),
+				_ => *space_id_opt,
+			},
+			Call::Posts(pallet_posts::Call::update_post(post_id, _)) => space_of_post(post_id),
+			Call::Reactions(pallet_reactions::Call::create_post_reaction(post_id, _)) => space_of_post(post_id),
+			Call::Reactions(pallet_reactions::Call::update_post_reaction(post_id, _, _)) => space_of_post(post_id),
+			Call::Reactions(pallet_reactions::Call::delete_post_reaction(post_id, _)) => space_of_post(post_id),
+			_ => None,
+		}
+	}
+}
+
 impl pallet_session_keys::Trait for Runtime {
 	type Event = Event;
 	type Call = Call;
 	type MaxSessionKeysPerAccount = MaxSessionKeysPerAccount;
 	type BaseFilter = SessionKeysProxyFilter;
 	type BaseSessionKeyBond = BaseSessionKeyBond;
+	type CallSpaceResolver = SessionKeysCallSpaceResolver;
 }
 
 impl pallet_donations::Trait for Runtime {
 	type Event = Event;
 }
 
+parameter_types! {
+	pub const FaucetClaimPriority: TransactionPriority = TransactionPriority::max_value() / 2;
+	pub const FaucetClaimLongevity: TransactionLongevity = 5 * MINUTES as TransactionLongevity;
+	pub const MaxFaucetsToRefillPerBlock: u16 = 20;
+}
+
+impl pallet_faucets::Trait for Runtime {
+	type Event = Event;
+	type ClaimPriority = FaucetClaimPriority;
+	type ClaimLongevity = FaucetClaimLongevity;
+	type MaxFaucetsToRefillPerBlock = MaxFaucetsToRefillPerBlock;
+}
+
 parameter_types! {
 	pub const DefaultAutoblockThreshold: u16 = 20;
+	pub const ModerationAppealPeriod: BlockNumber = DAYS * 3;
 }
 
 impl pallet_moderation::Trait for Runtime {
 	type Event = Event;
 	type DefaultAutoblockThreshold = DefaultAutoblockThreshold;
+	type GlobalModeratorOrigin = EnsureRoot<AccountId>;
+	type AppealPeriod = ModerationAppealPeriod;
 }
 
 parameter_types! {
@@ -491,23 +548,46 @@ parameter_types! {
 	pub const MonthlyPeriodInBlocks: BlockNumber = DAYS * 30;
 	pub const QuarterlyPeriodInBlocks: BlockNumber = DAYS * 30 * 3;
 	pub const YearlyPeriodInBlocks: BlockNumber = DAYS * 365;
+	pub const MinCustomPeriodInBlocks: BlockNumber = HOURS;
+	pub const MaxCustomPeriodInBlocks: BlockNumber = DAYS * 365;
+	pub const PaymentRetryIntervalInBlocks: BlockNumber = DAYS;
+	pub const MaxPaymentRetries: u16 = 3;
 }
 
 impl pallet_subscriptions::Trait for Runtime {
 	type Event = Event;
 	type Subscription = Call;
+	type PalletsOrigin = OriginCaller;
 	type Scheduler = Scheduler;
 	type DailyPeriodInBlocks = DailyPeriodInBlocks;
 	type WeeklyPeriodInBlocks = WeeklyPeriodInBlocks;
 	type MonthlyPeriodInBlocks = MonthlyPeriodInBlocks;
 	type QuarterlyPeriodInBlocks = QuarterlyPeriodInBlocks;
 	type YearlyPeriodInBlocks = YearlyPeriodInBlocks;
+	type MinCustomPeriodInBlocks = MinCustomPeriodInBlocks;
+	type MaxCustomPeriodInBlocks = MaxCustomPeriodInBlocks;
+	type PaymentRetryIntervalInBlocks = PaymentRetryIntervalInBlocks;
+	type MaxPaymentRetries = MaxPaymentRetries;
 }
-*/
 
-impl pallet_faucets::Trait for Runtime {
+parameter_types! {
+	pub const MinSpaceOwners: u16 = 1;
+	pub const MaxSpaceOwners: u16 = 1000;
+	pub const MaxChangeNotesLength: u16 = 1024;
+	pub const BlocksToLive: BlockNumber = DAYS * 7;
+	pub const DeleteExpiredChangesPeriod: BlockNumber = HOURS;
+	pub const SpaceMultiOwnershipModuleId: ModuleId = ModuleId(*b"df/spcmo");
+}
+
+impl pallet_space_multi_ownership::Trait for Runtime {
 	type Event = Event;
-	type Currency = Balances;
+	type Call = Call;
+	type ModuleId = SpaceMultiOwnershipModuleId;
+	type MinSpaceOwners = MinSpaceOwners;
+	type MaxSpaceOwners = MaxSpaceOwners;
+	type MaxChangeNotesLength = MaxChangeNotesLength;
+	type BlocksToLive = BlocksToLive;
+	type DeleteExpiredChangesPeriod = DeleteExpiredChangesPeriod;
 }
 
 construct_runtime!(
@@ -546,11 +626,12 @@ construct_runtime!(
 
 		// New experimental pallets. Not recommended to use in production yet.
 
-		Faucets: pallet_faucets::{Module, Call, Storage, Event<T>},
-		// SessionKeys: pallet_session_keys::{Module, Call, Storage, Event<T>},
-		// Moderation: pallet_moderation::{Module, Call, Storage, Event<T>},
-		// Donations: pallet_donations::{Module, Call, Storage, Event<T>},
-		// Subscriptions: pallet_subscriptions::{Module, Call, Storage, Event<T>},
+		Faucets: pallet_faucets::{Module, Call, Storage, Event<T>, ValidateUnsigned},
+		SessionKeys: pallet_session_keys::{Module, Call, Storage, Event<T>},
+		Moderation: pallet_moderation::{Module, Call, Storage, Event<T>},
+		Donations: pallet_donations::{Module, Call, Storage, Event<T>},
+		Subscriptions: pallet_subscriptions::{Module, Call, Storage, Event<T>},
+		SpaceMultiOwnership: pallet_space_multi_ownership::{Module, Call, Storage, Event<T>},
 	}
 );
 
@@ -714,6 +795,65 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl posts_runtime_api::PostsApi<Block, AccountId, BlockNumber> for Runtime {
+		fn get_posts_by_space(
+			space_id: SpaceId,
+			offset: u64,
+			limit: u64,
+			include_hidden: bool,
+		) -> Vec<FlatPost<AccountId, BlockNumber>> {
+			Posts::get_posts_by_space(space_id, offset, limit, include_hidden)
+		}
+
+		fn get_reply_tree(root_post_id: PostId, max_depth: u32) -> Option<FlatReplyTree<AccountId, BlockNumber>> {
+			Posts::get_reply_tree(root_post_id, max_depth)
+		}
+
+		fn get_shared_posts(original_post_id: PostId) -> Vec<FlatPost<AccountId, BlockNumber>> {
+			Posts::get_shared_posts(original_post_id)
+		}
+	}
+
+	impl donations_runtime_api::DonationsApi<Block, AccountId, Balance> for Runtime {
+		fn get_top_supporters(
+			recipient: DonationRecipient<AccountId>,
+			limit: u64,
+		) -> Vec<FlatSupporter<AccountId, Balance>> {
+			Donations::get_top_supporters(recipient, limit)
+		}
+
+		fn get_super_comments(post_id: PostId) -> Vec<FlatSuperComment<AccountId, Balance>> {
+			Donations::get_super_comments(post_id)
+		}
+	}
+
+	impl roles_runtime_api::RolesApi<Block, AccountId> for Runtime {
+		fn get_space_permissions_by_user(
+			user: User<AccountId>,
+			space_id: SpaceId,
+		) -> Option<FlatSpacePermissions> {
+			Roles::get_space_permissions_by_user(user, space_id)
+		}
+	}
+
+	impl spaces_runtime_api::SpacesApi<Block, AccountId, BlockNumber> for Runtime {
+		fn get_spaces(start_id: u64, limit: u64) -> Vec<FlatSpace<AccountId, BlockNumber>> {
+			Spaces::get_spaces(start_id, limit)
+		}
+
+		fn get_spaces_by_ids(space_ids: Vec<SpaceId>) -> Vec<FlatSpace<AccountId, BlockNumber>> {
+			Spaces::get_spaces_by_ids(space_ids)
+		}
+
+		fn get_space_by_handle(handle: Vec<u8>) -> Option<FlatSpace<AccountId, BlockNumber>> {
+			Spaces::get_space_by_handle(handle)
+		}
+
+		fn get_space_ids_by_owner(owner: AccountId) -> Vec<SpaceId> {
+			Spaces::get_space_ids_by_owner(owner)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn dispatch_benchmark(